@@ -0,0 +1,229 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use bresenham::Bresenham;
+use core::f32::consts::PI;
+use core::str::FromStr;
+
+/// Where a dial lies on a grid, in cells.
+///
+/// Angles are in degrees, where 0 is north and they grow clockwise, the same way the hands of a
+/// clock move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dial {
+    pub midpoint_x: f32,
+    pub midpoint_y: f32,
+    pub radius: f32,
+    /// Clockwise rotation of everything drawn on the dial.
+    pub rotation: f32,
+    pub shape: Shape,
+}
+
+/// The outline of a dial. Polygons have flat sides at 12, 3, 6 and 9 o'clock, and they are as
+/// far from the center as the circle there, so that every shape fits in the same square.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    Circle,
+    Square,
+    Octagon,
+}
+
+impl Shape {
+    /// How many sides the outline has, unless it is a circle.
+    fn sides(self) -> Option<usize> {
+        match self {
+            Shape::Circle => None,
+            Shape::Square => Some(4),
+            Shape::Octagon => Some(8),
+        }
+    }
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Shape, String> {
+        match text {
+            "circle" => Ok(Shape::Circle),
+            "square" => Ok(Shape::Square),
+            "octagon" => Ok(Shape::Octagon),
+            _ => Err(format!(
+                "Invalid dial shape '{}', expected one of circle, square or octagon",
+                text
+            )),
+        }
+    }
+}
+
+/// Wrap `degree` into `0..360`.
+pub fn normalize_degree(degree: f32) -> f32 {
+    let degree = libm::fmodf(degree, 360.0);
+    if degree < 0.0 {
+        degree + 360.0
+    } else {
+        degree
+    }
+}
+
+impl Dial {
+    /// The same dial on a grid that is `factor` times as large in each direction.
+    pub fn scale(self, factor: f32) -> Dial {
+        Dial {
+            midpoint_x: self.midpoint_x * factor,
+            midpoint_y: self.midpoint_y * factor,
+            radius: self.radius * factor,
+            ..self
+        }
+    }
+
+    /// Distance of the cell at (x, y) from the center, in cells.
+    pub fn distance(&self, x: f32, y: f32) -> f32 {
+        let dx = x - self.midpoint_x;
+        let dy = y - self.midpoint_y;
+        libm::sqrtf(dx * dx + dy * dy)
+    }
+
+    /// The angle of the cell at (x, y) on the dial, with the rotation undone.
+    pub fn angle(&self, x: f32, y: f32) -> f32 {
+        let dx = x - self.midpoint_x;
+        let dy = y - self.midpoint_y;
+        // Grid rows grow downwards, so north is negative y
+        normalize_degree(libm::atan2f(dx, -dy).to_degrees() - self.rotation)
+    }
+
+    /// How far the outline is from the center towards `degree`, relative to the radius.
+    pub fn extent(&self, degree: f32) -> f32 {
+        let sides = match self.shape.sides() {
+            Some(sides) => sides,
+            None => return 1.0,
+        };
+        let sector = 360.0 / sides as f32;
+        // The angle from the middle of the nearest side
+        let from_side = libm::fmodf(normalize_degree(degree + sector / 2.0), sector) - sector / 2.0;
+        1.0 / libm::cosf(from_side.to_radians())
+    }
+
+    /// Whether the cell at (x, y) lies within the outline.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.distance(x, y) <= self.radius * self.extent(self.angle(x, y))
+    }
+
+    /// Whether the cell at (x, y) lies within the dial and clockwise from `from_degree` to
+    /// `to_degree`. A sector from an angle to the same angle is empty.
+    pub fn sector_contains(&self, x: f32, y: f32, from_degree: f32, to_degree: f32) -> bool {
+        let span = normalize_degree(to_degree - from_degree);
+        let span = if span == 0.0 && to_degree != from_degree {
+            360.0
+        } else {
+            span
+        };
+        self.contains(x, y) && normalize_degree(self.angle(x, y) - from_degree) < span
+    }
+
+    /// Cells on the outline, which are the cells of `circle` for a circle, or lines between the
+    /// corners of a polygon.
+    ///
+    /// `height` is the height of the grid, as corners are found with `point`, so that labels
+    /// drawn with `line` meet the outline.
+    pub fn outline(&self, height: usize) -> Vec<(isize, isize)> {
+        let sides = match self.shape.sides() {
+            Some(sides) => sides,
+            None => return self.circle(),
+        };
+        let sector = 360.0 / sides as f32;
+        let corner = |index: usize| {
+            let degree = sector * index as f32 + sector / 2.0;
+            self.point(height, degree, self.extent(degree), (0.0, 0.0))
+        };
+        (0..sides)
+            .flat_map(|index| Bresenham::new(corner(index), corner(index + 1)))
+            .collect()
+    }
+
+    /// Cells on the circumference, drawn with the midpoint circle algorithm, see
+    /// [A Rasterizing Algorithm for Drawing Curves](http://members.chello.at/easyfilter/bresenham.pdf).
+    pub fn circle(&self) -> Vec<(isize, isize)> {
+        let center_x = self.midpoint_x as isize;
+        let center_y = self.midpoint_y as isize;
+        let radius = self.radius as isize;
+        let mut x = -radius;
+        let mut y = 0;
+        let mut error = 2 - 2 * radius;
+        let mut points = Vec::new();
+        while x < 0 {
+            points.push((center_x - x, center_y + y));
+            points.push((center_x - y, center_y - x));
+            points.push((center_x + x, center_y - y));
+            points.push((center_x + y, center_y + x));
+            let radius = error;
+            if radius <= y {
+                y += 1;
+                error += y * 2 + 1;
+            }
+            if radius > x || error > y {
+                x += 1;
+                error += x * 2 + 1;
+            }
+        }
+        points
+    }
+
+    /// Cells on the line pointing to `degree`, from `start` to `end` away from the center,
+    /// relative to the radius, where a negative `start` lies behind the center.
+    /// The line starts at the center moved by `offset`, which is how thick hands are drawn.
+    ///
+    /// `height` is the height of the grid, as lines are computed on the Cartesian plane.
+    /// The cell at `distance` relative to the radius from the center, in the direction of
+    /// `degree`, rounded the same way as the ends of `line`.
+    pub fn point(
+        &self,
+        height: usize,
+        degree: f32,
+        distance: f32,
+        offset: (f32, f32),
+    ) -> (isize, isize) {
+        let (sin, cos) = libm::sincosf(PI / 2.0 - (degree + self.rotation).to_radians());
+        let hypotenuse = self.radius * distance;
+        let y = height as f32 - self.midpoint_y + offset.1 + hypotenuse * sin;
+        (
+            (self.midpoint_x + offset.0 + hypotenuse * cos) as isize,
+            height as isize - y as isize,
+        )
+    }
+
+    pub fn line(
+        &self,
+        height: usize,
+        degree: f32,
+        start: f32,
+        end: f32,
+        offset: (f32, f32),
+    ) -> Vec<(isize, isize)> {
+        let radian = PI / 2.0 - (degree + self.rotation).to_radians();
+        let (sin, cos) = libm::sincosf(radian);
+        let x = self.midpoint_x + offset.0;
+        // Convert to Cartesian plane, see the comment on inverting y below
+        let y = height as f32 - self.midpoint_y + offset.1;
+
+        // We treat radius as the hypotenuse
+        // Trigonometry hints:
+        // Adjacent = Hypotenuse * cos theta
+        // Opposite = Hypotenuse * sin theta
+        let get_point = |distance: f32| -> (isize, isize) {
+            let hypotenuse = self.radius * distance;
+            (
+                (x + hypotenuse * cos) as isize,
+                (y + hypotenuse * sin) as isize,
+            )
+        };
+
+        // We will be using [Bresenham Line Algorithm](https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm#History).
+        Bresenham::new(get_point(start), get_point(end))
+            // We have to invert y because the result returned by Bresenham is based on Cartesian plane
+            // where (0, 0) is at the bottom left corner.
+            // However for our grid, (0, 0) is at the top left corner, which is like the Cartesian
+            // plane flip around the x-axis.
+            .map(|(x, y)| (x, height as isize - y))
+            .collect()
+    }
+}
@@ -0,0 +1,265 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Signature;
+
+/// A grid of cells, where empty cells are `None`.
+///
+/// Cells are stored row by row in a single buffer, so that going through every cell, as
+/// drawing and diffing do, walks memory in order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<Option<T>>,
+    width: usize,
+    height: usize,
+}
+
+/// A rectangle of cells, where (x, y) is its top left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// A cell that differs between two grids.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change<T> {
+    pub x: usize,
+    pub y: usize,
+    pub cell: Option<T>,
+}
+
+/// Grids of different sizes cannot be diffed, such as frames drawn before and after the
+/// terminal was resized.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SizeMismatch {
+    /// Width and height of the old grid.
+    pub old: (usize, usize),
+    /// Width and height of the new grid.
+    pub new: (usize, usize),
+}
+
+impl fmt::Display for SizeMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Unable to diff a {}x{} grid against a {}x{} grid",
+            self.old.0, self.old.1, self.new.0, self.new.1
+        )
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize) -> Grid<T> {
+        Grid {
+            cells: vec![None; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Empty every cell and change the size of this grid to `width` x `height`, which reuses
+    /// its buffer instead of allocating another, unless the grid grows.
+    pub fn reset(&mut self, width: usize, height: usize) {
+        self.cells.clear();
+        self.cells.resize(width * height, None);
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every row, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[Option<T>]> {
+        (0..self.height).map(move |y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Coordinates of every cell, row by row.
+    pub fn points(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        if self.contains(x, y) {
+            self.cells[y as usize * self.width + x as usize].as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Set the cell at (x, y), unless it lies outside of this grid.
+    pub fn set(&mut self, x: isize, y: isize, cell: T) {
+        if self.contains(x, y) {
+            self.cells[y as usize * self.width + x as usize] = Some(cell)
+        }
+    }
+
+    fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// Every non-empty cell.
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut().flatten()
+    }
+
+    /// Copy every non-empty cell of `other` onto this grid, with the top left corner of `other`
+    /// at (x, y).
+    pub fn paste(&mut self, other: &Grid<T>, x: isize, y: isize) {
+        for (row_index, row) in other.rows().enumerate() {
+            for (column_index, cell) in row.iter().enumerate() {
+                if let Some(cell) = cell {
+                    self.set(
+                        x + column_index as isize,
+                        y + row_index as isize,
+                        cell.clone(),
+                    )
+                }
+            }
+        }
+    }
+
+    /// A stable hash of the size and the content of this grid, where `write_cell` writes the
+    /// content of each non-empty cell.
+    pub fn signature(&self, write_cell: impl Fn(&T, &mut Signature)) -> u64 {
+        let mut signature = Signature::new();
+        signature.write(&(self.width as u64).to_le_bytes());
+        signature.write(&(self.height as u64).to_le_bytes());
+        for cell in &self.cells {
+            match cell {
+                Some(cell) => {
+                    signature.write(&[1]);
+                    write_cell(cell, &mut signature)
+                }
+                None => signature.write(&[0]),
+            }
+        }
+        signature.finish()
+    }
+
+    /// Stretch or squeeze this grid horizontally to `width`.
+    ///
+    /// Nearest-neighbor sampling is used, so that no cell is blended with another.
+    pub fn rescale_width(&self, width: usize) -> Grid<T> {
+        let mut grid = Grid::new(0, 0);
+        self.rescale_width_into(width, &mut grid);
+        grid
+    }
+
+    /// Like `rescale_width`, but the result is written to `target`, whose buffer is reused.
+    pub fn rescale_width_into(&self, width: usize, target: &mut Grid<T>) {
+        target.reset(0, 0);
+        target.width = width;
+        target.height = self.height;
+        if self.width == 0 {
+            target.cells.resize(width * self.height, None);
+            return;
+        }
+        let ratio = self.width as f32 / width as f32;
+        let last_column = self.width - 1;
+        for row in self.rows() {
+            target.cells.extend(
+                (0..width)
+                    .map(|x| row[(((x as f32 + 0.5) * ratio) as usize).min(last_column)].clone()),
+            )
+        }
+    }
+
+    /// Shrink this grid by `factor` in both directions, rounding down, where each cell is made
+    /// by `merge` from the cells of a `factor` x `factor` block that are not empty, given with
+    /// how many cells the block has.
+    pub fn downsample(&self, factor: usize, merge: impl Fn(&[&T], usize) -> Option<T>) -> Grid<T> {
+        let factor = factor.max(1);
+        let (width, height) = (self.width / factor, self.height / factor);
+        let mut block = Vec::with_capacity(factor * factor);
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                block.clear();
+                for row in y * factor..(y + 1) * factor {
+                    let start = row * self.width + x * factor;
+                    block.extend(self.cells[start..start + factor].iter().flatten())
+                }
+                cells.push(if block.is_empty() {
+                    None
+                } else {
+                    merge(&block, factor * factor)
+                })
+            }
+        }
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// The cells of `new` that differ from this grid, which fails if the grids are not of the
+    /// same size.
+    pub fn diff(&self, new: &Grid<T>) -> Result<Vec<Change<T>>, SizeMismatch> {
+        let old = self;
+        old.check_same_size(new)?;
+        Ok(old
+            .cells
+            .iter()
+            .zip(&new.cells)
+            .enumerate()
+            .filter(|(_, (old_cell, new_cell))| old_cell != new_cell)
+            .map(|(index, (_, new_cell))| Change {
+                x: index % old.width,
+                y: index / old.width,
+                cell: new_cell.clone(),
+            })
+            .collect())
+    }
+
+    /// Like `diff`, but only the cells within `regions` are compared, which is faster when
+    /// nothing is ever drawn outside of them.
+    pub fn diff_regions(
+        &self,
+        new: &Grid<T>,
+        regions: &[Region],
+    ) -> Result<Vec<Change<T>>, SizeMismatch> {
+        self.check_same_size(new)?;
+        let mut changes = Vec::new();
+        for region in regions {
+            for y in region.y..(region.y + region.height).min(self.height) {
+                let row = y * self.width;
+                for x in region.x..(region.x + region.width).min(self.width) {
+                    let new_cell = &new.cells[row + x];
+                    if self.cells[row + x] != *new_cell {
+                        changes.push(Change {
+                            x,
+                            y,
+                            cell: new_cell.clone(),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    fn check_same_size(&self, new: &Grid<T>) -> Result<(), SizeMismatch> {
+        if (self.width, self.height) == (new.width, new.height) {
+            Ok(())
+        } else {
+            Err(SizeMismatch {
+                old: (self.width, self.height),
+                new: (new.width, new.height),
+            })
+        }
+    }
+}
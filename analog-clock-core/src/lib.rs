@@ -0,0 +1,21 @@
+//! Geometry and rasterization of analog clock dials, shared by the terminal clock and anything
+//! else that lights up a grid of cells, such as an LED panel driven by a microcontroller, or a
+//! web page through WebAssembly, see `analog-clock-ffi`.
+//!
+//! This crate does not use the standard library, but it needs an allocator.
+#![no_std]
+
+extern crate alloc;
+
+mod dial;
+mod grid;
+mod render;
+mod signature;
+
+pub use dial::{normalize_degree, Dial, Shape};
+pub use grid::{Change, Grid, Region, SizeMismatch};
+pub use render::{
+    render_clock, render_rgba, render_signature, Layer, BOLD_OFFSETS, DEFAULT_PALETTE,
+    HOUR_HAND_LENGTH, MINUTE_HAND_LENGTH, SECOND_HAND_LENGTH,
+};
+pub use signature::Signature;
@@ -0,0 +1,123 @@
+use crate::{Dial, Grid, Shape};
+
+use alloc::vec::Vec;
+
+/// What a cell of a rendered clock shows.
+///
+/// The values are part of the C interface of `analog-clock-ffi`, so they must never change.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Empty = 0,
+    Face = 1,
+    HourHand = 2,
+    MinuteHand = 3,
+    SecondHand = 4,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 5] = [
+        Layer::Empty,
+        Layer::Face,
+        Layer::HourHand,
+        Layer::MinuteHand,
+        Layer::SecondHand,
+    ];
+}
+
+/// A stable hash of a grid returned by `render_clock`.
+pub fn render_signature(grid: &Grid<Layer>) -> u64 {
+    grid.signature(|layer, signature| signature.write(&[*layer as u8]))
+}
+
+/// Colors of the layers in the order of `Layer::ALL` as RGBA, from the nord-frost theme of the
+/// terminal clock, where empty cells are transparent.
+pub const DEFAULT_PALETTE: [[u8; 4]; 5] = [
+    [0x00, 0x00, 0x00, 0x00],
+    [0x8F, 0xBC, 0xBB, 0xFF],
+    [0x5E, 0x81, 0xAC, 0xFF],
+    [0x81, 0xA1, 0xC1, 0xFF],
+    [0x88, 0xC0, 0xD0, 0xFF],
+];
+
+/// Convert a grid returned by `render_clock` into RGBA pixels, one per cell, row by row starting
+/// from the top left corner, where each layer has the color at its index in `palette`.
+pub fn render_rgba(grid: &Grid<Layer>, palette: &[[u8; 4]; 5]) -> Vec<u8> {
+    grid.points()
+        .flat_map(|(x, y)| {
+            let layer = grid
+                .get(x as isize, y as isize)
+                .copied()
+                .unwrap_or(Layer::Empty);
+            palette[layer as usize]
+        })
+        .collect()
+}
+
+/// Lengths of the hands relative to the radius.
+pub const HOUR_HAND_LENGTH: f32 = 0.5;
+pub const MINUTE_HAND_LENGTH: f32 = 0.9;
+pub const SECOND_HAND_LENGTH: f32 = 0.9;
+
+/// Starting points of the lines of a bold hand, relative to the center on the Cartesian plane,
+/// see `Dial::line`.
+pub const BOLD_OFFSETS: [(f32, f32); 9] = [
+    (-1.0, 1.0),  // top left
+    (0.0, 1.0),   // top
+    (1.0, 1.0),   // top right
+    (-1.0, 0.0),  // left
+    (0.0, 0.0),   // center
+    (1.0, 0.0),   // right
+    (-1.0, -1.0), // bottom left
+    (0.0, -1.0),  // bottom
+    (1.0, -1.0),  // bottom right
+];
+
+/// Render a plain clock showing `seconds` since midnight, with the dial as large as the grid
+/// allows. Cells are assumed to be square, as on an LED panel.
+pub fn render_clock(seconds: u32, width: usize, height: usize) -> Grid<Layer> {
+    let midpoint_x = width as f32 / 2.0;
+    let midpoint_y = height as f32 / 2.0;
+    let dial = Dial {
+        midpoint_x,
+        midpoint_y,
+        radius: midpoint_x.min(midpoint_y) / 1.1,
+        rotation: 0.0,
+        shape: Shape::Circle,
+    };
+
+    let second = (seconds % 60) as f32;
+    let minute = (seconds / 60 % 60) as f32 + second / 60.0;
+    let hour = (seconds / 3600 % 12) as f32 + minute / 60.0;
+
+    let mut grid = Grid::new(width, height);
+    for (x, y) in dial.circle() {
+        grid.set(x, y, Layer::Face)
+    }
+    let mut draw_hand = |degree: f32, length: f32, offsets: &[(f32, f32)], layer: Layer| {
+        for offset in offsets {
+            for (x, y) in dial.line(height, degree, 0.0, length, *offset) {
+                grid.set(x, y, layer)
+            }
+        }
+    };
+    draw_hand(
+        minute / 60.0 * 360.0,
+        MINUTE_HAND_LENGTH,
+        &BOLD_OFFSETS,
+        Layer::MinuteHand,
+    );
+    draw_hand(
+        hour / 12.0 * 360.0,
+        HOUR_HAND_LENGTH,
+        &BOLD_OFFSETS,
+        Layer::HourHand,
+    );
+    draw_hand(
+        second / 60.0 * 360.0,
+        SECOND_HAND_LENGTH,
+        &[(0.0, 0.0)],
+        Layer::SecondHand,
+    );
+    grid
+}
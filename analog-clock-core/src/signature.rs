@@ -0,0 +1,31 @@
+/// A 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hash of the content of a frame.
+///
+/// Unlike the hashers of the standard library, the result is specified, so it is the same on
+/// every platform and across versions, as long as the content is written the same way.
+#[derive(Clone, Debug)]
+pub struct Signature(u64);
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Signature {
+    pub fn new() -> Signature {
+        Signature(FNV_OFFSET_BASIS)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(FNV_PRIME)
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for Signature {
+    fn default() -> Signature {
+        Signature::new()
+    }
+}
@@ -0,0 +1,5 @@
+//! Print the C header of the analog_clock library.
+
+fn main() {
+    print!("{}", analog_clock::header())
+}
@@ -0,0 +1,184 @@
+//! C interface to the renderer of `analog-clock-core`, for programs not written in Rust, such as
+//! a status bar written in C or a Python script using ctypes.
+//!
+//! The rendered frame is a buffer of `width * height` bytes, one per cell, row by row starting
+//! from the top left corner. Each byte is one of the `CLOCK_LAYER_*` values of `Layer`.
+//! Run `cargo run -p analog-clock-ffi --bin analog-clock-header > analog_clock.h` to generate
+//! the header.
+//!
+//! The same functions are exported by a WebAssembly module built with
+//! `cargo build -p analog-clock-ffi --release --target wasm32-unknown-unknown`, for example to
+//! draw the clock on a canvas. Buffers in the memory of the module are allocated with
+//! `clock_buffer_new`, and `clock_render_rgba` fills them with pixels for an `ImageData`.
+
+use analog_clock_core::{render_clock, render_rgba, render_signature, Layer, DEFAULT_PALETTE};
+use std::slice;
+
+/// `clock_render` or `clock_render_rgba` succeeded.
+pub const CLOCK_OK: i32 = 0;
+/// `out_buffer` is null, or its size overflows.
+pub const CLOCK_INVALID_ARGUMENT: i32 = -1;
+
+/// Render the clock showing `time`, in seconds since midnight, into `out_buffer`.
+///
+/// Returns `CLOCK_OK`, or `CLOCK_INVALID_ARGUMENT` without touching `out_buffer`.
+///
+/// # Safety
+///
+/// `out_buffer` must be null or point to at least `width * height` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn clock_render(
+    time: u32,
+    width: u32,
+    height: u32,
+    out_buffer: *mut u8,
+) -> i32 {
+    let (width, height) = (width as usize, height as usize);
+    let length = match width.checked_mul(height) {
+        Some(length) if !out_buffer.is_null() => length,
+        _ => return CLOCK_INVALID_ARGUMENT,
+    };
+    let out = slice::from_raw_parts_mut(out_buffer, length);
+    let grid = render_clock(time, width, height);
+    for (index, (x, y)) in grid.points().enumerate() {
+        out[index] = grid
+            .get(x as isize, y as isize)
+            .copied()
+            .unwrap_or(Layer::Empty) as u8;
+    }
+    CLOCK_OK
+}
+
+/// Render the clock showing `time`, in seconds since midnight, into `out_buffer` as RGBA pixels,
+/// 4 bytes per cell. `palette` holds the RGBA color of each `CLOCK_LAYER_*` value in order, or is
+/// null for the colors of the nord-frost theme, where empty cells are transparent.
+///
+/// Returns `CLOCK_OK`, or `CLOCK_INVALID_ARGUMENT` without touching `out_buffer`.
+///
+/// # Safety
+///
+/// `out_buffer` must be null or point to at least `width * height * 4` writable bytes, and
+/// `palette` must be null or point to 20 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn clock_render_rgba(
+    time: u32,
+    width: u32,
+    height: u32,
+    palette: *const u8,
+    out_buffer: *mut u8,
+) -> i32 {
+    let (width, height) = (width as usize, height as usize);
+    let length = match width
+        .checked_mul(height)
+        .and_then(|cells| cells.checked_mul(4))
+    {
+        Some(length) if !out_buffer.is_null() => length,
+        _ => return CLOCK_INVALID_ARGUMENT,
+    };
+    let palette = if palette.is_null() {
+        DEFAULT_PALETTE
+    } else {
+        *(palette as *const [[u8; 4]; 5])
+    };
+    let pixels = render_rgba(&render_clock(time, width, height), &palette);
+    slice::from_raw_parts_mut(out_buffer, length).copy_from_slice(&pixels);
+    CLOCK_OK
+}
+
+/// Allocate a zeroed buffer of `length` bytes, for callers that cannot allocate memory that this
+/// library can write to, such as JavaScript calling the WebAssembly module.
+/// Returns null if `length` is 0.
+#[no_mangle]
+pub extern "C" fn clock_buffer_new(length: usize) -> *mut u8 {
+    if length == 0 {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(vec![0u8; length].into_boxed_slice()) as *mut u8
+}
+
+/// Free a buffer allocated by `clock_buffer_new`.
+///
+/// # Safety
+///
+/// `buffer` must be null or returned by `clock_buffer_new` with the same `length`, and not freed
+/// before.
+#[no_mangle]
+pub unsafe extern "C" fn clock_buffer_free(buffer: *mut u8, length: usize) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            buffer, length,
+        )))
+    }
+}
+
+/// A stable hash of the frame `clock_render` would write for the same arguments, which is the
+/// same on every platform, so that rendering regressions are cheap to detect.
+#[no_mangle]
+pub extern "C" fn clock_render_hash(time: u32, width: u32, height: u32) -> u64 {
+    render_signature(&render_clock(time, width as usize, height as usize))
+}
+
+/// Name of `layer` in the header, such as `CLOCK_LAYER_HOUR_HAND`.
+fn layer_name(layer: Layer) -> &'static str {
+    match layer {
+        Layer::Empty => "CLOCK_LAYER_EMPTY",
+        Layer::Face => "CLOCK_LAYER_FACE",
+        Layer::HourHand => "CLOCK_LAYER_HOUR_HAND",
+        Layer::MinuteHand => "CLOCK_LAYER_MINUTE_HAND",
+        Layer::SecondHand => "CLOCK_LAYER_SECOND_HAND",
+    }
+}
+
+/// The C header declaring everything exported by this library.
+pub fn header() -> String {
+    let layers: String = Layer::ALL
+        .iter()
+        .map(|layer| format!("#define {} {}\n", layer_name(*layer), *layer as u8))
+        .collect();
+    format!(
+        "/* Generated by analog-clock-header, do not edit. */
+#ifndef ANALOG_CLOCK_H
+#define ANALOG_CLOCK_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* Values of the cells written by clock_render. */
+{layers}
+/* Results of clock_render and clock_render_rgba. */
+#define CLOCK_OK {ok}
+#define CLOCK_INVALID_ARGUMENT ({invalid_argument})
+
+/*
+ * Render the clock showing `time`, in seconds since midnight, into `out_buffer`, which must hold
+ * `width * height` bytes. Cells are written row by row starting from the top left corner.
+ */
+int32_t clock_render(uint32_t time, uint32_t width, uint32_t height, uint8_t *out_buffer);
+
+/*
+ * Render the same clock into `out_buffer` as RGBA pixels, which must hold `width * height * 4`
+ * bytes. `palette` holds the 4 bytes of the color of each CLOCK_LAYER_* value in order, or is
+ * NULL for the default colors, where empty cells are transparent.
+ */
+int32_t clock_render_rgba(uint32_t time, uint32_t width, uint32_t height, const uint8_t *palette,
+                          uint8_t *out_buffer);
+
+/* Allocate a zeroed buffer of `length` bytes, or NULL if `length` is 0. */
+uint8_t *clock_buffer_new(size_t length);
+
+/* Free a buffer allocated by clock_buffer_new with the same `length`. */
+void clock_buffer_free(uint8_t *buffer, size_t length);
+
+/*
+ * A hash of the frame clock_render would write for the same arguments, which is the same on
+ * every platform.
+ */
+uint64_t clock_render_hash(uint32_t time, uint32_t width, uint32_t height);
+
+#endif
+",
+        layers = layers,
+        ok = CLOCK_OK,
+        invalid_argument = CLOCK_INVALID_ARGUMENT,
+    )
+}
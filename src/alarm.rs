@@ -0,0 +1,372 @@
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration as StdDuration, Instant, SystemTime};
+
+/// How often the schedule file is checked for changes.
+const RELOAD_INTERVAL: StdDuration = StdDuration::from_secs(2);
+
+/// The first moment after `now` when the hour hand points to `degree` on a 12-hour dial,
+/// rounded to the minute.
+pub fn next_time_at_degree(degree: f32, now: NaiveDateTime) -> NaiveDateTime {
+    let minutes = (degree / 360.0 * 12.0 * 60.0).round() as i64 % (12 * 60);
+    let mut time = now.date().and_hms(0, 0, 0) + Duration::minutes(minutes);
+    while time <= now {
+        time += Duration::hours(12)
+    }
+    time
+}
+
+/// On which days an alarm goes off.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Recurrence {
+    /// Only the first time after the alarm is set.
+    Once,
+    Daily,
+    /// On the given days of the week.
+    Days(Vec<Weekday>),
+}
+
+impl Recurrence {
+    fn includes(&self, day: Weekday) -> bool {
+        match self {
+            Recurrence::Once | Recurrence::Daily => true,
+            Recurrence::Days(days) => days.contains(&day),
+        }
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Recurrence, String> {
+        match text {
+            "once" => Ok(Recurrence::Once),
+            "daily" => Ok(Recurrence::Daily),
+            "weekdays" => Ok(Recurrence::Days(vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ])),
+            "weekends" => Ok(Recurrence::Days(vec![Weekday::Sat, Weekday::Sun])),
+            _ => text
+                .split(',')
+                .map(|day| day.trim().parse::<Weekday>().ok())
+                .collect::<Option<Vec<_>>>()
+                .map(Recurrence::Days)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid recurrence '{}', expected once, daily, weekdays, weekends or days \
+                         such as mon,wed,fri",
+                        text
+                    )
+                }),
+        }
+    }
+}
+
+/// An alarm of `--alarm` or of the schedule file, such as `07:30 weekdays`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlarmRule {
+    pub time: NaiveTime,
+    pub recurrence: Recurrence,
+}
+
+impl AlarmRule {
+    /// The first time after `after` that this alarm goes off, where `since` is when the alarm was
+    /// set, after which an alarm that goes off once does so only the first time.
+    pub fn next_trigger(
+        &self,
+        since: NaiveDateTime,
+        after: NaiveDateTime,
+    ) -> Option<NaiveDateTime> {
+        let from = match self.recurrence {
+            Recurrence::Once => since,
+            _ => after.max(since),
+        };
+        // A week from the day after today covers every day of the week
+        let trigger = (0..=7)
+            .map(|days| from.date() + Duration::days(days))
+            .filter(|date| self.recurrence.includes(date.weekday()))
+            .map(|date| date.and_time(self.time))
+            .find(|trigger| *trigger > from)?;
+        (trigger > after).then_some(trigger)
+    }
+}
+
+impl FromStr for AlarmRule {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<AlarmRule, String> {
+        let mut words = text.split_whitespace();
+        let time = words.next().unwrap_or_default();
+        let time = NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| {
+            format!(
+                "Invalid alarm '{}', expected a time such as 07:30, optionally followed by how \
+                 often it goes off, such as 07:30 weekdays",
+                text
+            )
+        })?;
+        let recurrence = match words.next() {
+            Some(recurrence) => recurrence.parse()?,
+            None => Recurrence::Once,
+        };
+        match words.next() {
+            Some(word) => Err(format!("Unexpected '{}' in alarm '{}'", word, text)),
+            None => Ok(AlarmRule { time, recurrence }),
+        }
+    }
+}
+
+/// Content of `~/.config/analog-clock/alarms.toml`, for example:
+///
+/// ```toml
+/// alarms = ["07:30 weekdays", "09:00 sat,sun", "22:15"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ScheduleFile {
+    alarms: Vec<String>,
+}
+
+/// The alarms of `--alarm` and of the schedule file, which is read again whenever it changes.
+pub struct Schedule {
+    rules: Vec<AlarmRule>,
+    file: Option<PathBuf>,
+    file_rules: Vec<AlarmRule>,
+    /// When the schedule file was modified, as of the last time it was read.
+    modified: Option<SystemTime>,
+    reloaded_at: Instant,
+    /// Why the schedule file could not be read again, until it is taken.
+    error: Option<String>,
+    /// When the alarms of `--alarm` were set, which is when the clock started.
+    since: Option<NaiveDateTime>,
+    /// When the alarms of the schedule file were set, which is when it was last read.
+    file_since: Option<NaiveDateTime>,
+    /// Until when the alarms have gone off.
+    checked: Option<NaiveDateTime>,
+}
+
+impl Schedule {
+    /// Read the schedule file for the first time, failing if it is invalid. A schedule file that
+    /// does not exist yet is read once it is created.
+    pub fn new(rules: Vec<AlarmRule>, file: Option<PathBuf>) -> Result<Schedule, String> {
+        let mut schedule = Schedule {
+            rules,
+            file,
+            file_rules: Vec::new(),
+            modified: None,
+            reloaded_at: Instant::now(),
+            error: None,
+            since: None,
+            file_since: None,
+            checked: None,
+        };
+        schedule.reload()?;
+        Ok(schedule)
+    }
+
+    /// Read the schedule file again if it was modified, and return whether it was. A file that
+    /// is invalid fails once, and is read again only after it is modified again.
+    fn reload(&mut self) -> Result<bool, String> {
+        self.reloaded_at = Instant::now();
+        let path = match &self.file {
+            Some(path) => path,
+            None => return Ok(false),
+        };
+        let modified = match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => Some(modified),
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => {
+                return Err(format!(
+                    "Unable to read alarms from {}: {}",
+                    path.display(),
+                    error
+                ))
+            }
+        };
+        if modified == self.modified {
+            return Ok(false);
+        }
+        let file_rules = match modified {
+            Some(_) => fs::read_to_string(path)
+                .map_err(|error| error.to_string())
+                .and_then(|content| {
+                    toml::from_str::<ScheduleFile>(&content).map_err(|error| error.to_string())
+                })
+                .and_then(|file| file.alarms.iter().map(|alarm| alarm.parse()).collect())
+                .map_err(|error| format!("Invalid alarms in {}: {}", path.display(), error)),
+            None => Ok(Vec::new()),
+        };
+        self.modified = modified;
+        self.file_rules = file_rules?;
+        Ok(true)
+    }
+
+    /// Every alarm, with when it was set, or `now` if it has not been set yet.
+    fn all_rules(&self, now: NaiveDateTime) -> impl Iterator<Item = (&AlarmRule, NaiveDateTime)> {
+        let since = self.since.unwrap_or(now);
+        let file_since = self.file_since.unwrap_or(now);
+        (self.rules.iter().map(move |rule| (rule, since)))
+            .chain(self.file_rules.iter().map(move |rule| (rule, file_since)))
+    }
+
+    /// The last time that an alarm went off since the previous call, if any. The schedule file
+    /// is read again first if it changed, and if it has become invalid, the alarms read from it
+    /// before are kept, and the error is kept for `take_error`.
+    pub fn due(&mut self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.reloaded_at.elapsed() >= RELOAD_INTERVAL {
+            match self.reload() {
+                // Alarms of the file that go off once are set again by changing the file
+                Ok(true) => self.file_since = self.checked,
+                Ok(false) => (),
+                Err(error) => self.error = Some(error),
+            }
+        }
+        let checked = match self.checked {
+            Some(checked) => checked,
+            None => {
+                self.since = Some(now);
+                self.file_since = Some(now);
+                self.checked = Some(now);
+                return None;
+            }
+        };
+        self.checked = Some(checked.max(now));
+        self.all_rules(now)
+            .filter_map(|(rule, since)| rule.next_trigger(since, checked))
+            .filter(|trigger| *trigger <= now)
+            .max()
+    }
+
+    /// When each alarm goes off next within 12 hours, which is as far as the dial shows.
+    pub fn upcoming(&self, now: NaiveDateTime) -> Vec<NaiveDateTime> {
+        self.all_rules(now)
+            .filter_map(|(rule, since)| rule.next_trigger(since, now))
+            .filter(|trigger| *trigger - now < Duration::hours(12))
+            .collect()
+    }
+
+    /// Why the schedule file could not be read again since the last call, if it could not.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// A time in the week of Monday 2024-01-15.
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd(2024, 1, day).and_hms(hour, minute, 0)
+    }
+
+    fn rule(text: &str) -> AlarmRule {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_recurrences() {
+        assert_eq!("once".parse(), Ok(Recurrence::Once));
+        assert_eq!("daily".parse(), Ok(Recurrence::Daily));
+        assert_eq!(
+            "weekends".parse(),
+            Ok(Recurrence::Days(vec![Weekday::Sat, Weekday::Sun]))
+        );
+        assert_eq!(
+            "mon,wed, fri".parse(),
+            Ok(Recurrence::Days(vec![
+                Weekday::Mon,
+                Weekday::Wed,
+                Weekday::Fri
+            ]))
+        );
+        assert!("mon,someday".parse::<Recurrence>().is_err());
+        assert!("".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn parses_alarm_rules() {
+        assert_eq!(
+            rule("07:30"),
+            AlarmRule {
+                time: NaiveTime::from_hms(7, 30, 0),
+                recurrence: Recurrence::Once,
+            }
+        );
+        assert_eq!(rule("22:15 daily").recurrence, Recurrence::Daily);
+        assert!("7.30".parse::<AlarmRule>().is_err());
+        assert!("07:30 daily again".parse::<AlarmRule>().is_err());
+        assert!("".parse::<AlarmRule>().is_err());
+    }
+
+    #[test]
+    fn daily_alarm_rolls_over_to_the_next_day() {
+        let alarm = rule("07:30 daily");
+        let since = at(15, 6, 0);
+        assert_eq!(alarm.next_trigger(since, since), Some(at(15, 7, 30)));
+        assert_eq!(alarm.next_trigger(since, at(15, 8, 0)), Some(at(16, 7, 30)));
+        // Across the end of the month
+        assert_eq!(
+            alarm.next_trigger(since, at(31, 23, 0)),
+            Some(NaiveDate::from_ymd(2024, 2, 1).and_hms(7, 30, 0))
+        );
+    }
+
+    #[test]
+    fn trigger_at_after_is_not_next() {
+        let alarm = rule("07:30 daily");
+        assert_eq!(
+            alarm.next_trigger(at(15, 6, 0), at(15, 7, 30)),
+            Some(at(16, 7, 30))
+        );
+    }
+
+    #[test]
+    fn once_goes_off_only_the_first_time() {
+        let alarm = rule("07:30");
+        let since = at(15, 8, 0);
+        assert_eq!(alarm.next_trigger(since, since), Some(at(16, 7, 30)));
+        assert_eq!(alarm.next_trigger(since, at(16, 7, 30)), None);
+        assert_eq!(alarm.next_trigger(since, at(20, 0, 0)), None);
+    }
+
+    #[test]
+    fn weekday_alarm_skips_the_weekend() {
+        let alarm = rule("07:30 weekdays");
+        // Friday after the alarm, then Monday
+        assert_eq!(
+            alarm.next_trigger(at(15, 0, 0), at(19, 8, 0)),
+            Some(at(22, 7, 30))
+        );
+        let alarm = rule("09:00 sun");
+        assert_eq!(
+            alarm.next_trigger(at(15, 0, 0), at(21, 9, 0)),
+            Some(at(28, 9, 0))
+        );
+    }
+
+    #[test]
+    fn schedule_rings_each_alarm_once() {
+        let mut schedule = Schedule::new(vec![rule("07:30"), rule("08:00 daily")], None).unwrap();
+        assert_eq!(schedule.due(at(15, 7, 0)), None);
+        assert_eq!(
+            schedule.upcoming(at(15, 7, 0)),
+            [at(15, 7, 30), at(15, 8, 0)]
+        );
+        assert_eq!(schedule.due(at(15, 7, 29)), None);
+        assert_eq!(schedule.due(at(15, 7, 30)), Some(at(15, 7, 30)));
+        assert_eq!(schedule.due(at(15, 7, 31)), None);
+        // Missed days ring once, for the first alarm that was missed
+        assert_eq!(schedule.due(at(17, 9, 0)), Some(at(15, 8, 0)));
+        assert_eq!(schedule.due(at(17, 9, 1)), None);
+        assert_eq!(schedule.upcoming(at(17, 9, 1)), []);
+    }
+}
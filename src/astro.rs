@@ -0,0 +1,77 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::f64::consts::PI;
+
+/// Whether and when the sun rises and sets on a given day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Daylight {
+    /// Local times of sunrise and sunset.
+    Normal {
+        sunrise: NaiveDateTime,
+        sunset: NaiveDateTime,
+    },
+    /// The sun never sets, i.e. midnight sun.
+    PolarDay,
+    /// The sun never rises.
+    PolarNight,
+}
+
+/// Compute sunrise and sunset at the given latitude and longitude (in degrees, north and east
+/// are positive), with the approximation of the NOAA solar calculator, which is accurate to
+/// about a minute away from the poles.
+///
+/// Refer https://gml.noaa.gov/grad/solcalc/solareqns.PDF
+pub fn daylight(date: NaiveDate, latitude: f64, longitude: f64) -> Daylight {
+    // Fractional year in radians
+    let gamma = 2.0 * PI / 365.0 * (date.ordinal() as f64 - 1.0);
+
+    // Equation of time in minutes
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+
+    // Solar declination in radians
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    // The zenith of 90.833 degrees accounts for atmospheric refraction and the size of the sun
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = 90.833_f64.to_radians().cos() / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if cos_hour_angle > 1.0 {
+        return Daylight::PolarNight;
+    }
+    if cos_hour_angle < -1.0 {
+        return Daylight::PolarDay;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    // Minutes after midnight UTC
+    let at = |minutes: f64| -> NaiveDateTime {
+        let utc: DateTime<Utc> = Utc.from_utc_datetime(&date.and_hms(0, 0, 0))
+            + Duration::seconds((minutes * 60.0) as i64);
+        utc.with_timezone(&Local).naive_local()
+    };
+    Daylight::Normal {
+        sunrise: at(720.0 - 4.0 * (longitude + hour_angle) - equation_of_time),
+        sunset: at(720.0 - 4.0 * (longitude - hour_angle) - equation_of_time),
+    }
+}
+
+/// Mean length of a lunar month, from one new moon to the next, in days.
+const SYNODIC_MONTH: f64 = 29.530588853;
+
+/// How far the moon is through its phases at the given time, from 0 at new moon through 0.5 at
+/// full moon back to 1 at the next new moon.
+///
+/// Counted from the new moon of 2000-01-06 18:14 UTC in mean lunar months, which is accurate to
+/// about a day, so the time zone of `datetime` is ignored.
+pub fn moon_phase(datetime: NaiveDateTime) -> f64 {
+    let new_moon = NaiveDate::from_ymd(2000, 1, 6).and_hms(18, 14, 0);
+    let days = (datetime - new_moon).num_seconds() as f64 / 86_400.0;
+    (days / SYNODIC_MONTH).rem_euclid(1.0)
+}
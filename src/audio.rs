@@ -0,0 +1,94 @@
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Sample rate of the captured audio, which only has to be high enough to follow loudness.
+const SAMPLE_RATE: usize = 8000;
+
+/// How many samples the loudness is measured over, 50ms worth.
+const WINDOW: usize = SAMPLE_RATE / 20;
+
+/// How much of the previous level is kept when the audio gets quieter, so that the clock fades
+/// out instead of flickering. Louder audio is followed immediately.
+const DECAY: f32 = 0.8;
+
+/// Loudness above which the level is 1.
+const FULL_SCALE_RMS: f32 = 0.3;
+
+/// The loudness of the default audio input, measured by a background thread that reads raw
+/// samples from `parec` (PulseAudio and PipeWire) or `arecord` (ALSA).
+pub struct AudioLevel {
+    /// Bits of an `f32`, as there is no atomic float.
+    level: Arc<AtomicU32>,
+    recorder: Child,
+}
+
+impl AudioLevel {
+    pub fn start() -> Result<AudioLevel, String> {
+        let rate = SAMPLE_RATE.to_string();
+        let mut recorder = spawn(
+            "parec",
+            &["--raw", "--format=s16le", "--channels=1", "--rate", &rate],
+        )
+        .or_else(|_| {
+            spawn(
+                "arecord",
+                &["-q", "-t", "raw", "-f", "S16_LE", "-c", "1", "-r", &rate],
+            )
+        })
+        .map_err(|error| format!("Unable to record audio with parec or arecord: {}", error))?;
+        let mut stdout = recorder
+            .stdout
+            .take()
+            .ok_or_else(|| "Unable to read the recorded audio".to_string())?;
+
+        let level = Arc::new(AtomicU32::new(0f32.to_bits()));
+        let shared_level = level.clone();
+        thread::spawn(move || {
+            let mut buffer = [0; WINDOW * 2];
+            let mut smoothed = 0.0;
+            while stdout.read_exact(&mut buffer).is_ok() {
+                let loudness = (rms(&buffer) / FULL_SCALE_RMS).min(1.0);
+                smoothed = loudness.max(smoothed * DECAY);
+                shared_level.store(f32::to_bits(smoothed), Ordering::Relaxed)
+            }
+        });
+        Ok(AudioLevel { level, recorder })
+    }
+
+    /// From 0 for silence to 1 for loud audio.
+    pub fn level(&self) -> f32 {
+        f32::from_bits(self.level.load(Ordering::Relaxed))
+    }
+}
+
+impl Drop for AudioLevel {
+    fn drop(&mut self) {
+        let _ = self.recorder.kill();
+        let _ = self.recorder.wait();
+    }
+}
+
+fn spawn(program: &str, args: &[&str]) -> std::io::Result<Child> {
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+}
+
+/// Root mean square of signed 16-bit little-endian samples, from 0 to 1.
+fn rms(samples: &[u8]) -> f32 {
+    let count = samples.len() / 2;
+    let sum: f32 = samples
+        .chunks_exact(2)
+        .map(|bytes| {
+            let sample = i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / i16::MAX as f32;
+            sample * sample
+        })
+        .sum();
+    (sum / count.max(1) as f32).sqrt()
+}
@@ -0,0 +1,30 @@
+use std::fs;
+use std::path::Path;
+
+/// Where Linux lists batteries and power adapters.
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// How charged the first battery is, from 0 to 1, or `None` if there is no battery or its charge
+/// cannot be read on this platform.
+pub fn level() -> Option<f32> {
+    let mut supplies: Vec<_> = fs::read_dir(POWER_SUPPLY_DIR)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .collect();
+    supplies.sort();
+    supplies.iter().find_map(|supply| capacity(supply))
+}
+
+/// The charge of the power supply at `supply` in percent divided by 100, if it is a battery.
+fn capacity(supply: &Path) -> Option<f32> {
+    let kind = fs::read_to_string(supply.join("type")).ok()?;
+    if kind.trim() != "Battery" {
+        return None;
+    }
+    let percent: f32 = fs::read_to_string(supply.join("capacity"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some((percent / 100.0).clamp(0.0, 1.0))
+}
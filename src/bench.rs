@@ -0,0 +1,59 @@
+use crate::clock::{draw_clock, RunClockOptions, UiState};
+
+use chrono::Duration as ChronoDuration;
+use std::io::{self, stdout, Write};
+use std::mem;
+use std::time::{Duration, Instant};
+
+pub struct BenchOptions {
+    /// How many frames are rendered.
+    pub frames: u32,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Render frames as fast as possible without printing them, and report how long each stage of
+/// rendering takes on average, so that optimizations can be measured.
+pub fn run_bench(options: RunClockOptions, bench: BenchOptions) -> io::Result<()> {
+    let state = UiState::new(&options);
+    let start = options.now();
+    let frames = bench.frames.max(1);
+    let size = (bench.width, bench.height);
+
+    let mut draw = Duration::ZERO;
+    let mut diff = Duration::ZERO;
+    let mut updates = 0;
+    let mut previous = draw_clock(&state, &options, start, size);
+    for frame_index in 1..=frames {
+        let elapsed = options.tick_interval * frame_index;
+        let datetime =
+            start + ChronoDuration::from_std(elapsed).unwrap_or_else(|_| ChronoDuration::zero());
+
+        let started = Instant::now();
+        let matrix = draw_clock(&state, &options, datetime, size);
+        draw += started.elapsed();
+
+        let started = Instant::now();
+        updates += previous.diff(&matrix).map_or(0, |diff| diff.len());
+        diff += started.elapsed();
+
+        // As in the clock, the buffer of the previous frame is reused by the next
+        mem::replace(&mut previous, matrix).recycle();
+    }
+
+    let mut stdout = stdout();
+    writeln!(
+        stdout,
+        "{} frames of {}x{} characters",
+        frames, bench.width, bench.height
+    )?;
+    for (stage, total) in [("draw", draw), ("diff", diff), ("total", draw + diff)] {
+        writeln!(stdout, "{:<6}{:>10.1?} per frame", stage, total / frames)?;
+    }
+    writeln!(
+        stdout,
+        "{:<6}{:>10} per frame",
+        "cells",
+        updates / frames as usize
+    )
+}
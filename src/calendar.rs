@@ -0,0 +1,38 @@
+use chrono::{Datelike, NaiveDate};
+
+/// Columns taken by a week, where each day takes two columns and a space.
+pub const CALENDAR_WIDTH: usize = 7 * 3 - 1;
+
+/// The days of a month arranged in weeks from Monday, see `--show-calendar`.
+pub struct Month {
+    first: NaiveDate,
+    length: u32,
+}
+
+impl Month {
+    /// The month that `date` is in.
+    pub fn of(date: NaiveDate) -> Month {
+        let first = date.with_day(1).unwrap();
+        let length = (28..=31)
+            .take_while(|day| first.with_day(*day).is_some())
+            .last()
+            .unwrap_or(28);
+        Month { first, length }
+    }
+
+    /// Such as `2026-10`, which reads the same in every language.
+    pub fn title(&self) -> String {
+        self.first.format("%Y-%m").to_string()
+    }
+
+    /// One row per week, where the days of the previous and the next month are `None`.
+    pub fn weeks(&self) -> Vec<[Option<u32>; 7]> {
+        let leading_days = self.first.weekday().num_days_from_monday() as usize;
+        let mut weeks = vec![[None; 7]; (leading_days + self.length as usize).div_ceil(7)];
+        for day in 1..=self.length {
+            let index = leading_days + day as usize - 1;
+            weeks[index / 7][index % 7] = Some(day)
+        }
+        weeks
+    }
+}
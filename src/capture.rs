@@ -0,0 +1,77 @@
+use crate::clock::{describe_updates, DiffUpdate, Matrix};
+use crate::glyph::Glyphs;
+
+use chrono::{Local, NaiveDateTime};
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// The most recent frames, kept by `--debug-capture` to diagnose rendering bugs.
+pub struct FrameCapture {
+    limit: usize,
+    frames: VecDeque<CapturedFrame>,
+}
+
+struct CapturedFrame {
+    datetime: NaiveDateTime,
+    /// What the screen should look like after this frame.
+    screen: String,
+    /// What was actually printed to get there from the previous frame.
+    diff: String,
+}
+
+impl FrameCapture {
+    pub fn new(limit: usize) -> FrameCapture {
+        FrameCapture {
+            limit,
+            frames: VecDeque::with_capacity(limit),
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        datetime: NaiveDateTime,
+        matrix: &Matrix,
+        diff: &[DiffUpdate],
+        glyphs: &Glyphs,
+    ) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.frames.len() == self.limit {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(CapturedFrame {
+            datetime,
+            screen: matrix.to_text(glyphs),
+            diff: describe_updates(diff, glyphs),
+        })
+    }
+
+    /// Write every recorded frame as `frame-N.txt` and `diff-N.txt` into a new directory
+    /// under the current directory, oldest first. Returns the path of the directory.
+    pub fn write(&self) -> io::Result<PathBuf> {
+        let dir = PathBuf::from(format!(
+            "analog-clock-capture-{}",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        fs::create_dir_all(&dir)?;
+        for (index, frame) in self.frames.iter().enumerate() {
+            let header = format!("{}\n", frame.datetime.format("%Y-%m-%d %H:%M:%S%.3f"));
+            fs::write(
+                dir.join(format!("frame-{:03}.txt", index)),
+                header.clone() + &frame.screen,
+            )?;
+            fs::write(
+                dir.join(format!("diff-{:03}.txt", index)),
+                header + &frame.diff,
+            )?;
+        }
+        Ok(dir)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
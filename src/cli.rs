@@ -1,38 +1,908 @@
+use crate::alarm::AlarmRule;
+use crate::clock::{
+    BackgroundArea, Chime, ComplicationKind, Elapsed, Face, HandStyle, Markers, Mode, Numerals,
+    PlacedComplication, Quadrant, Slot, Subdial,
+};
+use crate::color::ColorMode;
+use crate::config::Defaults;
+use crate::layout::Layout;
+use crate::motion::Movement;
+use crate::output::FrameFormat;
+use crate::temperature::ColorTemperature;
+use crate::wall::Wall;
+use crate::zone::Zone;
+use analog_clock_core::Shape;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use colors_transform::Rgb;
+use std::num::NonZeroU64;
+use std::path::PathBuf;
+use std::time::Duration;
+use structopt::clap::{ArgGroup, ArgMatches, Shell};
 use structopt::StructOpt;
 
+// The `about` text is translated at runtime, see `i18n::Messages::about`.
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "Analog Clock",
-    about = "
-Key bindings:
-  'q'     : quit
-  '-'     : decrease clock width
-  '='/'+' : increase clock width
-  'j'     : next theme
-  'k'     : previous theme
-
-For more info, please refer https://github.com/wongjiahau/analog-clock
-"
+    group = ArgGroup::with_name("output").multiple(true),
+    group = ArgGroup::with_name("epoch")
 )]
 pub struct CliOptions {
-    /// Theme of the clock.
-    /// See https://github.com/wongjiahau/analog-clock/blob/master/src/theme.rs
+    /// Theme of the clock, or `auto` to pick a light or a dark theme that suits the background
+    /// color of the terminal.
+    /// See --list-themes for the themes to choose from.
     #[structopt(long, default_value = "nord-frost")]
     pub theme: String,
 
-    /// How often should the clock be redrawn in millisecond.
-    #[structopt(long, default_value = "1000")]
-    pub tick: usize,
+    /// Print every theme, including those in the theme directory, with the colors of its hands
+    /// and its face, and exit.
+    #[structopt(long)]
+    pub list_themes: bool,
+
+    /// Theme of the clock during the day, see --night-from and --night-to.
+    /// Requires --theme-night, and replaces --theme.
+    #[structopt(long, requires = "theme-night")]
+    pub theme_day: Option<String>,
+
+    /// Theme of the clock during the night. Requires --theme-day.
+    #[structopt(long, requires = "theme-day")]
+    pub theme_night: Option<String>,
+
+    /// When the night starts, for --theme-night.
+    #[structopt(long, default_value = "19:00", parse(try_from_str = parse_time))]
+    pub night_from: NaiveTime,
+
+    /// When the night ends, for --theme-night.
+    #[structopt(long, default_value = "07:00", parse(try_from_str = parse_time))]
+    pub night_to: NaiveTime,
+
+    /// How many frames are drawn per second, one by default. Frames are drawn on a fixed
+    /// schedule that accounts for the time spent drawing them, so a sweeping second hand
+    /// neither drifts nor stutters.
+    #[structopt(long, parse(try_from_str = parse_fps))]
+    pub fps: Option<f64>,
+
+    /// Deprecated, use --fps instead. How often should the clock be redrawn in millisecond.
+    #[structopt(long, conflicts_with = "fps")]
+    pub tick: Option<usize>,
+
+    /// How many times a character is taller than it is wide, which is how much the dial is
+    /// stretched horizontally to look round. Press '-' and '+' to adjust it, which is
+    /// remembered for the terminal unless --no-persist is given. At least 1.
+    #[structopt(long, default_value = "2.0", parse(try_from_str = parse_aspect_ratio))]
+    pub aspect_ratio: f32,
+
+    /// Do not restore the width and size of the dial adjusted in a previous run, nor save them
+    /// on exit to $XDG_STATE_HOME/analog-clock/state.toml.
+    #[structopt(long)]
+    pub no_persist: bool,
+
+    /// Reserve the bottom row for the theme, timezone, frame rate and the key to quit.
+    #[structopt(long)]
+    pub status_line: bool,
 
-    /// Hide second hand.
+    /// Hide second hand. Press 's' to toggle it while running.
     #[structopt(long)]
     pub hide_second_hand: bool,
 
-    /// Hide hour labels.
+    /// Show the second hand, even if the config file hides it.
+    #[structopt(long, conflicts_with = "hide-second-hand")]
+    pub show_second_hand: bool,
+
+    /// Hide hour labels. Press 'h' to toggle them while running.
     #[structopt(long)]
     pub hide_hour_labels: bool,
 
-    /// Show minute labels.
+    /// Show the hour labels, even if the config file hides them.
+    #[structopt(long, conflicts_with = "hide-hour-labels")]
+    pub show_hour_labels: bool,
+
+    /// Show minute labels. Press 'm' to toggle them while running.
     #[structopt(long)]
     pub show_minute_labels: bool,
+
+    /// Hide the minute labels, even if the config file shows them.
+    #[structopt(long, conflicts_with = "show-minute-labels")]
+    pub hide_minute_labels: bool,
+
+    /// Show the calendar of the month next to the dial, with today highlighted.
+    /// It can also be toggled with 'c'.
+    #[structopt(long)]
+    pub show_calendar: bool,
+
+    /// Draw a ring just inside the dial that fills up over an hour and starts again, showing how
+    /// long the clock has been running (session, the default), or the machine (uptime).
+    #[structopt(long, value_name = "SINCE")]
+    pub show_elapsed: Option<Option<Elapsed>>,
+
+    /// Language of the messages, for example `en`, `zh` or `ms`.
+    /// Defaults to the language of the current locale.
+    #[structopt(long)]
+    pub lang: Option<String>,
+
+    /// Pin the center of the clock to a corner of the terminal, so that only one quarter of
+    /// the dial is drawn. One of top-left, top-right, bottom-left or bottom-right.
+    #[structopt(long)]
+    pub quadrant: Option<Quadrant>,
+
+    /// Fill the terminal with a grid of clocks, COLUMNSxROWS such as `3x2`.
+    #[structopt(long, value_name = "COLUMNSxROWS")]
+    pub wall: Option<Wall>,
+
+    /// Offsets from UTC of the clocks of --wall, row by row and separated by commas, such as
+    /// `+0,+9,-5`. Clocks without one show the usual time.
+    #[structopt(
+        long,
+        use_delimiter = true,
+        allow_hyphen_values = true,
+        requires = "wall",
+        parse(try_from_str = parse_utc_offset)
+    )]
+    pub wall_zones: Vec<FixedOffset>,
+
+    /// `dial` for just the dial, or `full` for the dial on the left, and the digital time above the
+    /// calendar of the month on the right. Terminals narrower than 60 columns only show the dial.
+    /// Cannot be full with --wall.
+    #[structopt(long, default_value = "dial")]
+    pub layout: Layout,
+
+    /// Draw a tiny dial with only a sweeping second hand at the top left corner,
+    /// meant for a small tmux pane. Use a high --fps for a smooth sweep.
+    #[structopt(long)]
+    pub mini_seconds: bool,
+
+    /// Run a scripted tour that cycles themes, toggles labels and hands, and changes the speed
+    /// of the clock, with a caption for each step. Combine with `export --animate` to record it.
+    #[structopt(long)]
+    pub demo: bool,
+
+    /// Draw a smaller dial that drifts across the terminal and bounces off its edges, to prevent
+    /// burn-in on screens that are always on. Any key quits.
+    #[structopt(long, conflicts_with_all = &["quadrant", "x", "y"])]
+    pub screensaver: bool,
+
+    /// Print a single frame to stdout and exit, for scripts, MOTD banners and files.
+    #[structopt(long)]
+    pub once: bool,
+
+    /// With --once, print a hash of the frame instead of the frame, which only changes when the
+    /// characters or colors of the frame change, on any platform.
+    #[structopt(long, requires = "once")]
+    pub print_hash: bool,
+
+    /// Stream every frame to this named pipe instead of drawing on the terminal, for other
+    /// programs such as `cat` in another terminal or the overlays of streaming software. The
+    /// pipe is made if it does not exist, and the clock waits for a reader to open it.
+    #[cfg(unix)]
+    #[structopt(long, value_name = "PATH", group = "output", conflicts_with = "once")]
+    pub output_fifo: Option<PathBuf>,
+
+    /// Write every frame to a file of its own in this directory instead of drawing on the
+    /// terminal, such as `frame-000001.png`.
+    #[structopt(long, value_name = "DIR", group = "output", conflicts_with = "once")]
+    pub output_frames: Option<PathBuf>,
+
+    /// With --output-frames, write every frame to the same file, `frame.png` or `frame.ansi`,
+    /// which is replaced at once, instead of to a file of its own. This suits programs that show
+    /// an image file as it changes.
+    #[structopt(long, requires = "output-frames")]
+    pub overwrite: bool,
+
+    /// Stop after this many frames of --output-fifo or --output-frames, instead of running until
+    /// the clock is stopped.
+    #[structopt(long, value_name = "N", requires = "output", parse(try_from_str = parse_count))]
+    pub frames: Option<NonZeroU64>,
+
+    /// Format of the files of --output-frames, ansi (text with colors, as printed by --once) or
+    /// png, the default.
+    // Without a default value, as clap would take it for the option being given
+    #[structopt(long, requires = "output")]
+    pub format: Option<FrameFormat>,
+
+    /// Size of the frames of --output-fifo and --output-frames in characters, up to 500x200.
+    /// 80x24 by default.
+    #[structopt(
+        long,
+        value_name = "WIDTHxHEIGHT",
+        requires = "output",
+        parse(try_from_str = parse_size)
+    )]
+    pub output_size: Option<(usize, usize)>,
+
+    /// Move like a Swiss railway clock: the second hand sweeps the dial in 58.5 seconds and
+    /// waits at 12, then the minute hand jumps. Use a high --fps for a smooth sweep.
+    #[structopt(long)]
+    pub station_clock: bool,
+
+    /// How the second hand moves, one of sweep (continuously), tick (snaps once per second)
+    /// or quartz-jump (a quick eased jump every second). By default the second hand sweeps
+    /// if --fps is more than one, and ticks otherwise. Use a high --fps for a smooth sweep.
+    #[structopt(long, conflicts_with = "station-clock")]
+    pub second_style: Option<Movement>,
+
+    /// Show the day of the month inside the dial, which rolls over like an odometer at midnight.
+    /// Same as `--complication 6=date`.
+    #[structopt(long)]
+    pub show_date: bool,
+
+    /// Extend the second hand through the center with a short counterweight tail.
+    #[structopt(long)]
+    pub second_tail: bool,
+
+    /// Cover the center of the dial, where the hands meet, with a filled circle.
+    #[structopt(long)]
+    pub center_cap: bool,
+
+    /// Blink the center of the dial once a second, alternating between the color of the second
+    /// hand and a darker one, so that the clock ticks even with the second hand hidden.
+    #[structopt(long)]
+    pub blink_center: bool,
+
+    /// Draw a shadow in a darker color of the face under each hand, which gives the dial depth.
+    #[structopt(long)]
+    pub shadow: bool,
+
+    /// Ring the terminal bell as many times as the hour on the hour, like a grandfather clock.
+    /// One of hourly, or quarterly to also ring 1 to 3 times on each quarter hour.
+    #[structopt(long)]
+    pub chime: Option<Chime>,
+
+    /// Flash the outline of the dial with each bell of --chime.
+    #[structopt(long, requires = "chime")]
+    pub chime_flash: bool,
+
+    /// Draw a small dial at 9 o'clock whose hand turns once per second, one of ms (sweeping
+    /// through the milliseconds) or ds (jumping every tenth of a second).
+    /// Same as `--complication 9=ms` or `--complication 9=ds`.
+    #[structopt(long)]
+    pub show_subdial: Option<Subdial>,
+
+    /// Draw the current phase of the moon at 6 o'clock, as seen from the northern hemisphere
+    /// unless --latitude is negative. Same as `--complication 6=moon`, so it cannot be combined
+    /// with --show-date.
+    #[structopt(long)]
+    pub show_moonphase: bool,
+
+    /// Draw a 24-hour hand in the color of the theme for it, which shows the time in another time
+    /// zone by going around the dial once a day, like the GMT hand of a pilot's watch.
+    /// The zone is a name such as Europe/London, or an offset from UTC such as +9.
+    #[structopt(long, value_name = "ZONE")]
+    pub gmt_hand: Option<Zone>,
+
+    /// Draw a small dial inside the dial at 3, 6, 9 or 12 o'clock, such as `3=date` or `6=moon`.
+    /// Complications are date, ms and ds (see --show-subdial), moon and battery.
+    /// Can be given more than once.
+    #[structopt(long, value_name = "SLOT=NAME", number_of_values = 1)]
+    pub complication: Vec<PlacedComplication>,
+
+    /// Rotate the whole dial clockwise by this many degrees, for displays mounted sideways.
+    /// Press 'r' to rotate by another 90 degrees.
+    #[structopt(long, default_value = "0", allow_hyphen_values = true)]
+    pub rotate: f32,
+
+    /// Freeze the dial at this time instead of showing the current time, for example `10:09:37`.
+    #[structopt(long, parse(try_from_str = parse_time))]
+    pub time: Option<NaiveTime>,
+
+    /// Show the time at this offset from UTC instead of the local time,
+    /// for example `+5:30` or `-08:00`.
+    #[structopt(
+        long,
+        allow_hyphen_values = true,
+        conflicts_with = "time",
+        parse(try_from_str = parse_utc_offset)
+    )]
+    pub utc_offset: Option<FixedOffset>,
+
+    /// Correct the clock of this computer with the time of an NTP server, which is queried at
+    /// startup and every 15 minutes. The server defaults to pool.ntp.org, another one is given
+    /// with an equals sign, for example `--ntp=time.google.com`.
+    #[structopt(long, value_name = "server", require_equals = true)]
+    pub ntp: Option<Option<String>>,
+
+    /// Accept commands on a Unix domain socket, one per line: `theme NAME`, `timer DURATION`
+    /// (ring the bell after a duration such as 10m), `pause` or `resume`. Each command is
+    /// answered with `ok` or an error. The socket is $XDG_RUNTIME_DIR/analog-clock.sock unless
+    /// another path is given with an equals sign, for example
+    /// `--control-socket=/tmp/clock.sock`.
+    #[cfg(unix)]
+    #[structopt(long, value_name = "path", require_equals = true)]
+    pub control_socket: Option<Option<PathBuf>>,
+
+    /// `analog` for the usual 12-hour dial, or `daydial` for a 24-hour dial with noon at the top,
+    /// where the time between sunrise and sunset is light and the rest is dark.
+    #[structopt(long, default_value = "analog")]
+    pub mode: Mode,
+
+    /// The outline of the dial, one of circle, square or octagon. The hour and minute labels
+    /// follow it, while the hands still turn around the center.
+    #[structopt(long, default_value = "circle")]
+    pub dial_shape: Shape,
+
+    /// Draw the dial at 2 to 4 times the resolution of the terminal and shrink it back, so that
+    /// hands blend into the face and edges are shaded with ░, ▒ and ▓ instead of being jagged.
+    #[structopt(long, default_value = "1", parse(try_from_str = parse_supersample))]
+    pub supersample: usize,
+
+    /// How the 12 hour positions are drawn, one of lines, dots, batons (thick bars), double-12
+    /// (lines, with a doubled line at 12 o'clock) or none.
+    #[structopt(long, default_value = "lines")]
+    pub markers: Markers,
+
+    /// Write the hours inside the hour labels in arabic (1 to 12), roman (I to XII),
+    /// devanagari or chinese numerals. Hidden along with the hour labels.
+    #[structopt(long)]
+    pub numerals: Option<Numerals>,
+
+    /// Shape of the hour and minute hands: plain (lines of the same width), arrow (a narrow
+    /// shaft ending in an arrowhead) or tapered (narrowing to a point at the tip).
+    #[structopt(long, default_value = "plain")]
+    pub hand_style: HandStyle,
+
+    /// `analog` for a dial with hands, `binary` for a column of dots for each digit of the time,
+    /// where the lit dots add up to the digit, `digital` for seven-segment digits, or `words`
+    /// for a grid of letters where the words telling the time in English light up.
+    #[structopt(long, default_value = "analog")]
+    pub face: Face,
+
+    /// Latitude in degrees, north is positive. Required by `--mode daydial`.
+    #[structopt(long, allow_hyphen_values = true, required_if("mode", "daydial"))]
+    pub latitude: Option<f64>,
+
+    /// Longitude in degrees, east is positive. Required by `--mode daydial`.
+    #[structopt(long, allow_hyphen_values = true, required_if("mode", "daydial"))]
+    pub longitude: Option<f64>,
+
+    /// Column of the center of the dial, by default the middle of the terminal.
+    #[structopt(long)]
+    pub x: Option<usize>,
+
+    /// Row of the center of the dial, by default the middle of the terminal.
+    #[structopt(long)]
+    pub y: Option<usize>,
+
+    /// Radius of the dial in rows, by default as large as the terminal allows.
+    /// For example `--x 12 --y 6 --radius 5` pins a small clock to the top left corner.
+    #[structopt(long)]
+    pub radius: Option<usize>,
+
+    /// Keep the last N frames in memory, and write them together with their diffs to a
+    /// directory when 'd' is pressed. Meant for reporting rendering bugs.
+    #[structopt(long, value_name = "N")]
+    pub debug_capture: Option<usize>,
+
+    /// Print how long the clock ran, how many countdowns and alarms went off, and how long
+    /// frames took to draw when quitting.
+    #[structopt(long)]
+    pub stats: bool,
+
+    /// Append the statistics of --stats to this file when quitting, one line per session.
+    #[structopt(long, value_name = "PATH")]
+    pub stats_file: Option<PathBuf>,
+
+    /// Show the time elapsed since this date under the dial,
+    /// for example `2024-01-15` or `2024-01-15 08:30`.
+    // The group also keeps --since and --until from being given together
+    #[structopt(long, parse(try_from_str = parse_datetime), group = "epoch")]
+    pub since: Option<NaiveDateTime>,
+
+    /// Show the time remaining until this date under the dial,
+    /// for example `2024-12-25` or `2024-12-25 18:00`.
+    #[structopt(long, parse(try_from_str = parse_datetime), group = "epoch")]
+    pub until: Option<NaiveDateTime>,
+
+    /// Label of the time shown by --since or --until, for example `days sober`.
+    #[structopt(long, requires = "epoch")]
+    pub label: Option<String>,
+
+    /// During the last 10 seconds of --until, show the seconds left in huge digits that flash
+    /// at the start of every second.
+    #[structopt(long, requires = "until")]
+    pub finale: bool,
+
+    /// Ring the terminal bell on every second of the --finale, and when the countdown is over.
+    #[structopt(long, requires = "finale")]
+    pub finale_bell: bool,
+
+    /// Text shown at the top once the countdown of --until is over, for example `We are live!`.
+    #[structopt(long, requires = "until")]
+    pub finish_banner: Option<String>,
+
+    /// Shell command run once the countdown of --until is over,
+    /// for example `notify-send 'Time is up'`.
+    #[structopt(long, requires = "until")]
+    pub finish_command: Option<String>,
+
+    /// Pulse the clock with the loudness of the microphone or line-in: the face brightens and
+    /// the second hand thickens on loud audio. Audio is recorded with parec or arecord.
+    #[cfg(feature = "audio")]
+    #[structopt(long)]
+    pub audio_pulse: bool,
+
+    /// Mark the pending taskwarrior tasks due today on the dial, colored by priority.
+    #[cfg(feature = "tasks")]
+    #[structopt(long, conflicts_with = "todo-txt")]
+    pub taskwarrior: bool,
+
+    /// Mark the tasks of this todo.txt file that are due today on the dial, colored by priority.
+    /// Only tasks with a due time, such as `due:2024-01-15T17:30`, are marked.
+    #[cfg(feature = "tasks")]
+    #[structopt(long)]
+    pub todo_txt: Option<PathBuf>,
+
+    /// Enable the mouse: drag to move the clock, scroll to resize it,
+    /// and scroll while holding Ctrl to change its width.
+    /// Hovering over the dial shows the time it points to.
+    #[structopt(long)]
+    pub mouse: bool,
+
+    /// Show the time in the title of the terminal tab or window, such as `🕒 14:32 —
+    /// analog-clock`, which is restored on exit.
+    #[structopt(long)]
+    pub set_title: bool,
+
+    /// Only print ASCII characters, for serial consoles and old terminals. Hands and labels are
+    /// drawn with |, /, - or \ following their direction, and the face with o.
+    #[structopt(long)]
+    pub ascii: bool,
+
+    /// Background color of the clock as a hex color such as `#2E3440`,
+    /// or `transparent` to keep the background of the terminal.
+    #[structopt(long, default_value = "transparent")]
+    pub background: String,
+
+    /// Which part of the screen is painted with --background.
+    /// One of inside (the dial), outside (everything but the dial) or all.
+    #[structopt(long, default_value = "inside")]
+    pub background_area: BackgroundArea,
+
+    /// Fill every empty cell with a key color such as `#00FF00`, including the background of
+    /// text, so that the clock can be keyed out when capturing the terminal in a stream.
+    /// The color is only exact with `--color-mode truecolor`.
+    #[structopt(long, value_name = "COLOR", parse(try_from_str = parse_color))]
+    pub chroma: Option<Rgb>,
+
+    /// Draw faded ghost hands at this time, for example `17:30`,
+    /// to see how far the current time is from it.
+    #[structopt(long, parse(try_from_str = parse_time))]
+    pub target: Option<NaiveTime>,
+
+    /// Draw a picture, such as a logo in a PNG file, inside the dial beneath the hands. It is
+    /// darkened or lightened so that the hands stay readable.
+    #[structopt(long, value_name = "PATH")]
+    pub face_image: Option<PathBuf>,
+
+    /// Ring the terminal bell at this time, such as `07:30`, followed by how often: once (the
+    /// default), daily, weekdays, weekends or days such as `mon,wed,fri`, as in
+    /// `--alarm "07:30 weekdays"`. Press 'z' to snooze a ringing alarm. Can be given more than
+    /// once.
+    #[structopt(long, value_name = "TIME [REPEAT]", number_of_values = 1)]
+    pub alarm: Vec<AlarmRule>,
+
+    /// Read more alarms from this file, such as `alarms = ["07:30 weekdays"]`, which is read
+    /// again whenever it changes. Defaults to `alarms.toml` next to the config file.
+    #[structopt(long, value_name = "PATH")]
+    pub alarm_file: Option<PathBuf>,
+
+    /// How many colors the terminal supports, one of auto, truecolor, 256, 16 or none.
+    /// By default it is detected from the NO_COLOR, COLORTERM and TERM environment variables.
+    #[structopt(long, default_value = "auto")]
+    pub color_mode: ColorMode,
+
+    /// Do not use colors, instead draw each hand with a different character.
+    /// Same as `--color-mode none`.
+    #[structopt(long)]
+    pub no_color: bool,
+
+    /// Shift the colors to match a night-shifted screen, either a temperature such as `3500K`,
+    /// or `auto` to follow redshift or gammastep.
+    #[structopt(long)]
+    pub color_temp: Option<ColorTemperature>,
+
+    /// Character used for drawing the hands, for example `*` or `#`.
+    #[structopt(long)]
+    pub hand_char: Option<char>,
+
+    /// Character used for drawing the circle of the dial.
+    #[structopt(long)]
+    pub face_char: Option<char>,
+
+    /// Character used for drawing the hour and minute labels.
+    #[structopt(long)]
+    pub label_char: Option<char>,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl CliOptions {
+    /// Replace the options that are not given on the command line with `defaults`, which fails
+    /// if a default is invalid.
+    pub fn apply_defaults(
+        &mut self,
+        defaults: &Defaults,
+        matches: &ArgMatches,
+    ) -> Result<(), String> {
+        let given = |name: &str| matches.occurrences_of(name) > 0;
+        if let (false, Some(theme)) = (given("theme"), &defaults.theme) {
+            self.theme = theme.clone()
+        }
+        if !given("fps") && !given("tick") {
+            self.fps = defaults.fps;
+            self.tick = defaults.tick
+        }
+        if let (false, Some(aspect_ratio)) = (given("aspect-ratio"), defaults.aspect_ratio) {
+            self.aspect_ratio = check_aspect_ratio(aspect_ratio).ok_or_else(|| {
+                invalid_aspect_ratio(&format!("{} in the config file", aspect_ratio))
+            })?
+        }
+        // A flag of the config file is overridden by its negation on the command line
+        if !self.show_second_hand {
+            self.hide_second_hand |= defaults.hide_second_hand.unwrap_or(false)
+        }
+        if !self.show_hour_labels {
+            self.hide_hour_labels |= defaults.hide_hour_labels.unwrap_or(false)
+        }
+        if !self.hide_minute_labels {
+            self.show_minute_labels |= defaults.show_minute_labels.unwrap_or(false)
+        }
+        Ok(())
+    }
+
+    /// Complications from --complication, followed by those of the flags that are shorthands
+    /// for one, which fails if two of them are in the same slot.
+    pub fn complications(&self) -> Result<Vec<PlacedComplication>, String> {
+        let shorthands = [
+            (
+                "--show-date",
+                Slot::Six,
+                self.show_date.then_some(ComplicationKind::Date),
+            ),
+            (
+                "--show-subdial",
+                Slot::Nine,
+                self.show_subdial.map(ComplicationKind::Subdial),
+            ),
+            (
+                "--show-moonphase",
+                Slot::Six,
+                self.show_moonphase.then_some(ComplicationKind::Moon),
+            ),
+        ];
+        let placed = self
+            .complication
+            .iter()
+            .map(|&placed| (format!("--complication {}", placed), placed))
+            .chain(shorthands.iter().filter_map(|&(flag, slot, kind)| {
+                Some((flag.to_string(), PlacedComplication { slot, kind: kind? }))
+            }))
+            .collect::<Vec<_>>();
+        for (index, (option, complication)) in placed.iter().enumerate() {
+            if let Some((other, _)) = placed[..index]
+                .iter()
+                .find(|(_, other)| other.slot == complication.slot)
+            {
+                return Err(format!(
+                    "Both {} and {} place a complication at {} o'clock",
+                    other,
+                    option,
+                    complication.slot.hour()
+                ));
+            }
+        }
+        Ok(placed
+            .into_iter()
+            .map(|(_, complication)| complication)
+            .collect())
+    }
+
+    /// How long a frame lasts, from --fps or the deprecated --tick, a second by default.
+    pub fn frame_interval(&self) -> Duration {
+        let interval = match (self.fps, self.tick) {
+            (Some(fps), _) => {
+                Duration::try_from_secs_f64(1.0 / fps).unwrap_or(Duration::from_secs(1))
+            }
+            (None, Some(tick)) => Duration::from_millis(tick as u64),
+            (None, None) => Duration::from_secs(1),
+        };
+        interval.max(MIN_FRAME_INTERVAL)
+    }
+}
+
+const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(1);
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Export the clock as an image file instead of drawing it on the terminal.
+    Export {
+        /// Path of the exported image.
+        /// A still image is exported as PNG, while an animation is always exported as GIF.
+        #[structopt(long, short, default_value = "clock.png")]
+        output: PathBuf,
+
+        /// Width of the exported clock in characters.
+        #[structopt(long, default_value = "80")]
+        width: usize,
+
+        /// Height of the exported clock in characters.
+        #[structopt(long, default_value = "40")]
+        height: usize,
+
+        /// Export an animation of the clock ticking instead of a still image.
+        /// Frames are rendered at --fps.
+        #[structopt(long)]
+        animate: bool,
+
+        /// Length of the animation, for example `60s`, `5m` or `500ms`.
+        #[structopt(long, default_value = "60s", parse(try_from_str = parse_duration))]
+        duration: Duration,
+    },
+
+    /// Render frames as fast as possible without printing them, and report how long rendering
+    /// takes on average.
+    Bench {
+        /// How many frames to render, at --fps.
+        #[structopt(long, default_value = "1000")]
+        frames: u32,
+
+        /// Width of the screen in characters.
+        #[structopt(long, default_value = "160")]
+        width: usize,
+
+        /// Height of the screen in characters.
+        #[structopt(long, default_value = "48")]
+        height: usize,
+    },
+
+    /// Show the clock in every theme in turn, with the name of the theme in the bottom right
+    /// corner, to help picking one.
+    Themes {
+        /// How long each theme is shown, for example `3s` or `500ms`.
+        #[structopt(long, default_value = "3s", parse(try_from_str = parse_duration))]
+        interval: Duration,
+    },
+
+    /// Stream the clock to every client that connects with telnet or nc, for example
+    /// `telnet localhost 7070`. Telnet clients get a clock the size of their window.
+    Serve {
+        /// Port to listen on.
+        #[structopt(long, default_value = "7070")]
+        port: u16,
+
+        /// Address to listen on, for example 0.0.0.0 to accept clients of other computers.
+        #[structopt(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Width of the clock in characters, for clients that do not tell the size of their
+        /// window, up to 500.
+        #[structopt(long, default_value = "80", parse(try_from_str = parse_width))]
+        width: usize,
+
+        /// Height of the clock in characters, for clients that do not tell the size of their
+        /// window, up to 200.
+        #[structopt(long, default_value = "24", parse(try_from_str = parse_height))]
+        height: usize,
+    },
+
+    /// Print a completion script for the given shell, one of bash, zsh or fish, which completes
+    /// theme names, including themes added later, for --theme.
+    Completions {
+        #[structopt(possible_values = &["bash", "zsh", "fish"])]
+        shell: Shell,
+    },
+
+    /// Print a one-line clock, such as `🕙 10:09`, for status bars like tmux and polybar.
+    Statusbar {
+        /// Keep printing a new line at --fps instead of printing once.
+        #[structopt(long)]
+        follow: bool,
+
+        /// Format of the time after the clock emoji, as in strftime.
+        #[structopt(long, default_value = "%H:%M", parse(try_from_str = parse_strftime))]
+        format: String,
+    },
+}
+
+/// Parse a size in characters such as `80x24`, up to `MAX_SIZE`.
+fn parse_size(text: &str) -> Result<(usize, usize), String> {
+    let invalid = || {
+        format!(
+            "Invalid size '{}', expected a width and a height such as 80x24, up to {}x{}",
+            text, MAX_SIZE.0, MAX_SIZE.1
+        )
+    };
+    let (width, height) = text.split_once('x').ok_or_else(invalid)?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height))
+            if (1..=MAX_SIZE.0).contains(&width) && (1..=MAX_SIZE.1).contains(&height) =>
+        {
+            Ok((width, height))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Beyond this, a frame takes too much memory to draw, let alone to save as an image.
+const MAX_SIZE: (usize, usize) = (500, 200);
+
+/// Parse a width in characters, up to that of `MAX_SIZE`.
+fn parse_width(text: &str) -> Result<usize, String> {
+    parse_length(text, "width", MAX_SIZE.0)
+}
+
+/// Parse a height in characters, up to that of `MAX_SIZE`.
+fn parse_height(text: &str) -> Result<usize, String> {
+    parse_length(text, "height", MAX_SIZE.1)
+}
+
+fn parse_length(text: &str, name: &str, max: usize) -> Result<usize, String> {
+    match text.parse() {
+        Ok(length) if (1..=max).contains(&length) => Ok(length),
+        _ => Err(format!(
+            "Invalid {} '{}', expected a number of characters from 1 to {}",
+            name, text, max
+        )),
+    }
+}
+
+/// Check that `text` is a format of strftime, such as `%H:%M`, as formatting a time with an
+/// invalid one panics.
+fn parse_strftime(text: &str) -> Result<String, String> {
+    if StrftimeItems::new(text).any(|item| item == Item::Error) {
+        Err(format!(
+            "Invalid format '{}', expected a format of strftime such as %H:%M",
+            text
+        ))
+    } else {
+        Ok(text.to_string())
+    }
+}
+
+/// Parse how many times something happens, which is at least once.
+fn parse_count(text: &str) -> Result<NonZeroU64, String> {
+    text.parse()
+        .map_err(|_| format!("Invalid count '{}', expected a number of at least 1", text))
+}
+
+/// Parse a duration such as `60s`, `5m`, `1h` or `500ms`.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (amount, unit) = text.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}'", text))?;
+    match unit {
+        "ms" => Ok(Duration::from_millis(amount)),
+        "s" | "" => Ok(Duration::from_secs(amount)),
+        "m" => Ok(Duration::from_secs(amount * 60)),
+        "h" => Ok(Duration::from_secs(amount * 60 * 60)),
+        _ => Err(format!(
+            "Invalid duration unit '{}', expected one of ms, s, m or h",
+            unit
+        )),
+    }
+}
+
+/// Parse a local date with an optional time, such as `2024-01-15`, `2024-01-15 08:30`
+/// or `2024-01-15T08:30:00`.
+pub fn parse_datetime(text: &str) -> Result<NaiveDateTime, String> {
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .map(|date| date.and_hms(0, 0, 0))
+    })
+    .ok_or_else(|| {
+        format!(
+            "Invalid date '{}', expected YYYY-MM-DD or YYYY-MM-DD HH:MM",
+            text
+        )
+    })
+}
+
+/// Parse how many times a character is taller than it is wide, such as `2.2`.
+fn parse_aspect_ratio(text: &str) -> Result<f32, String> {
+    text.parse()
+        .ok()
+        .and_then(check_aspect_ratio)
+        .ok_or_else(|| invalid_aspect_ratio(&format!("'{}'", text)))
+}
+
+/// `aspect_ratio` if it is finite and at least 1, as the dial cannot be narrower than that
+/// while running either.
+fn check_aspect_ratio(aspect_ratio: f32) -> Option<f32> {
+    (aspect_ratio.is_finite() && aspect_ratio >= 1.0).then_some(aspect_ratio)
+}
+
+fn invalid_aspect_ratio(value: &str) -> String {
+    format!(
+        "Invalid aspect ratio {}, expected a number of at least 1 such as 2.2",
+        value
+    )
+}
+
+/// Parse a number of frames per second such as `30` or `0.5`, which has to be positive.
+pub fn parse_fps(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(fps) if fps > 0.0 && fps.is_finite() => Ok(fps),
+        _ => Err(format!(
+            "Invalid frame rate '{}', expected a positive number such as 30",
+            text
+        )),
+    }
+}
+
+fn parse_supersample(text: &str) -> Result<usize, String> {
+    match text.parse::<usize>() {
+        Ok(factor) if (1..=MAX_SUPERSAMPLE).contains(&factor) => Ok(factor),
+        _ => Err(format!(
+            "Invalid supersampling factor '{}', expected a number from 1 to {}",
+            text, MAX_SUPERSAMPLE
+        )),
+    }
+}
+
+/// Beyond this, drawing takes much longer without looking any smoother.
+const MAX_SUPERSAMPLE: usize = 4;
+
+/// Parse a time of the day such as `17:30` or `17:30:15`.
+pub fn parse_time(text: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(text, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .map_err(|_| format!("Invalid time '{}', expected HH:MM or HH:MM:SS", text))
+}
+
+/// Parse an offset from UTC such as `+5:30`, `-08:00` or `+9`, between -12:00 and +14:00.
+pub fn parse_utc_offset(text: &str) -> Result<FixedOffset, String> {
+    let invalid = || {
+        format!(
+            "Invalid UTC offset '{}', expected a sign followed by hours and optional minutes, such as +5:30",
+            text
+        )
+    };
+    let (sign, rest) = match text.chars().next() {
+        Some('+') => (1, &text[1..]),
+        Some('-') => (-1, &text[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None => (rest, "0"),
+    };
+    if !(hours.chars().chain(minutes.chars())).all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if !(0..60).contains(&minutes) {
+        return Err(invalid());
+    }
+    let seconds = sign * (hours * 60 + minutes) * 60;
+    if !(-12 * 60 * 60..=14 * 60 * 60).contains(&seconds) {
+        return Err(format!(
+            "Invalid UTC offset '{}', expected an offset between -12:00 and +14:00",
+            text
+        ));
+    }
+    FixedOffset::east_opt(seconds).ok_or_else(invalid)
+}
+
+/// Parse a hex color such as `#5E81AC`.
+pub fn parse_color(text: &str) -> Result<Rgb, String> {
+    Rgb::from_hex_str(text).map_err(|_| {
+        format!(
+            "Invalid color '{}', expected a hex color such as #5E81AC",
+            text
+        )
+    })
 }
@@ -1,40 +1,690 @@
-use crate::theme::THEMES;
+mod complication;
+mod face;
 
-use bresenham::Bresenham;
-use chrono::{DateTime, Local, Timelike};
+use crate::alarm::{next_time_at_degree, AlarmRule, Schedule};
+use crate::astro::{daylight, Daylight};
+#[cfg(feature = "audio")]
+use crate::audio::AudioLevel;
+use crate::battery;
+use crate::calendar::{Month, CALENDAR_WIDTH};
+use crate::capture::FrameCapture;
+use crate::color::{darken, mix, ColorMode};
+#[cfg(unix)]
+use crate::control::{ControlCommand, ControlSocket};
+use crate::demo::{self, Step};
+use crate::font::{glyph, rolling_glyph, Bitmap, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::glyph::{Element, Glyphs};
+use crate::i18n::Lang;
+use crate::input::Input;
+use crate::keymap::{Action, Keymap};
+use crate::layout::{Layout, Pane};
+use crate::motion::{hand_angles, Movement};
+use crate::ntp::NetworkTime;
+use crate::state::{self, Adjustments, State};
+use crate::stats::Stats;
+use crate::statusbar::clock_emoji;
+#[cfg(feature = "tasks")]
+use crate::tasks::TaskMarkers;
+use crate::temperature::{self, ColorTemperature};
+use crate::theme::{is_light, Paint, Theme, ThemeSchedule};
+use crate::uptime::uptime;
+use crate::visibility::{Toggle, Visibility};
+use crate::wall::Wall;
+use crate::zone::Zone;
+pub use complication::{ComplicationKind, PlacedComplication, Slot, Subdial};
+pub use face::Face;
+
+use analog_clock_core::{
+    Change, Dial, Grid, Region, Shape, SizeMismatch, BOLD_OFFSETS, HOUR_HAND_LENGTH,
+    MINUTE_HAND_LENGTH, SECOND_HAND_LENGTH,
+};
+use chrono::{
+    Datelike, Duration as ChronoDuration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Timelike, Utc,
+};
 use colors_transform::Color;
 use colors_transform::Rgb;
-use crossterm::event::KeyEvent;
-use crossterm::event::KeyModifiers;
 use crossterm::{
     cursor,
-    event::{poll, read, Event, KeyCode},
-    style::{self, Stylize},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
+    style::{self, StyledContent, Stylize},
     terminal, ExecutableCommand, QueueableCommand, Result,
 };
-use image::{imageops::resize, ImageBuffer, Rgb as RgbPixel};
-use line_drawing::BresenhamCircle;
-use std::f32::consts::PI;
-use std::io::{stdout, Write};
+use image::{imageops, ImageBuffer, RgbImage, Rgba as RgbaPixel, RgbaImage};
+use std::cell::RefCell;
+use std::io::{stdout, IsTerminal, Write};
+use std::mem;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
 use std::process;
-use std::time::Duration;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub struct RunClockOptions {
+    /// The built-in themes and the themes of the user.
+    pub themes: Vec<Theme>,
     pub theme_index: usize,
+    /// If defined, the theme changes by the time of the day.
+    pub theme_schedule: Option<ThemeSchedule>,
+
+    /// Where the time shown by the clock comes from.
+    pub time_source: TimeSource,
+    /// If defined, the clock of this computer is corrected by network time.
+    pub network_time: Option<NetworkTime>,
 
     /// How often should the clock be redrawn.
     pub tick_interval: Duration,
+    /// How many times a character is taller than it is wide, until changed with the keyboard.
+    pub aspect_ratio: f32,
+
+    /// How the hands move as time passes.
+    pub movement: Movement,
 
     pub show_second_hand: bool,
     pub show_hour_labels: bool,
     pub show_minute_labels: bool,
+    /// Show the calendar of the month next to the dial, which `--layout full` always does.
+    pub show_calendar: bool,
+    /// Draw a ring inside the dial showing how long the clock or the machine has been running.
+    pub show_elapsed: Option<Elapsed>,
+
+    /// Language of the error messages.
+    pub lang: Lang,
+
+    /// Reserve the bottom row for the theme, timezone, frame rate and the key to quit.
+    pub status_line: bool,
+    /// Draw a tiny dial with only a sweeping second hand instead of the full clock.
+    pub mini_seconds: bool,
+
+    /// What kind of dial is drawn.
+    pub mode: Mode,
+    /// The outline of the dial, which the hour and minute labels follow.
+    pub dial_shape: Shape,
+    /// How many times larger in each direction the dial is drawn before it is shrunk to the
+    /// screen, where 1 draws it at the size of the screen.
+    pub supersample: usize,
+    /// How the 12 hour positions are drawn.
+    pub markers: Markers,
+    /// Numerals written inside the hour labels, if any.
+    pub numerals: Option<Numerals>,
+    /// Shape of the hour and minute hands.
+    pub hand_style: HandStyle,
+    /// How the time is shown in the area of the dial.
+    pub face: Face,
+
+    /// Extend the second hand through the center with a short tail.
+    pub second_tail: bool,
+
+    /// Cover the center of the dial with a filled circle.
+    pub center_cap: bool,
+    /// Blink the center of the dial every second.
+    pub blink_center: bool,
+    /// Draw a shadow under each hand.
+    pub shadow: bool,
+    /// Ring the bell on the hour, and on every quarter hour if quarterly.
+    pub chime: Option<Chime>,
+    /// Flash the outline of the dial with each bell of the chime.
+    pub chime_flash: bool,
+
+    /// Small dials drawn inside the dial, such as the date and the phase of the moon.
+    pub complications: Vec<PlacedComplication>,
+
+    /// If defined, draw a 24-hour hand showing the time in this zone.
+    pub gmt_zone: Option<Zone>,
+
+    /// If defined, shift every color to this color temperature of the screen.
+    pub color_temperature: Option<ColorTemperature>,
+
+    /// Rules that override the `show_*` options depending on the time and the size of the dial.
+    pub visibility: Visibility,
+
+    /// Latitude and longitude in degrees, needed for computing sunrise and sunset.
+    pub location: Option<(f64, f64)>,
+
+    /// If defined, show the time elapsed since or remaining until this epoch under the dial.
+    pub epoch: Option<Epoch>,
+    /// Count the last seconds of the epoch down in huge flashing digits.
+    pub finale: bool,
+    /// Ring the bell on every second of the finale.
+    pub finale_bell: bool,
+    /// Shown once the countdown is over.
+    pub finish_banner: Option<String>,
+    /// Run with `sh -c` once the countdown is over.
+    pub finish_command: Option<String>,
+
+    /// Pulse the clock with the loudness of the audio input.
+    #[cfg(feature = "audio")]
+    pub audio_pulse: bool,
+
+    /// Tasks due today are marked on the dial.
+    #[cfg(feature = "tasks")]
+    pub task_source: Option<crate::tasks::TaskSource>,
+
+    /// If defined, draw ghost hands showing this time.
+    pub target: Option<NaiveTime>,
+    /// Picture drawn inside the dial, beneath everything else on it.
+    pub face_image: Option<RgbImage>,
+    /// Alarms given on the command line.
+    pub alarms: Vec<AlarmRule>,
+    /// File with more alarms, which is read again whenever it changes.
+    pub alarm_file: Option<PathBuf>,
+
+    /// If defined, paint the background of the clock with this color, otherwise it is transparent.
+    pub background: Option<Background>,
+    /// Key color of every empty cell, see `--chroma`.
+    pub chroma: Option<Rgb>,
+
+    /// How colors are printed, depending on how many colors the terminal supports.
+    pub color_mode: ColorMode,
+
+    /// Characters used for drawing each element of the clock.
+    pub glyphs: Glyphs,
+
+    /// Capture the mouse, so that the dial can be dragged and resized.
+    pub mouse: bool,
+
+    /// Show the time in the title of the terminal, which is restored on exit.
+    pub set_title: bool,
+
+    /// Which key triggers which action.
+    pub keymap: Keymap,
+
+    /// If defined, only the quarter of the dial that fits into this corner is drawn.
+    pub quadrant: Option<Quadrant>,
+
+    /// If defined, fill the screen with a grid of clocks.
+    pub wall: Option<Wall>,
+    /// Offsets from UTC of the clocks of the wall, row by row. Clocks without one show the
+    /// usual time.
+    pub wall_zones: Vec<FixedOffset>,
+    /// How the screen is divided between the dial and other panes.
+    pub layout: Layout,
+
+    /// Clockwise rotation of the whole dial in degrees, for displays mounted sideways.
+    pub rotation: f32,
+
+    /// If defined, the column of the center of the dial, instead of the middle of the screen.
+    pub dial_x: Option<usize>,
+
+    /// If defined, the row of the center of the dial, instead of the middle of the screen.
+    pub dial_y: Option<usize>,
+
+    /// If defined, the radius of the dial in rows, instead of filling the screen.
+    pub dial_radius: Option<usize>,
+
+    /// Run a scripted tour of the features instead of showing the current time.
+    pub demo: bool,
+
+    /// If defined, accept commands such as `theme monokai` on a Unix domain socket at this path.
+    #[cfg(unix)]
+    pub control_socket: Option<PathBuf>,
+
+    /// If defined, show every theme in turn for this long, with its name in the bottom right
+    /// corner, see `analog-clock themes`.
+    pub theme_cycle: Option<Duration>,
+
+    /// Draw a smaller dial drifting across the screen, until any key is pressed.
+    pub screensaver: bool,
+
+    /// If defined, keep this many of the most recent frames, to be written to files on demand.
+    pub debug_capture: Option<usize>,
+
+    /// Print the statistics of the session when quitting.
+    pub stats: bool,
+    /// If defined, append the statistics of the session to this file when quitting.
+    pub stats_file: Option<PathBuf>,
+    /// Where the aspect ratio and zoom adjusted while running are saved when quitting.
+    pub state_file: Option<PathBuf>,
+    /// What was saved to the state file for this terminal in a previous run.
+    pub restored: Adjustments,
+}
+
+impl RunClockOptions {
+    /// Whether a complication changes within a second.
+    fn complications_animated(&self) -> bool {
+        self.complications
+            .iter()
+            .any(|placed| placed.kind.complication().animated())
+    }
+
+    fn shows_battery(&self) -> bool {
+        self.complications
+            .iter()
+            .any(|placed| placed.kind == ComplicationKind::Battery)
+    }
+
+    /// Milliseconds left in the countdown, if the finale of `--finale` is showing.
+    fn finale_millis(&self, now: NaiveDateTime) -> Option<i64> {
+        let millis = self.epoch.as_ref()?.millis_left(now)?;
+        if self.finale && millis > 0 && millis <= FINALE_SECONDS * 1000 {
+            Some(millis)
+        } else {
+            None
+        }
+    }
+
+    /// The time shown by the clock.
+    pub fn now(&self) -> NaiveDateTime {
+        let correction = self
+            .network_time
+            .as_ref()
+            .map_or_else(ChronoDuration::zero, NetworkTime::offset);
+        self.time_source.now(correction)
+    }
+}
+
+/// Where the time shown by the clock comes from.
+pub enum TimeSource {
+    /// The local time of this computer.
+    Local,
+    /// Always the same time of today.
+    Fixed(NaiveTime),
+    /// The time at a fixed offset from UTC, regardless of the timezone of this computer.
+    UtcOffset(FixedOffset),
+}
+
+impl TimeSource {
+    /// The current time, where the clock of this computer is `correction` behind.
+    pub fn now(&self, correction: ChronoDuration) -> NaiveDateTime {
+        let utc = Utc::now() + correction;
+        match self {
+            TimeSource::Local => utc.with_timezone(&Local).naive_local(),
+            TimeSource::Fixed(time) => utc
+                .with_timezone(&Local)
+                .naive_local()
+                .date()
+                .and_time(*time),
+            TimeSource::UtcOffset(offset) => utc.with_timezone(offset).naive_local(),
+        }
+    }
+
+    /// The time at `offset` from UTC, when it is `datetime` for this source.
+    fn in_zone(&self, datetime: NaiveDateTime, offset: FixedOffset) -> NaiveDateTime {
+        self.to_utc(datetime) + ChronoDuration::seconds(offset.local_minus_utc() as i64)
+    }
+
+    /// The time in UTC, when it is `datetime` for this source.
+    fn to_utc(&self, datetime: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            TimeSource::UtcOffset(source) => {
+                datetime - ChronoDuration::seconds(source.local_minus_utc() as i64)
+            }
+            TimeSource::Local | TimeSource::Fixed(_) => {
+                match Local.from_local_datetime(&datetime).earliest() {
+                    Some(local) => local.naive_utc(),
+                    None => datetime,
+                }
+            }
+        }
+    }
+}
+
+/// A moment to count the time from, or to count down to.
+pub struct Epoch {
+    pub datetime: NaiveDateTime,
+    pub direction: EpochDirection,
+    pub label: Option<String>,
+}
+
+pub enum EpochDirection {
+    Since,
+    Until,
+}
+
+impl Epoch {
+    /// Milliseconds until this epoch, if counting down to it.
+    fn millis_left(&self, now: NaiveDateTime) -> Option<i64> {
+        match self.direction {
+            EpochDirection::Until => Some((self.datetime - now).num_milliseconds()),
+            EpochDirection::Since => None,
+        }
+    }
+
+    /// Describe the time elapsed since, or remaining until, this epoch, for example `days sober: 12d 3h`.
+    fn describe(&self, now: NaiveDateTime, lang: Lang) -> String {
+        let duration = match self.direction {
+            EpochDirection::Since => now - self.datetime,
+            EpochDirection::Until => self.datetime - now,
+        }
+        .max(chrono::Duration::zero());
+        let text = lang
+            .messages()
+            .days_hours
+            .replace("{days}", &duration.num_days().to_string())
+            .replace("{hours}", &(duration.num_hours() % 24).to_string());
+        match &self.label {
+            Some(label) => format!("{}: {}", label, text),
+            None => text,
+        }
+    }
+}
+
+pub struct Background {
+    pub color: Rgb,
+    pub area: BackgroundArea,
+}
+
+/// Which part of the screen is painted with the background color.
+#[derive(Clone, Copy, Debug)]
+pub enum BackgroundArea {
+    Inside,
+    Outside,
+    All,
+}
+
+impl FromStr for BackgroundArea {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<BackgroundArea, String> {
+        match text {
+            "inside" => Ok(BackgroundArea::Inside),
+            "outside" => Ok(BackgroundArea::Outside),
+            "all" => Ok(BackgroundArea::All),
+            _ => Err(format!(
+                "Invalid background area '{}', expected one of inside, outside or all",
+                text
+            )),
+        }
+    }
+}
+
+/// A point of time highlighted on the circumference of the dial.
+#[derive(Clone, Debug)]
+pub struct Marker {
+    pub time: NaiveTime,
+    pub color: Rgb,
+}
+
+impl Marker {
+    fn degree(&self) -> f32 {
+        let hour = (self.time.hour() % 12) as f32;
+        let minute = self.time.minute() as f32;
+        (hour + minute / 60.0) / 12.0 * 360.0
+    }
+}
+
+/// What kind of dial is drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// The usual 12-hour dial.
+    Analog,
+    /// A 24-hour dial with noon at the top, shaded by daylight, with a single hand.
+    DayDial,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Mode, String> {
+        match text {
+            "analog" => Ok(Mode::Analog),
+            "daydial" => Ok(Mode::DayDial),
+            _ => Err(format!(
+                "Invalid mode '{}', expected one of analog or daydial",
+                text
+            )),
+        }
+    }
+}
+
+/// When `--chime` rings the bell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Chime {
+    /// On the hour, as many times as the hour like a grandfather clock.
+    Hourly,
+    /// On the hour, and also once, twice and three times at a quarter past, half past and a
+    /// quarter to.
+    Quarterly,
+}
+
+impl Chime {
+    /// How many bells ring at the start of `quarter`.
+    fn bells(self, quarter: NaiveDateTime) -> u32 {
+        match (self, quarter.minute() / 15) {
+            (_, 0) => match quarter.hour() % 12 {
+                0 => 12,
+                hour => hour,
+            },
+            (Chime::Quarterly, quarters) => quarters,
+            (Chime::Hourly, _) => 0,
+        }
+    }
+}
+
+impl FromStr for Chime {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Chime, String> {
+        match text {
+            "hourly" => Ok(Chime::Hourly),
+            "quarterly" => Ok(Chime::Quarterly),
+            _ => Err(format!(
+                "Invalid chime '{}', expected hourly or quarterly",
+                text
+            )),
+        }
+    }
+}
+
+/// Time between the bells of a chime, so that they can be counted.
+const BELL_INTERVAL_MILLIS: i64 = 800;
+
+/// How long a ringing alarm is put off by the snooze key.
+const SNOOZE_MINUTES: i64 = 9;
+
+/// How long the outline of the dial flashes for each bell of `--chime-flash`.
+const BELL_FLASH_MILLIS: i64 = 300;
+
+/// How the 12 hour positions are drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Markers {
+    /// A short line towards the center.
+    Lines,
+    /// A single dot.
+    Dots,
+    /// A longer, thick bar.
+    Batons,
+    /// Lines, with a doubled line at 12 o'clock.
+    Double12,
+    /// Nothing.
+    None,
+}
+
+impl FromStr for Markers {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Markers, String> {
+        match text {
+            "lines" => Ok(Markers::Lines),
+            "dots" => Ok(Markers::Dots),
+            "batons" => Ok(Markers::Batons),
+            "double-12" => Ok(Markers::Double12),
+            "none" => Ok(Markers::None),
+            _ => Err(format!(
+                "Invalid markers '{}', expected one of lines, dots, batons, double-12 or none",
+                text
+            )),
+        }
+    }
+}
+
+/// Read the picture of `--face-image`, cropped to the square in its center, and shrunk to at
+/// most `FACE_IMAGE_SIZE` pixels across, so that drawing it every frame stays cheap.
+pub fn load_face_image(path: &Path) -> std::result::Result<RgbImage, String> {
+    let image = image::open(path)
+        .map(|image| image.to_rgb8())
+        .map_err(|error| format!("Unable to read image {}: {}", path.display(), error))?;
+    let (width, height) = image.dimensions();
+    let side = width.min(height);
+    let square = imageops::crop_imm(&image, (width - side) / 2, (height - side) / 2, side, side);
+    Ok(if side > FACE_IMAGE_SIZE {
+        imageops::thumbnail(&square, FACE_IMAGE_SIZE, FACE_IMAGE_SIZE)
+    } else {
+        square.to_image()
+    })
+}
+
+/// Wider than a dial is drawn in cells on all but the largest screens.
+const FACE_IMAGE_SIZE: u32 = 512;
+
+/// What the ring of `--show-elapsed` counts from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Elapsed {
+    /// When the clock started.
+    Session,
+    /// When the machine started.
+    Uptime,
+}
+
+impl Elapsed {
+    /// How long it has been since the start, where the clock started at `started`.
+    fn since(self, started: Instant) -> Option<Duration> {
+        match self {
+            Elapsed::Session => Some(started.elapsed()),
+            Elapsed::Uptime => uptime().ok(),
+        }
+    }
+}
+
+impl FromStr for Elapsed {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Elapsed, String> {
+        match text {
+            "session" => Ok(Elapsed::Session),
+            "uptime" => Ok(Elapsed::Uptime),
+            _ => Err(format!(
+                "Invalid elapsed '{}', expected session or uptime",
+                text
+            )),
+        }
+    }
+}
+
+/// The shape of the hour and minute hands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HandStyle {
+    /// A line of the same width from end to end.
+    Plain,
+    /// A narrow shaft ending in an arrowhead.
+    Arrow,
+    /// Wide at the center, narrowing to a point at the tip.
+    Tapered,
+}
+
+impl FromStr for HandStyle {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<HandStyle, String> {
+        match text {
+            "plain" => Ok(HandStyle::Plain),
+            "arrow" => Ok(HandStyle::Arrow),
+            "tapered" => Ok(HandStyle::Tapered),
+            _ => Err(format!(
+                "Invalid hand style '{}', expected one of plain, arrow or tapered",
+                text
+            )),
+        }
+    }
+}
+
+/// The numerals written for the hours by `--numerals`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Numerals {
+    Arabic,
+    Roman,
+    Devanagari,
+    Chinese,
+}
+
+impl Numerals {
+    /// How `hour`, from 1 to 12, is written.
+    fn label(self, hour: u32) -> String {
+        match self {
+            Numerals::Arabic => hour.to_string(),
+            Numerals::Roman => [
+                "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII",
+            ][hour as usize - 1]
+                .to_string(),
+            // The digits from U+0966 are written in the same order as arabic digits
+            Numerals::Devanagari => hour
+                .to_string()
+                .chars()
+                .filter_map(|digit| digit.to_digit(10))
+                .filter_map(|digit| char::from_u32(0x0966 + digit))
+                .collect(),
+            Numerals::Chinese => [
+                "一", "二", "三", "四", "五", "六", "七", "八", "九", "十", "十一", "十二",
+            ][hour as usize - 1]
+                .to_string(),
+        }
+    }
+}
+
+impl FromStr for Numerals {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Numerals, String> {
+        match text {
+            "arabic" => Ok(Numerals::Arabic),
+            "roman" => Ok(Numerals::Roman),
+            "devanagari" => Ok(Numerals::Devanagari),
+            "chinese" => Ok(Numerals::Chinese),
+            _ => Err(format!(
+                "Invalid numerals '{}', expected one of arabic, roman, devanagari or chinese",
+                text
+            )),
+        }
+    }
+}
+
+/// A corner of the terminal.
+#[derive(Clone, Copy, Debug)]
+pub enum Quadrant {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Quadrant {
+    /// Coordinates of this corner in a matrix of the given size.
+    fn corner(self, width: f32, height: f32) -> (f32, f32) {
+        match self {
+            Quadrant::TopLeft => (0.0, 0.0),
+            Quadrant::TopRight => (width, 0.0),
+            Quadrant::BottomLeft => (0.0, height),
+            Quadrant::BottomRight => (width, height),
+        }
+    }
+}
+
+impl FromStr for Quadrant {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Quadrant, String> {
+        match text {
+            "top-left" => Ok(Quadrant::TopLeft),
+            "top-right" => Ok(Quadrant::TopRight),
+            "bottom-left" => Ok(Quadrant::BottomLeft),
+            "bottom-right" => Ok(Quadrant::BottomRight),
+            _ => Err(format!(
+                "Invalid quadrant '{}', expected one of top-left, top-right, bottom-left or bottom-right",
+                text
+            )),
+        }
+    }
 }
 
 fn new_error(message: String) -> std::io::Error {
-    std::io::Error::new(std::io::ErrorKind::Other, message.as_str())
+    std::io::Error::other(message)
 }
 
-struct UiState {
+pub(crate) struct UiState {
     /// Aspect ratio  = character_height / character_width
     ///
     /// where:
@@ -45,423 +695,3004 @@ struct UiState {
     /// This is needed to circularize the clock, otherwise it will look like an ellipse.
     aspect_ratio: f32,
     theme_index: usize,
+    /// Loudness of the audio input from 0 to 1, see `--audio-pulse`.
+    pulse: f32,
+    /// Seconds left in the countdown on the last frame, rounded up.
+    seconds_left: Option<i64>,
+    /// The theme chosen by `RunClockOptions::theme_schedule` on the last frame, so that a theme
+    /// chosen with the keyboard is kept until the next sunrise or nightfall.
+    scheduled_theme: Option<usize>,
+    /// Points of time highlighted on the dial.
+    markers: Vec<Marker>,
+    /// How far the dial is dragged away from its usual position, in characters.
+    dial_offset: (f32, f32),
+    /// Multiplier of the dial radius, changed by scrolling.
+    dial_zoom: f32,
+    /// When the dial of `--screensaver` started drifting from the top left corner.
+    drift_since: Option<NaiveDateTime>,
+    /// Clockwise rotation of the dial in degrees.
+    rotation: f32,
+    /// Where the mouse was at the last event of an ongoing drag.
+    drag_position: Option<(u16, u16)>,
+    /// Where the mouse cursor is, if it moved over the terminal.
+    hover_position: Option<(u16, u16)>,
+    /// An alarm waiting to be confirmed.
+    pending_alarm: Option<NaiveDateTime>,
+    alarms: Vec<NaiveDateTime>,
+    /// When each alarm of the schedule goes off next, within 12 hours.
+    scheduled_alarms: Vec<NaiveDateTime>,
+    /// The alarm that went off, until it is dismissed.
+    ringing_alarm: Option<NaiveDateTime>,
+    show_second_hand: bool,
+    show_hour_labels: bool,
+    show_minute_labels: bool,
+    show_calendar: bool,
+    target: Option<NaiveTime>,
+    /// Shown on the top row, such as the current step of `--demo`.
+    caption: Option<String>,
+    /// The date of the last frame, to notice when it changes.
+    date: Option<NaiveDate>,
+    /// The digits of the date rolling over after it changed.
+    date_rollover: Option<Rollover>,
+    /// The color temperature of the screen in kelvin, if colors are shifted.
+    kelvin: Option<f32>,
+    /// How charged the battery is, if a complication shows it.
+    battery: Option<f32>,
+    /// How long the clock or the machine has been running, if `--show-elapsed` shows it.
+    elapsed: Option<Duration>,
+    /// The time shown while the clock is paused.
+    paused: Option<NaiveDateTime>,
+    /// The quarter hour of the last frame, to notice when the next one starts.
+    quarter: Option<NaiveDateTime>,
+    /// Bells of the chime that have yet to ring.
+    bells_left: u32,
+    /// When the last bell of the chime rang.
+    last_bell: Option<NaiveDateTime>,
 }
 
-pub fn run_clock(options: RunClockOptions) -> Result<()> {
-    terminal::enable_raw_mode()?;
+/// Text changing from one value to another by rolling each changed character like the wheel
+/// of an odometer.
+struct Rollover {
+    from: String,
+    to: String,
+    started: NaiveDateTime,
+}
 
-    let mut stdout = stdout();
-    stdout
-        .execute(cursor::Hide)?
-        .execute(terminal::Clear(terminal::ClearType::All))?;
+/// How long a rollover takes.
+const ROLLOVER_MILLIS: i64 = 1000;
 
-    let mut state = UiState {
-        aspect_ratio: 2.0,
-        theme_index: options.theme_index,
-    };
+/// How often the screen is redrawn while something is animating, regardless of the tick.
+const ANIMATION_FRAME: Duration = Duration::from_millis(50);
+
+impl Rollover {
+    /// Progress from 0 to 1, or `None` if the rollover is over.
+    fn progress(&self, now: NaiveDateTime) -> Option<f32> {
+        let elapsed = (now - self.started).num_milliseconds();
+        if (0..ROLLOVER_MILLIS).contains(&elapsed) {
+            Some(elapsed as f32 / ROLLOVER_MILLIS as f32)
+        } else {
+            None
+        }
+    }
+
+    /// Bitmaps of each character at the given progress.
+    fn bitmaps(&self, progress: f32) -> Vec<Bitmap> {
+        let offset = (progress * (GLYPH_HEIGHT + 1) as f32) as usize;
+        self.from
+            .chars()
+            .zip(self.to.chars())
+            .map(|(from, to)| {
+                if from == to {
+                    glyph(to)
+                } else {
+                    rolling_glyph(from, to, offset)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Events of the countdown of `--until`.
+enum Countdown {
+    /// One of the last seconds has begun.
+    Second,
+    Finished,
+}
+
+/// How many of the last seconds of a countdown `--finale` shows.
+const FINALE_SECONDS: i64 = 10;
+
+/// How long the finale is inverted at the start of each second.
+const FINALE_FLASH_MILLIS: i64 = 250;
+
+/// Color of the digits of the finale while it is inverted.
+const FINALE_FLASH_COLOR: &str = "#2E3440";
+
+/// The text shown by `--show-date`.
+fn date_text(date: NaiveDate) -> String {
+    format!("{:02}", date.day())
+}
+
+impl UiState {
+    pub(crate) fn new(options: &RunClockOptions) -> UiState {
+        UiState {
+            aspect_ratio: options
+                .restored
+                .aspect_ratio
+                .unwrap_or(options.aspect_ratio),
+            theme_index: options.theme_index,
+            scheduled_theme: None,
+            seconds_left: None,
+            pulse: 0.0,
+            markers: Vec::new(),
+            dial_offset: (0.0, 0.0),
+            dial_zoom: options.restored.zoom.unwrap_or(1.0),
+            drift_since: if options.screensaver {
+                Some(options.now())
+            } else {
+                None
+            },
+            rotation: options.rotation,
+            drag_position: None,
+            hover_position: None,
+            pending_alarm: None,
+            alarms: Vec::new(),
+            scheduled_alarms: Vec::new(),
+            ringing_alarm: None,
+            show_second_hand: options.show_second_hand,
+            show_hour_labels: options.show_hour_labels,
+            show_minute_labels: options.show_minute_labels,
+            show_calendar: options.show_calendar || options.layout == Layout::Full,
+            target: options.target,
+            caption: None,
+            paused: None,
+            quarter: None,
+            bells_left: 0,
+            last_bell: None,
+            date: None,
+            date_rollover: None,
+            kelvin: options
+                .color_temperature
+                .and_then(|temperature| temperature.kelvin()),
+            battery: if options.shows_battery() {
+                battery::level()
+            } else {
+                None
+            },
+            elapsed: options
+                .show_elapsed
+                .and_then(|elapsed| elapsed.since(Instant::now())),
+        }
+    }
+
+    /// Start rolling the date over if it changed since the last frame.
+    pub(crate) fn roll_date(&mut self, now: NaiveDateTime) {
+        let date = now.date();
+        if let Some(previous) = self.date {
+            if previous != date {
+                self.date_rollover = Some(Rollover {
+                    from: date_text(previous),
+                    to: date_text(date),
+                    started: now,
+                })
+            }
+        }
+        self.date = Some(date)
+    }
+
+    /// Switch to the theme of the day or the night when it changes.
+    pub(crate) fn follow_theme_schedule(
+        &mut self,
+        schedule: Option<ThemeSchedule>,
+        now: NaiveDateTime,
+    ) {
+        if let Some(schedule) = schedule {
+            let theme_index = schedule.theme_index(now.time());
+            if self.scheduled_theme != Some(theme_index) {
+                self.theme_index = theme_index;
+                self.scheduled_theme = Some(theme_index)
+            }
+        }
+    }
+
+    /// Follow the countdown of `--until`, and return what happened since the last frame.
+    fn count_down(&mut self, options: &RunClockOptions, now: NaiveDateTime) -> Option<Countdown> {
+        let millis = options.epoch.as_ref()?.millis_left(now)?;
+        let seconds_left = (millis + 999).div_euclid(1000);
+        let previous = self.seconds_left.replace(seconds_left)?;
+        if previous == seconds_left {
+            None
+        } else if seconds_left <= 0 && previous > 0 {
+            Some(Countdown::Finished)
+        } else if seconds_left > 0 && seconds_left <= FINALE_SECONDS {
+            Some(Countdown::Second)
+        } else {
+            None
+        }
+    }
+
+    /// Whether something is animating, so that the screen has to be redrawn more often.
+    fn animating(&self, now: NaiveDateTime) -> bool {
+        self.date_rollover
+            .as_ref()
+            .is_some_and(|rollover| rollover.progress(now).is_some())
+    }
+
+    /// Switch to the theme that `analog-clock themes` shows after `elapsed`.
+    fn cycle_themes(&mut self, interval: Duration, elapsed: Duration, theme_count: usize) {
+        let shown = elapsed.as_nanos() / interval.as_nanos().max(1);
+        self.theme_index = (shown % theme_count as u128) as usize
+    }
+
+    /// Follow a command sent to the control socket, at `now` in real time.
+    #[cfg(unix)]
+    fn control(
+        &mut self,
+        command: ControlCommand,
+        options: &RunClockOptions,
+        now: NaiveDateTime,
+    ) -> std::result::Result<(), String> {
+        match command {
+            ControlCommand::Theme(name) => {
+                self.theme_index = crate::theme::find_theme(&options.themes, &name)
+                    .ok_or_else(|| format!("No theme has the name of '{}'", name))?
+            }
+            ControlCommand::Timer(duration) => self
+                .alarms
+                .push(now + ChronoDuration::from_std(duration).map_err(|error| error.to_string())?),
+            ControlCommand::Pause => self.paused = Some(self.paused.unwrap_or(now)),
+            ControlCommand::Resume => self.paused = None,
+        }
+        Ok(())
+    }
+
+    /// Show what the given step of `--demo` is about.
+    pub(crate) fn show_demo_step(&mut self, step: &Step, themes: &[Theme]) {
+        self.theme_index = step.theme_index(themes);
+        self.show_second_hand = step.show_second_hand;
+        self.show_hour_labels = step.show_hour_labels;
+        self.show_minute_labels = step.show_minute_labels;
+        self.target = step
+            .target
+            .and_then(|(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0));
+        self.caption = Some(step.caption.to_string())
+    }
+
+    /// Queue the bells of `chime` when a quarter hour starts, and return whether a bell rings
+    /// now. The bells ring one after another, rather than all at once.
+    fn chime(&mut self, chime: Chime, now: NaiveDateTime) -> bool {
+        let quarter = now.date().and_hms(now.hour(), now.minute() / 15 * 15, 0);
+        if self.quarter.is_some_and(|previous| previous != quarter) {
+            self.bells_left = chime.bells(quarter)
+        }
+        self.quarter = Some(quarter);
+        let ready = self
+            .last_bell
+            .is_none_or(|last_bell| (now - last_bell).num_milliseconds() >= BELL_INTERVAL_MILLIS);
+        if self.bells_left > 0 && ready {
+            self.bells_left -= 1;
+            self.last_bell = Some(now);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether bells of the chime are still to ring, or the last one just rang.
+    fn chiming(&self, now: NaiveDateTime) -> bool {
+        self.bells_left > 0 || self.bell_flashing(now)
+    }
+
+    /// Whether the outline of the dial is flashing for a bell that just rang.
+    fn bell_flashing(&self, now: NaiveDateTime) -> bool {
+        self.last_bell
+            .is_some_and(|last_bell| (now - last_bell).num_milliseconds() < BELL_FLASH_MILLIS)
+    }
+
+    /// Remove the alarms that are due, and return whether any went off.
+    fn ring_alarms(&mut self, now: NaiveDateTime) -> bool {
+        let (due, alarms): (Vec<_>, Vec<_>) = self.alarms.iter().partition(|alarm| **alarm <= now);
+        self.alarms = alarms;
+        match due.last() {
+            Some(alarm) => {
+                self.ringing_alarm = Some(*alarm);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Clicking the circumference of the dial asks to set an alarm there, otherwise dragging
+    /// moves the dial, scrolling resizes it, and scrolling while holding Ctrl changes the aspect
+    /// ratio.
+    ///
+    /// `dial` is the empty dial of the current frame, as created by `empty_dial`.
+    fn handle_mouse(
+        &mut self,
+        event: MouseEvent,
+        dial: &Matrix,
+        screen_width: usize,
+        now: NaiveDateTime,
+    ) {
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                match dial.point_at(event.column, event.row, screen_width) {
+                    Some((degree, distance)) if distance > 0.8 => {
+                        self.pending_alarm = Some(next_time_at_degree(degree, now))
+                    }
+                    _ => self.drag_position = Some((event.column, event.row)),
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((column, row)) = self.drag_position {
+                    self.dial_offset.0 += event.column as f32 - column as f32;
+                    self.dial_offset.1 += event.row as f32 - row as f32;
+                }
+                self.drag_position = Some((event.column, event.row))
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.drag_position = None,
+            MouseEventKind::Moved => self.hover_position = Some((event.column, event.row)),
+            MouseEventKind::ScrollUp if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.aspect_ratio += 0.1
+            }
+            MouseEventKind::ScrollDown if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.aspect_ratio = (self.aspect_ratio - 0.1).max(1.0)
+            }
+            MouseEventKind::ScrollUp => self.dial_zoom *= 1.1,
+            MouseEventKind::ScrollDown => self.dial_zoom /= 1.1,
+            _ => (),
+        }
+    }
+}
+
+/// Everything besides `UiState` that outlives a restart of the render loop.
+struct Runtime {
+    started: Instant,
+    /// Whether any frame was drawn, otherwise errors are not considered transient.
+    rendered: bool,
+    capture: Option<FrameCapture>,
+    stats: Stats,
+    input: Input,
+    schedule: Schedule,
+    #[cfg(feature = "audio")]
+    audio: Option<AudioLevel>,
+    #[cfg(feature = "tasks")]
+    task_markers: Option<TaskMarkers>,
+    #[cfg(unix)]
+    control_socket: Option<ControlSocket>,
+}
+
+/// The first delay before restarting the render loop after an error, which doubles after every
+/// consecutive failure up to `RESTART_DELAY_MAX`.
+const RESTART_DELAY_MIN: Duration = Duration::from_millis(100);
+const RESTART_DELAY_MAX: Duration = Duration::from_secs(30);
 
-    let (width, height) = term_size::dimensions()
-        .ok_or_else(|| new_error("Unable to get term size :(".to_string()))?;
-    let mut current_matrix = Matrix::new(width, height);
+/// How long the render loop has to run before its failure is no longer considered consecutive.
+const RESTART_HEALTHY_AFTER: Duration = Duration::from_secs(60);
+
+/// Run the clock until it is quit, restarting the render loop whenever it fails, so that a
+/// transient error such as a failed write does not end an unattended clock.
+pub fn run_clock(options: RunClockOptions) -> Result<()> {
+    let mut state = UiState::new(&options);
+    let mut runtime = Runtime {
+        started: Instant::now(),
+        rendered: false,
+        capture: options.debug_capture.map(FrameCapture::new),
+        stats: Stats::new(options.now()),
+        input: Input::start(),
+        schedule: Schedule::new(options.alarms.clone(), options.alarm_file.clone())
+            .map_err(new_error)?,
+        #[cfg(feature = "audio")]
+        audio: if options.audio_pulse {
+            Some(AudioLevel::start().map_err(new_error)?)
+        } else {
+            None
+        },
+        #[cfg(feature = "tasks")]
+        task_markers: match &options.task_source {
+            Some(source) => Some(TaskMarkers::start(source.clone()).map_err(new_error)?),
+            None => None,
+        },
+        #[cfg(unix)]
+        control_socket: match &options.control_socket {
+            Some(path) => Some(ControlSocket::bind(path.clone())?),
+            None => None,
+        },
+    };
+    let mut delay = RESTART_DELAY_MIN;
+    loop {
+        let session_started = Instant::now();
+        let result = run_session(&options, &mut state, &mut runtime);
+        // Errors while restoring the terminal are ignored, as the terminal may be what failed
+        let _ = restore_terminal(&options);
+        let error = match result {
+            Ok(()) => {
+                save_adjustments(&options, &state)?;
+                return report_stats(&options, &runtime.stats);
+            }
+            Err(error) if !runtime.rendered => return Err(error),
+            Err(error) => error,
+        };
+        if session_started.elapsed() >= RESTART_HEALTHY_AFTER {
+            delay = RESTART_DELAY_MIN
+        }
+        eprintln!(
+            "{}",
+            options
+                .lang
+                .messages()
+                .restarting
+                .replace("{error}", &error.to_string())
+                .replace("{seconds}", &format!("{:.1}", delay.as_secs_f32()))
+        );
+        thread::sleep(delay);
+        delay = (delay * 2).min(RESTART_DELAY_MAX);
+    }
+}
+
+/// Save the aspect ratio and zoom for this terminal if they were adjusted, where values that
+/// were adjusted back to the default are removed, so that the default applies again.
+fn save_adjustments(options: &RunClockOptions, state: &UiState) -> Result<()> {
+    let path = match &options.state_file {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let adjusted = |current: f32, restored: Option<f32>, default: f32| {
+        if current == restored.unwrap_or(default) {
+            None
+        } else if current == default {
+            Some(None)
+        } else {
+            // Steps of the keys and the scroll wheel add rounding errors
+            Some(Some((current * 1000.0).round() / 1000.0))
+        }
+    };
+    let aspect_ratio = adjusted(
+        state.aspect_ratio,
+        options.restored.aspect_ratio,
+        options.aspect_ratio,
+    );
+    let zoom = adjusted(state.dial_zoom, options.restored.zoom, 1.0);
+    if aspect_ratio.is_none() && zoom.is_none() {
+        return Ok(());
+    }
+    // Read the file again, as another clock may have saved it for another terminal meanwhile
+    let mut saved = State::load(path);
+    let terminal = state::terminal();
+    let mut adjustments = saved.terminals.get(&terminal).copied().unwrap_or_default();
+    adjustments.aspect_ratio = aspect_ratio.unwrap_or(adjustments.aspect_ratio);
+    adjustments.zoom = zoom.unwrap_or(adjustments.zoom);
+    if adjustments == Adjustments::default() {
+        saved.terminals.remove(&terminal);
+    } else {
+        saved.terminals.insert(terminal, adjustments);
+    }
+    saved.save(path).map_err(new_error)
+}
+
+/// Print the statistics of `--stats`, and append them to the file of `--stats-file`.
+fn report_stats(options: &RunClockOptions, stats: &Stats) -> Result<()> {
+    if options.stats {
+        // The screen has just been cleared, but the cursor is still where the last frame ended
+        stdout().execute(cursor::MoveTo(0, 0))?;
+        println!("{}", stats.summary(options.lang))
+    }
+    match &options.stats_file {
+        Some(path) => stats.append_to(path).map_err(new_error),
+        None => Ok(()),
+    }
+}
+
+/// The size of the terminal in characters.
+fn terminal_size(options: &RunClockOptions) -> Result<(usize, usize)> {
+    let (width, height) = terminal::size()
+        .map_err(|_| new_error(options.lang.messages().term_size_unavailable.to_string()))?;
+    Ok((width as usize, height as usize))
+}
+
+fn restore_terminal(options: &RunClockOptions) -> Result<()> {
+    let mut stdout = stdout();
+    if options.mouse {
+        stdout.execute(DisableMouseCapture)?;
+    }
+    if options.set_title {
+        stdout.execute(style::Print(POP_TITLE))?;
+    }
+    stdout
+        .execute(terminal::Clear(terminal::ClearType::All))?
+        .execute(cursor::Show)?;
+    terminal::disable_raw_mode()
+}
+
+/// Save the title of the terminal on a stack, and restore it, see XTWINOPS in
+/// https://invisible-island.net/xterm/ctlseqs/ctlseqs.html
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// The title of `--set-title`, such as `🕒 14:32 — analog-clock`.
+fn window_title(now: NaiveDateTime) -> String {
+    format!(
+        "{} {} — analog-clock",
+        clock_emoji(now.time()),
+        now.format("%H:%M")
+    )
+}
+
+/// Take over the terminal and draw the clock until it is quit or fails.
+fn run_session(
+    options: &RunClockOptions,
+    state: &mut UiState,
+    runtime: &mut Runtime,
+) -> Result<()> {
+    terminal::enable_raw_mode()?;
+
+    let mut stdout = stdout();
+    stdout
+        .execute(cursor::Hide)?
+        .execute(terminal::Clear(terminal::ClearType::All))?;
+    if options.mouse {
+        stdout.execute(EnableMouseCapture)?;
+    }
+    if options.set_title {
+        stdout.execute(style::Print(PUSH_TITLE))?;
+    }
+    // The title of the terminal, once it is set
+    let mut title: Option<String> = None;
+
+    // Afterwards the size only changes through resize events
+    let mut screen_size = terminal_size(options)?;
+    let mut current_matrix = Matrix::new(screen_size.0, screen_size.1);
+    let mut next_frame = Instant::now();
+    // Until when the frame on the screen stays the same, as its ticks are skipped
+    let mut unchanged_until: Option<Instant> = None;
+    let mut temperature_checked = Instant::now();
+    let mut battery_checked = Instant::now();
+    let mut dirty = true;
+    // When the terminal was last resized, if it has not been redrawn since
+    let mut resized: Option<Instant> = None;
+
+    loop {
+        #[cfg(unix)]
+        if let Some(socket) = &runtime.control_socket {
+            socket.serve(|command| {
+                dirty = true;
+                state.control(command, options, options.now())
+            })
+        }
+        let remaining = next_frame
+            .max(unchanged_until.unwrap_or(next_frame))
+            .saturating_duration_since(Instant::now());
+        // Nothing is drawn until the terminal stops being resized, as dragging the border of a
+        // window sends a storm of resize events
+        let settling = resized.map(|resized| RESIZE_DEBOUNCE.saturating_sub(resized.elapsed()));
+        if (!remaining.is_zero() && !dirty) || settling.is_some_and(|left| !left.is_zero()) {
+            // Wait for input until the next frame, as the input thread wakes this loop up as
+            // soon as there is any
+            let timeout = match settling {
+                Some(left) if !left.is_zero() => left,
+                _ => remaining,
+            };
+            #[cfg(unix)]
+            let timeout = match runtime.control_socket {
+                Some(_) => timeout.min(CONTROL_POLL_INTERVAL),
+                None => timeout,
+            };
+            let event = match runtime.input.next(timeout)? {
+                Some(event) => event,
+                None => continue,
+            };
+            dirty = true;
+            match event {
+                Event::Key(_) if options.screensaver => return Ok(()),
+                Event::Key(event) => match options.keymap.action(&event) {
+                    Some(Action::Wider) => state.aspect_ratio += 0.1,
+                    Some(Action::Narrower) if state.aspect_ratio > 1.0 => state.aspect_ratio -= 0.1,
+                    Some(Action::ResetWidth) => state.aspect_ratio = options.aspect_ratio,
+                    Some(Action::Rotate) => state.rotation = (state.rotation + 90.0) % 360.0,
+                    Some(Action::ToggleCalendar) => state.show_calendar = !state.show_calendar,
+                    Some(Action::ToggleSecondHand) => {
+                        state.show_second_hand = !state.show_second_hand
+                    }
+                    Some(Action::ToggleHourLabels) => {
+                        state.show_hour_labels = !state.show_hour_labels
+                    }
+                    Some(Action::ToggleMinuteLabels) => {
+                        state.show_minute_labels = !state.show_minute_labels
+                    }
+                    Some(Action::Pause) => {
+                        state.paused = match state.paused {
+                            Some(_) => None,
+                            None => Some(options.now()),
+                        }
+                    }
+                    Some(Action::Quit) => return Ok(()),
+                    Some(Action::ThemeNext) => {
+                        state.theme_index = (state.theme_index + 1) % options.themes.len()
+                    }
+                    Some(Action::ThemePrevious) => {
+                        state.theme_index = if state.theme_index == 0 {
+                            options.themes.len() - 1
+                        } else {
+                            state.theme_index - 1
+                        }
+                    }
+                    Some(Action::Confirm) => {
+                        state.alarms.extend(state.pending_alarm.take());
+                        state.ringing_alarm = None
+                    }
+                    Some(Action::Snooze) => {
+                        if state.ringing_alarm.take().is_some() {
+                            state
+                                .alarms
+                                .push(options.now() + ChronoDuration::minutes(SNOOZE_MINUTES))
+                        }
+                    }
+                    Some(Action::Cancel) => {
+                        state.pending_alarm = None;
+                        state.ringing_alarm = None;
+                        state.caption = None
+                    }
+                    Some(Action::DebugCapture) => {
+                        if let Some(capture) = &runtime.capture {
+                            let path = capture.write()?;
+                            state.caption = Some(
+                                options
+                                    .lang
+                                    .messages()
+                                    .frames_captured
+                                    .replace("{count}", &capture.frame_count().to_string())
+                                    .replace("{path}", &path.display().to_string()),
+                            )
+                        }
+                    }
+                    Some(Action::Narrower) | None => (),
+                },
+                Event::Mouse(event) => {
+                    let now = options.now();
+                    let dial = empty_dial(state, options, now, screen_size);
+                    state.handle_mouse(event, &dial, screen_size.0, now)
+                }
+                Event::Resize(width, height) => {
+                    screen_size = (width as usize, height as usize);
+                    resized = Some(Instant::now())
+                }
+            }
+            continue;
+        }
+        // Follow redshift or gammastep as they shift the screen throughout the day
+        if options.color_temperature == Some(ColorTemperature::Auto)
+            && temperature_checked.elapsed() >= TEMPERATURE_CHECK_INTERVAL
+        {
+            state.kelvin = ColorTemperature::Auto.kelvin();
+            temperature_checked = Instant::now()
+        }
+        if let Some(elapsed) = options.show_elapsed {
+            state.elapsed = elapsed.since(runtime.started)
+        }
+        if options.shows_battery() && battery_checked.elapsed() >= BATTERY_CHECK_INTERVAL {
+            state.battery = battery::level();
+            battery_checked = Instant::now()
+        }
+
+        #[cfg(feature = "tasks")]
+        if let Some(task_markers) = &runtime.task_markers {
+            state.markers = task_markers.markers()
+        }
+        #[cfg(feature = "audio")]
+        if let Some(audio) = &runtime.audio {
+            state.pulse = audio.level()
+        }
+
+        let now = if options.demo {
+            let (step, datetime) = demo::scene(runtime.started.elapsed());
+            state.show_demo_step(step, &options.themes);
+            datetime
+        } else if let Some(paused) = state.paused {
+            paused
+        } else {
+            options.now()
+        };
+        if options.set_title {
+            let new_title = window_title(now);
+            if title.as_ref() != Some(&new_title) {
+                stdout.execute(terminal::SetTitle(&new_title))?;
+                title = Some(new_title)
+            }
+        }
+        state.follow_theme_schedule(options.theme_schedule, now);
+        if let Some(interval) = options.theme_cycle {
+            state.cycle_themes(interval, runtime.started.elapsed(), options.themes.len())
+        }
+        state.roll_date(now);
+        // The bells are queued first, so that the frame where the hour starts is animating
+        if options.chime.is_some_and(|chime| state.chime(chime, now)) {
+            stdout.execute(style::Print('\u{7}'))?;
+        }
+        let animating = state.animating(now)
+            || state.pulse > 0.0
+            || (options.movement.animated() && state.show_second_hand)
+            || options.finale_millis(now).is_some()
+            || state.chiming(now)
+            || options.complications_animated()
+            || state.drift_since.is_some();
+        state.alarms.extend(runtime.schedule.due(now));
+        if let Some(error) = runtime.schedule.take_error() {
+            state.caption = Some(error)
+        }
+        state.scheduled_alarms = runtime.schedule.upcoming(now);
+        if state.ring_alarms(now) {
+            runtime.stats.alarms_fired += 1;
+            // Ring the terminal bell
+            stdout.execute(style::Print('\u{7}'))?;
+        }
+        match state.count_down(options, now) {
+            Some(Countdown::Second) if options.finale_bell => {
+                stdout.execute(style::Print('\u{7}'))?;
+            }
+            Some(Countdown::Finished) => {
+                runtime.stats.timers_completed += 1;
+                if options.finale_bell {
+                    stdout.execute(style::Print('\u{7}'))?;
+                }
+                if let Some(command) = &options.finish_command {
+                    run_finish_command(command, options.lang, state)
+                }
+            }
+            Some(Countdown::Second) | None => (),
+        }
+
+        let frame_started = Instant::now();
+        let new_matrix = draw_clock(state, options, now, screen_size);
+
+        // A resize invalidates whatever is on the screen, so the next frame is drawn in full
+        if resized.take().is_some() {
+            stdout.execute(terminal::Clear(terminal::ClearType::All))?;
+            current_matrix = Matrix::new(screen_size.0, screen_size.1)
+        }
+
+        // Print based on diff, this is to improve rendering performance
+        let diff = match current_matrix.diff(&new_matrix) {
+            Ok(diff) => diff,
+            Err(_) => {
+                stdout.execute(terminal::Clear(terminal::ClearType::All))?;
+                Matrix::new(new_matrix.width(), new_matrix.height())
+                    .diff(&new_matrix)
+                    .map_err(|error| new_error(error.to_string()))?
+            }
+        };
+
+        if let Some(capture) = &mut runtime.capture {
+            capture.record(now, &new_matrix, &diff, &options.glyphs)
+        }
+
+        Matrix::print(&mut stdout, diff, options.color_mode, &options.glyphs)?;
+        runtime.rendered = true;
+        runtime.stats.record_frame(frame_started.elapsed());
+
+        // Update current_matrix, whose buffer the next frame is drawn into
+        mem::replace(&mut current_matrix, new_matrix).recycle();
+        dirty = false;
+
+        // Frames are scheduled on absolute time, so that rendering does not make the clock drift.
+        // Redraws caused by input do not move the schedule, and missed frames are skipped.
+        let interval = if animating {
+            ANIMATION_FRAME.min(options.tick_interval)
+        } else {
+            options.tick_interval
+        };
+        // Ticks that would draw the same frame again are skipped
+        let unchanged_for = if animating {
+            None
+        } else {
+            next_visible_change(state, options, now, screen_size)
+                .and_then(|change| (change - now).to_std().ok())
+        };
+        if remaining.is_zero() {
+            next_frame += interval;
+        }
+        let now = Instant::now();
+        if next_frame <= now {
+            // Stay on the schedule by skipping to the next frame that is still ahead
+            let late = (now - next_frame).as_nanos() % interval.as_nanos();
+            next_frame = now + interval - Duration::from_nanos(late as u64)
+        }
+        // A redraw caused by input replaces it, as the input may have changed what moves next
+        unchanged_until = unchanged_for.map(|unchanged_for| now + unchanged_for)
+    }
+}
+
+/// When the frame drawn at `now` first looks different, for dials where only the hour and the
+/// minute hand move within a minute, otherwise `None`.
+///
+/// The hands are rasterized second by second until the next minute, which is much cheaper than
+/// drawing and diffing the whole frame every tick. Theme schedules, visibility rules and the
+/// alarms of the schedule only change at a whole minute, while timers and snoozed alarms can go
+/// off at any second, so the result is no later than the next of them. Countdowns to an epoch
+/// are never skipped.
+fn next_visible_change(
+    state: &UiState,
+    options: &RunClockOptions,
+    now: NaiveDateTime,
+    screen_size: (usize, usize),
+) -> Option<NaiveDateTime> {
+    let only_hands_move = !state.show_second_hand
+        && options.face == Face::Analog
+        && options.mode == Mode::Analog
+        && options.layout == Layout::Dial
+        && options.wall.is_none()
+        && options.epoch.is_none()
+        && !options.mini_seconds
+        && !options.blink_center
+        && options.show_elapsed.is_none()
+        && !options.complications_animated()
+        && !options.demo
+        && options.theme_cycle.is_none()
+        && !state.chiming(now)
+        && state.hover_position.is_none();
+    if !only_hands_move {
+        return None;
+    }
+    let matrix = empty_dial(state, options, now, clock_area(options, screen_size));
+    let (dial, height) = (&matrix.dial, matrix.height());
+    let hands = |datetime: NaiveDateTime| {
+        let angles = hand_angles(datetime, options.movement);
+        [
+            (angles.minute, MINUTE_HAND_LENGTH),
+            (angles.hour, HOUR_HAND_LENGTH),
+        ]
+        .iter()
+        .flat_map(|&(degree, length)| {
+            BOLD_OFFSETS
+                .iter()
+                .map(move |offset| dial.line(height, degree, 0.0, length, *offset))
+        })
+        .collect::<Vec<_>>()
+    };
+    let drawn = hands(now);
+    let second = now.with_nanosecond(0)?;
+    let next_minute = second.with_second(0)? + ChronoDuration::minutes(1);
+    let change = (1..)
+        .map(|seconds| second + ChronoDuration::seconds(seconds))
+        .take_while(|datetime| *datetime < next_minute)
+        .find(|datetime| hands(*datetime) != drawn)
+        .unwrap_or(next_minute);
+    Some(
+        state
+            .alarms
+            .iter()
+            .fold(change, |change, alarm| change.min(*alarm)),
+    )
+}
+
+/// Start `command` without waiting for it, showing an error in the caption if it cannot start.
+fn run_finish_command(command: &str, lang: Lang, state: &mut UiState) {
+    #[cfg(windows)]
+    let shell = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let shell = ("sh", "-c");
+    let result = process::Command::new(shell.0)
+        .arg(shell.1)
+        .arg(command)
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .spawn();
+    if let Err(error) = result {
+        state.caption = Some(
+            lang.messages()
+                .command_failed
+                .replace("{command}", command)
+                .replace("{error}", &error.to_string()),
+        )
+    }
+}
+
+/// How often redshift or gammastep is asked for the color temperature, with `--color-temp auto`.
+const TEMPERATURE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the charge of the battery is read again, if a complication shows it.
+const BATTERY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long the terminal has to keep its size after a resize before the clock is redrawn.
+const RESIZE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How often the control socket is checked for commands between frames.
+#[cfg(unix)]
+const CONTROL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Print a single frame to stdout with plain ANSI colors, without taking over the terminal.
+/// When stdout is not a terminal, the frame is 80 x 24 characters.
+pub fn print_once(options: RunClockOptions, print_hash: bool) -> Result<()> {
+    let now = if options.demo {
+        demo::scene(Duration::ZERO).1
+    } else {
+        options.now()
+    };
+    let screen_size = if stdout().is_terminal() {
+        terminal::size().map_or((80, 24), |(width, height)| {
+            (width as usize, height as usize)
+        })
+    } else {
+        (80, 24)
+    };
+    let output = if print_hash {
+        let matrix = draw_frame(&options, now, screen_size);
+        format!("{:016x}\n", matrix.signature(&options.glyphs))
+    } else {
+        render_frame(&options, now, screen_size)
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect()
+    };
+    let mut stdout = stdout();
+    stdout.write_all(output.as_bytes())?;
+    stdout.flush()
+}
+
+/// The first frame of a clock showing `datetime` on a screen of the given size, as one line
+/// per row in the colors of `options.color_mode`, without trailing empty cells.
+///
+/// Neither the clock nor the terminal is read, so the same arguments always give the same lines.
+pub(crate) fn render_frame(
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    screen_size: (usize, usize),
+) -> Vec<String> {
+    draw_frame(options, datetime, screen_size).to_lines(options.color_mode, &options.glyphs)
+}
+
+fn draw_frame(
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    screen_size: (usize, usize),
+) -> Matrix {
+    let mut state = UiState::new(options);
+    if options.demo {
+        state.show_demo_step(demo::scene(Duration::ZERO).0, &options.themes)
+    }
+    state.follow_theme_schedule(options.theme_schedule, datetime);
+    draw_clock(&state, options, datetime, screen_size)
+}
+
+/// Draw the clock every tick, on a screen of the size returned by `screen_size` before each
+/// frame, and hand each frame to `write` along with the frame before it, until `count` frames
+/// are written, if given, or writing fails.
+///
+/// Drawing is kept apart from writing, so that frames can go to a terminal, a client of
+/// `serve`, a named pipe or image files alike.
+pub(crate) fn render_frames(
+    options: &RunClockOptions,
+    count: Option<NonZeroU64>,
+    screen_size: impl Fn() -> (usize, usize),
+    mut write: impl FnMut(&Matrix, Option<&Matrix>) -> Result<()>,
+) -> Result<()> {
+    let mut state = UiState::new(options);
+    let mut previous: Option<Matrix> = None;
+    for written in 1.. {
+        let now = options.now();
+        state.follow_theme_schedule(options.theme_schedule, now);
+        state.roll_date(now);
+        let matrix = draw_clock(&state, options, now, screen_size());
+        write(&matrix, previous.as_ref())?;
+        if count.is_some_and(|count| written >= count.get()) {
+            break;
+        }
+        if let Some(matrix) = previous.replace(matrix) {
+            matrix.recycle()
+        }
+        thread::sleep(options.tick_interval)
+    }
+    Ok(())
+}
+
+/// Draw the clock to `writer` every tick, on a screen of the size returned by `screen_size`
+/// before each frame, until writing fails, such as when a client of `serve` disconnects.
+pub(crate) fn stream_frames(
+    options: &RunClockOptions,
+    writer: &mut impl Write,
+    screen_size: impl Fn() -> (usize, usize),
+) -> Result<()> {
+    writer.queue(cursor::Hide)?;
+    render_frames(options, None, screen_size, |matrix, previous| {
+        matrix.write_changes(writer, previous, options.color_mode, &options.glyphs)
+    })
+}
+
+/// Draw the clock showing the given `datetime` on a screen of `screen_width` x `height` characters.
+pub(crate) fn draw_clock(
+    state: &UiState,
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    full_screen_size: (usize, usize),
+) -> Matrix {
+    let screen_size = clock_area(options, full_screen_size);
+    let matrix = match options.wall {
+        Some(wall) => draw_wall(state, options, wall, datetime, screen_size),
+        None => {
+            let panes = options.layout.panes(screen_size, state.show_calendar);
+            match panes.as_slice() {
+                [(Pane::Dial, _)] => {
+                    options
+                        .face
+                        .renderer()
+                        .draw(state, options, datetime, screen_size)
+                }
+                _ => draw_panes(state, options, &panes, datetime, screen_size),
+            }
+        }
+    };
+
+    let matrix = if screen_size == full_screen_size {
+        matrix
+    } else {
+        draw_status_line(matrix, state, options, full_screen_size)
+    };
+
+    // Colors are shifted in a post pass, so that everything is shifted alike
+    let matrix = match state.kelvin {
+        Some(kelvin) => matrix.map_colors(|color| temperature::shift(color, kelvin)),
+        None => matrix,
+    };
+
+    // The key color is filled in last, so that it is not shifted
+    match options.chroma {
+        Some(color) => matrix.fill_chroma(color),
+        None => matrix,
+    }
+}
+
+/// The part of the screen that the clock is drawn on, which is all of it unless a row is
+/// reserved for `--status-line`.
+fn clock_area(options: &RunClockOptions, (width, height): (usize, usize)) -> (usize, usize) {
+    if options.status_line && height > 1 {
+        (width, height - 1)
+    } else {
+        (width, height)
+    }
+}
+
+/// Put `clock` on a screen of the given size with the status line on the bottom row.
+fn draw_status_line(
+    clock: Matrix,
+    state: &UiState,
+    options: &RunClockOptions,
+    (width, height): (usize, usize),
+) -> Matrix {
+    let messages = options.lang.messages();
+    let theme = options.themes[state.theme_index];
+    let zone = match options.time_source {
+        TimeSource::UtcOffset(offset) => format!("UTC{}", offset),
+        TimeSource::Local | TimeSource::Fixed(_) => messages.local_zone.to_string(),
+    };
+    let fps = 1.0 / options.tick_interval.as_secs_f64();
+    let text = messages
+        .status_line
+        .replace("{theme}", theme.name)
+        .replace("{zone}", &zone)
+        .replace("{fps}", &((fps * 10.0).round() / 10.0).to_string())
+        .replace(
+            "{quit}",
+            options.keymap.key_name(Action::Quit).unwrap_or("ctrl-c"),
+        );
+    let matrix = Matrix::new(width, height).paste(&clock, 0, 0);
+    clock.recycle();
+    matrix.draw_text(
+        0,
+        height as isize - 1,
+        &text,
+        Rgb::from_hex_str(theme.clock_face).unwrap(),
+    )
+}
+
+/// Draw a clock on every tile of `wall`. Tiles showing the same time are drawn once.
+fn draw_wall(
+    state: &UiState,
+    options: &RunClockOptions,
+    wall: Wall,
+    datetime: NaiveDateTime,
+    screen_size: (usize, usize),
+) -> Matrix {
+    let theme = options.themes[state.theme_index];
+    let tiles = wall.tiles(screen_size);
+    let mut dials: Vec<(Option<FixedOffset>, Matrix)> = Vec::new();
+    let mut matrix = Matrix::new(screen_size.0, screen_size.1);
+    for (index, tile) in tiles.iter().enumerate() {
+        let zone = options.wall_zones.get(index).copied();
+        if !dials.iter().any(|(drawn, _)| *drawn == zone) {
+            let tile_size = (tile.width, tile.height);
+            let dial = match zone {
+                Some(zone) => {
+                    let datetime = options.time_source.in_zone(datetime, zone);
+                    options
+                        .face
+                        .renderer()
+                        .draw(state, options, datetime, tile_size)
+                        .draw_centered_text(
+                            tile.height as isize - 1,
+                            &format!("UTC{}", zone),
+                            Rgb::from_hex_str(theme.clock_face).unwrap(),
+                        )
+                }
+                None => options
+                    .face
+                    .renderer()
+                    .draw(state, options, datetime, tile_size),
+            };
+            dials.push((zone, dial))
+        }
+        if let Some((_, dial)) = dials.iter().find(|(drawn, _)| *drawn == zone) {
+            matrix = matrix.paste(dial, tile.x as isize, tile.y as isize)
+        }
+    }
+    Matrix { tiles, ..matrix }
+}
+
+/// Draw every pane in its own area of the screen.
+fn draw_panes(
+    state: &UiState,
+    options: &RunClockOptions,
+    panes: &[(Pane, Region)],
+    datetime: NaiveDateTime,
+    screen_size: (usize, usize),
+) -> Matrix {
+    panes.iter().fold(
+        Matrix::new(screen_size.0, screen_size.1),
+        |matrix, (pane, region)| {
+            let size = (region.width, region.height);
+            let pane = match pane {
+                Pane::Dial => options.face.renderer().draw(state, options, datetime, size),
+                Pane::DigitalTime => draw_digital_time(state, options, datetime, size),
+                Pane::Calendar => draw_calendar(state, options, datetime.date(), size),
+            };
+            matrix.paste(&pane, region.x as isize, region.y as isize)
+        },
+    )
+}
+
+/// The time in big digits, with seconds if they fit.
+fn draw_digital_time(
+    state: &UiState,
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    (screen_width, height): (usize, usize),
+) -> Matrix {
+    let theme = options.themes[state.theme_index];
+    // Like the dial, the digits are drawn with square cells and widened afterwards
+    let width = (screen_width as f32 / state.aspect_ratio) as usize;
+    let fitting = ["%H:%M:%S", "%H:%M"].iter().find_map(|format| {
+        let bitmaps: Vec<Bitmap> = datetime
+            .format(format)
+            .to_string()
+            .chars()
+            .map(glyph)
+            .collect();
+        let glyphs_width = bitmaps.len() * (GLYPH_WIDTH + 1) - 1;
+        let scale = (width / glyphs_width).min(height.saturating_sub(2) / GLYPH_HEIGHT);
+        Some((bitmaps, glyphs_width, scale)).filter(|_| scale > 0)
+    });
+    match fitting {
+        Some((bitmaps, glyphs_width, scale)) => Matrix::new(width, height)
+            .draw_bitmaps(
+                (width.saturating_sub(glyphs_width * scale) / 2) as isize,
+                (height - GLYPH_HEIGHT * scale) as isize / 2,
+                &bitmaps,
+                scale,
+                theme.minute.color(),
+            )
+            .rescale(screen_width),
+        None => Matrix::new(screen_width, height).draw_centered_text(
+            height as isize / 2,
+            &datetime.format("%H:%M:%S").to_string(),
+            theme.minute.color(),
+        ),
+    }
+}
+
+/// The calendar of the month of `today` with today highlighted, one week per row.
+fn draw_calendar(
+    state: &UiState,
+    options: &RunClockOptions,
+    today: NaiveDate,
+    (width, height): (usize, usize),
+) -> Matrix {
+    let theme = options.themes[state.theme_index];
+    let text_color = Rgb::from_hex_str(theme.clock_face).unwrap();
+    let month = Month::of(today);
+    let weeks = month.weeks();
+    let left = (width as isize - CALENDAR_WIDTH as isize) / 2;
+    let top = (height as isize - weeks.len() as isize - 2) / 2;
+
+    let matrix =
+        Matrix::new(width, height).draw_centered_text(top, &month.title(), theme.hour.color());
+    let matrix = options.lang.messages().weekdays.iter().enumerate().fold(
+        matrix,
+        |matrix, (index, weekday)| {
+            matrix.draw_text(left + index as isize * 3, top + 1, weekday, text_color)
+        },
+    );
+    let days = weeks.iter().enumerate().flat_map(|(row, week)| {
+        week.iter()
+            .enumerate()
+            .filter_map(move |(column, day)| Some((row, column, (*day)?)))
+    });
+    days.fold(matrix, |mut matrix, (row, column, day)| {
+        let (x, y) = (left + column as isize * 3, top + 2 + row as isize);
+        let text = format!("{:>2}", day);
+        if day == today.day() {
+            for (offset, character) in text.chars().enumerate() {
+                matrix.set_cell(
+                    x + offset as isize,
+                    y,
+                    Cell {
+                        color: text_color,
+                        element: Element::Text(character),
+                        background: Some(theme.second.color()),
+                        degree: None,
+                    },
+                )
+            }
+            matrix
+        } else {
+            matrix.draw_text(x, y, &text, text_color)
+        }
+    })
+}
+
+fn draw_dial(
+    state: &UiState,
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    (screen_width, height): (usize, usize),
+) -> Matrix {
+    if options.mini_seconds {
+        return draw_mini_seconds(state, options, datetime, (screen_width, height));
+    }
+    if options.mode == Mode::DayDial {
+        return draw_day_dial(state, options, datetime, (screen_width, height));
+    }
+    let matrix = empty_dial(state, options, datetime, (screen_width, height));
+    let radius = matrix.dial.radius;
+    let matrix = matrix.upsample(options.supersample);
+    let visible = |toggle: Toggle, default: bool| {
+        options
+            .visibility
+            .visible(toggle, default, datetime.time(), radius)
+    };
+
+    let matrix = match &options.background {
+        Some(background) => matrix.fill_background(background),
+        None => matrix,
+    };
+
+    let theme = options.themes[state.theme_index];
+    // The face brightens with the loudness of the audio input
+    let face_color = mix(
+        Rgb::from(255.0, 255.0, 255.0),
+        Rgb::from_hex_str(theme.clock_face).unwrap(),
+        state.pulse,
+    );
+    let matrix = match &options.face_image {
+        Some(image) => {
+            // Without colors, hands are drawn with the densest characters, like light colors
+            let light_hands =
+                options.color_mode == ColorMode::Monochrome || is_light(theme.hour.color());
+            matrix.draw_face_image(image, light_hands)
+        }
+        None => matrix,
+    };
+    let matrix = matrix.draw_circle(face_color);
+    let matrix = if options.chime_flash && state.bell_flashing(datetime) {
+        matrix.draw_circle(theme.second.color())
+    } else {
+        matrix
+    };
+
+    if let Some(millis) = options.finale_millis(datetime) {
+        let matrix = draw_finale(matrix, state, options, millis)
+            .downsample(options.supersample)
+            .rescale(screen_width);
+        return draw_overlays(matrix, state, options, datetime, None, height);
+    }
+
+    let matrix = match state.elapsed {
+        Some(elapsed) => matrix.draw_elapsed(elapsed, theme.secondary),
+        None => matrix,
+    };
+
+    // Draw clock face: hour labels
+    let matrix = if visible(Toggle::HourLabels, state.show_hour_labels) {
+        let color = Rgb::from_hex_str(theme.clock_face).unwrap();
+        (0..12).fold(matrix, |matrix, n| {
+            let degree = (n as f32) / 12.0 * 360.0;
+            let hand = |thickness, length| Hand {
+                degree,
+                thickness,
+                length,
+                style: HandStyle::Plain,
+                line_start: HandLineStart::FromCircumference,
+                color,
+                tip_color: None,
+                element: Element::HourLabel,
+            };
+            match options.markers {
+                Markers::Lines => matrix.draw_hand(hand(HandThickness::Thin, 0.15)),
+                Markers::Dots => {
+                    let distance = matrix.dial.extent(degree) - 0.15;
+                    matrix.draw_dot(degree, distance, color, Element::HourLabel)
+                }
+                Markers::Batons => matrix.draw_hand(hand(HandThickness::Bold, 0.2)),
+                Markers::Double12 if n == 0 => matrix.draw_hand(hand(HandThickness::Double, 0.2)),
+                Markers::Double12 => matrix.draw_hand(hand(HandThickness::Thin, 0.15)),
+                Markers::None => matrix,
+            }
+        })
+    } else {
+        matrix
+    };
+
+    // Draw clock face: minute/seconds labels
+    let matrix = if visible(Toggle::MinuteLabels, state.show_minute_labels) {
+        (0..60).fold(matrix, |matrix, n| {
+            matrix.draw_hand(Hand {
+                degree: (n as f32) / 60.0 * 360.0,
+                thickness: HandThickness::Thin,
+                length: 0.05,
+                style: HandStyle::Plain,
+                line_start: HandLineStart::FromCircumference,
+                color: Rgb::from_hex_str("#4C566A").unwrap(),
+                tip_color: None,
+                element: Element::MinuteLabel,
+            })
+        })
+    } else {
+        matrix
+    };
+
+    // Draw markers, just like hour labels but in their own color
+    let alarm_markers = state
+        .alarms
+        .iter()
+        .chain(&state.scheduled_alarms)
+        .map(|alarm| Marker {
+            time: alarm.time(),
+            color: theme.second.color(),
+        });
+    let matrix =
+        state
+            .markers
+            .iter()
+            .cloned()
+            .chain(alarm_markers)
+            .fold(matrix, |matrix, marker| {
+                matrix.draw_hand(Hand {
+                    degree: marker.degree(),
+                    thickness: HandThickness::Bold,
+                    length: 0.1,
+                    style: HandStyle::Plain,
+                    line_start: HandLineStart::FromCircumference,
+                    color: marker.color,
+                    tip_color: None,
+                    element: Element::Marker,
+                })
+            });
+
+    // Complications are drawn before the hands, so that they stay beneath them. A visibility
+    // rule can show the date at 6 o'clock even if it is not a complication.
+    let shows_date = options
+        .complications
+        .iter()
+        .any(|placed| placed.kind == ComplicationKind::Date);
+    let date = PlacedComplication {
+        slot: Slot::Six,
+        kind: ComplicationKind::Date,
+    };
+    let complications = options
+        .complications
+        .iter()
+        .filter(|placed| placed.kind != ComplicationKind::Date || visible(Toggle::Date, true))
+        .chain((!shows_date && visible(Toggle::Date, false)).then_some(&date));
+    let matrix = matrix.draw_complications(complications, state, options, datetime);
+
+    let angles = hand_angles(datetime, options.movement);
+
+    // Ghost hands are drawn first, so that the actual hands are on top of them
+    let matrix = match state.target {
+        Some(target) => {
+            // Translucency is faked by mixing the hand color with the background color
+            let background = match &options.background {
+                Some(background) => background.color,
+                None => Rgb::from(0.0, 0.0, 0.0),
+            };
+            let target_hour = (target.hour() % 12) as f32;
+            let target_minute = target.minute() as f32;
+            matrix
+                .draw_hand(Hand {
+                    degree: target_minute / 60.0 * 360.0,
+                    thickness: HandThickness::Bold,
+                    length: MINUTE_HAND_LENGTH,
+                    style: options.hand_style,
+                    line_start: HandLineStart::FromCenter,
+                    color: mix(theme.minute.color(), background, GHOST_OPACITY),
+                    tip_color: theme
+                        .minute
+                        .tip_color()
+                        .map(|tip_color| mix(tip_color, background, GHOST_OPACITY)),
+                    element: Element::GhostHand,
+                })
+                .draw_hand(Hand {
+                    degree: (target_hour + target_minute / 60.0) / 12.0 * 360.0,
+                    thickness: HandThickness::Bold,
+                    length: HOUR_HAND_LENGTH,
+                    style: options.hand_style,
+                    line_start: HandLineStart::FromCenter,
+                    color: mix(theme.hour.color(), background, GHOST_OPACITY),
+                    tip_color: theme
+                        .hour
+                        .tip_color()
+                        .map(|tip_color| mix(tip_color, background, GHOST_OPACITY)),
+                    element: Element::GhostHand,
+                })
+        }
+        None => matrix,
+    };
+
+    // Firstly, draw minute hand
+    let minute_hand = Hand {
+        degree: angles.minute,
+        thickness: HandThickness::Bold,
+        length: MINUTE_HAND_LENGTH,
+        style: options.hand_style,
+        line_start: HandLineStart::FromCenter,
+        color: theme.minute.color(),
+        tip_color: theme.minute.tip_color(),
+        element: Element::MinuteHand,
+    };
+
+    // Secondly, draw hour hand, as hour hand must be on top of minute hand
+    let hour_hand = Hand {
+        degree: angles.hour,
+        thickness: HandThickness::Bold,
+        length: HOUR_HAND_LENGTH,
+        style: options.hand_style,
+        line_start: HandLineStart::FromCenter,
+        color: theme.hour.color(),
+        tip_color: theme.hour.tip_color(),
+        element: Element::HourHand,
+    };
+
+    // The 24-hour hand goes around the dial once a day
+    let gmt_hand = options.gmt_zone.as_ref().map(|zone| {
+        let utc = options.time_source.to_utc(datetime);
+        let time = options.time_source.in_zone(datetime, zone.offset_at(utc));
+        Hand {
+            degree: (time.hour() as f32 + time.minute() as f32 / 60.0) / 24.0 * 360.0,
+            thickness: HandThickness::Thin,
+            length: GMT_HAND_LENGTH,
+            style: HandStyle::Plain,
+            line_start: HandLineStart::FromCenter,
+            color: theme.gmt.color(),
+            tip_color: theme.gmt.tip_color(),
+            element: Element::GmtHand,
+        }
+    });
+
+    // Thirdly, draw second hand, which should be on top of hour hand & minute hand
+    let second_hand = if visible(Toggle::SecondHand, state.show_second_hand) {
+        Some(Hand {
+            degree: angles.second,
+            thickness: if state.pulse > PULSE_BOLD_LEVEL {
+                HandThickness::Bold
+            } else {
+                HandThickness::Thin
+            },
+            length: SECOND_HAND_LENGTH,
+            style: HandStyle::Plain,
+            line_start: if options.second_tail {
+                HandLineStart::BehindCenter(SECOND_TAIL_LENGTH)
+            } else {
+                HandLineStart::FromCenter
+            },
+            color: theme.second.color(),
+            tip_color: theme.second.tip_color(),
+            element: Element::SecondHand,
+        })
+    } else {
+        None
+    };
+
+    let hands: Vec<Hand> = vec![minute_hand, hour_hand]
+        .into_iter()
+        .chain(gmt_hand)
+        .chain(second_hand)
+        .collect();
+    // Every shadow is drawn before the hands, so that hands do not cast shadows on each other
+    let matrix = if options.shadow {
+        let color = darken(
+            Rgb::from_hex_str(theme.clock_face).unwrap(),
+            SHADOW_DARKNESS,
+        );
+        hands
+            .iter()
+            .fold(matrix, |matrix, hand| matrix.draw_shadow(hand, color))
+    } else {
+        matrix
+    };
+    let matrix = hands.into_iter().fold(matrix, Matrix::draw_hand);
+
+    // The cap covers where the hands meet
+    let matrix = if options.center_cap {
+        let radius = (matrix.dial.radius * CENTER_CAP_RADIUS).max(1.0);
+        matrix.fill_circle(radius, theme.second.color(), Element::CenterCap)
+    } else {
+        matrix
+    };
+
+    // The hub beats once a second, bright on even seconds and dim on odd seconds
+    let matrix = if options.blink_center {
+        let radius = (matrix.dial.radius * CENTER_CAP_RADIUS).max(1.0);
+        let color = if datetime.second().is_multiple_of(2) {
+            theme.second.color()
+        } else {
+            darken(theme.second.color(), BLINK_DIMNESS)
+        };
+        matrix.fill_circle(radius, color, Element::CenterCap)
+    } else {
+        matrix
+    };
+
+    let matrix = matrix.downsample(options.supersample);
+
+    // The time under the mouse cursor, mapped back to coordinates before resizing
+    let hover_readout = state.hover_position.and_then(|(column, row)| {
+        let (degree, _) = matrix.point_at(column, row, screen_width)?;
+        let minutes = (degree / 360.0 * 12.0 * 60.0).round() as u32 % (12 * 60);
+        let hour = match minutes / 60 {
+            0 => 12,
+            hour => hour,
+        };
+        Some(format!("~{}:{:02} ({:.1}°)", hour, minutes % 60, degree))
+    });
+
+    // After computing the final matrix, we have to resize it
+    let clock_width = matrix.width();
+    let matrix = matrix.rescale(screen_width);
+
+    // Numerals are text, so they are written after resizing as well
+    let matrix = match options.numerals {
+        Some(numerals) if visible(Toggle::HourLabels, state.show_hour_labels) => {
+            let color = Rgb::from_hex_str(theme.clock_face).unwrap();
+            matrix.draw_numerals(numerals, clock_width, color)
+        }
+        _ => matrix,
+    };
+
+    draw_overlays(matrix, state, options, datetime, hover_readout, height)
+}
+
+/// Draw the text on top of a clock that is already resized to the screen.
+fn draw_overlays(
+    matrix: Matrix,
+    state: &UiState,
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    hover_readout: Option<String>,
+    height: usize,
+) -> Matrix {
+    // Text has to be written after resizing, otherwise it will be stretched
+    let theme = options.themes[state.theme_index];
+    let matrix = match &options.epoch {
+        Some(epoch) => matrix.draw_centered_text(
+            height as isize - 1,
+            &epoch.describe(datetime, options.lang),
+            theme.hour.color(),
+        ),
+        None => matrix,
+    };
+    // Above the time since or until the epoch, which has the bottom row
+    let readout_row = height as isize - if options.epoch.is_some() { 2 } else { 1 };
+    let matrix = match hover_readout {
+        Some(readout) => matrix.draw_text(0, readout_row, &readout, theme.second.color()),
+        None => matrix,
+    };
+    let matrix = if options.theme_cycle.is_some() {
+        let x = matrix.width() as isize - text_width(theme.name) as isize;
+        matrix.draw_text(x, height as isize - 1, theme.name, theme.second.color())
+    } else {
+        matrix
+    };
+
+    // Messages are overlaid on the top row
+    let messages = options.lang.messages();
+    let matrix = if state.paused.is_some() {
+        let color = Rgb::from_hex_str(theme.clock_face).unwrap();
+        matrix.draw_text(0, 0, messages.paused, color)
+    } else {
+        matrix
+    };
+    let finish_banner = options.finish_banner.clone().filter(|_| {
+        options
+            .epoch
+            .as_ref()
+            .and_then(|epoch| epoch.millis_left(datetime))
+            .is_some_and(|millis| millis <= 0)
+    });
+    let message = match (state.pending_alarm, state.ringing_alarm) {
+        (_, Some(alarm)) => Some(
+            messages
+                .alarm_ringing
+                .replace("{time}", &alarm.format("%H:%M").to_string())
+                .replace(
+                    "{snooze}",
+                    options.keymap.key_name(Action::Snooze).unwrap_or("?"),
+                ),
+        ),
+        (Some(alarm), None) => Some(
+            messages
+                .confirm_alarm
+                .replace("{time}", &alarm.format("%H:%M").to_string()),
+        ),
+        (None, None) => finish_banner.or_else(|| state.caption.clone()),
+    };
+    match message {
+        Some(message) => matrix.draw_centered_text(0, &message, theme.second.color()),
+        None => matrix,
+    }
+}
+
+/// Draw the seconds left in the countdown in digits as large as the dial, which are inverted
+/// at the start of each second.
+fn draw_finale(matrix: Matrix, state: &UiState, options: &RunClockOptions, millis: i64) -> Matrix {
+    let theme = options.themes[state.theme_index];
+    let seconds_left = (millis + 999) / 1000;
+    let flash = seconds_left * 1000 - millis < FINALE_FLASH_MILLIS;
+    let (matrix, color) = if flash {
+        let background = Background {
+            color: theme.second.color(),
+            area: BackgroundArea::All,
+        };
+        (
+            matrix.fill_background(&background),
+            Rgb::from_hex_str(FINALE_FLASH_COLOR).unwrap(),
+        )
+    } else {
+        (matrix, theme.second.color())
+    };
+
+    let bitmaps: Vec<Bitmap> = seconds_left.to_string().chars().map(glyph).collect();
+    let scale = ((matrix.dial.radius * 1.2) / GLYPH_HEIGHT as f32).max(1.0) as usize;
+    let width = (bitmaps.len() * (GLYPH_WIDTH + 1) - 1) * scale;
+    let x = matrix.dial.midpoint_x - width as f32 / 2.0;
+    let y = matrix.dial.midpoint_y - (GLYPH_HEIGHT * scale) as f32 / 2.0;
+    matrix.draw_bitmaps(
+        x.round() as isize,
+        y.round() as isize,
+        &bitmaps,
+        scale,
+        color,
+    )
+}
+
+/// Length of the hand of `--gmt-hand`, relative to the radius, which reaches past the hour labels
+/// like on a pilot's watch.
+const GMT_HAND_LENGTH: f32 = 0.95;
+
+/// Length of the tail drawn by `--second-tail`, relative to the radius.
+const SECOND_TAIL_LENGTH: f32 = 0.2;
+
+/// Radius of the cap drawn by `--center-cap`, relative to the radius of the dial.
+const CENTER_CAP_RADIUS: f32 = 0.08;
+
+/// How much darker the center of `--blink-center` is on odd seconds.
+const BLINK_DIMNESS: f32 = 0.6;
+
+/// How much darker than the face the shadows of `--shadow` are.
+const SHADOW_DARKNESS: f32 = 0.6;
+
+/// Half the width of the shaft of `--hand-style arrow`, in cells.
+const ARROW_SHAFT_WIDTH: f32 = 1.0;
+
+/// Half the width of the widest part of the arrowhead of `--hand-style arrow`, in cells.
+const ARROW_HEAD_WIDTH: f32 = 2.5;
+
+/// Where the arrowhead of `--hand-style arrow` starts, relative to the length of the hand.
+const ARROW_NECK: f32 = 0.7;
+
+/// Half the width of `--hand-style tapered` at the center, in cells.
+const TAPER_WIDTH: f32 = 2.0;
+
+/// How much `--face-image` is darkened or lightened towards the opposite of the hands.
+const IMAGE_CONTRAST: f32 = 0.5;
+
+/// Thresholds of ordered dithering, from 0 to 15.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// How far inside the outline the ring of `--show-elapsed` is, relative to the distance from the
+/// center to the outline, which is just inside the hour labels.
+const ELAPSED_RING_INSET: f32 = 0.2;
+
+/// How far inside the outline the numerals of `--numerals` are, relative to the radius, which
+/// keeps them clear of the hour labels.
+const NUMERAL_INSET: f32 = 0.3;
+
+/// Opacity of the ghost hands drawn by `--target`.
+const GHOST_OPACITY: f32 = 0.35;
+
+/// Audio louder than this thickens the second hand, see `--audio-pulse`.
+const PULSE_BOLD_LEVEL: f32 = 0.5;
+
+/// An empty matrix where the dial is positioned and sized according to the options and state.
+fn empty_dial(
+    state: &UiState,
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    (screen_width, height): (usize, usize),
+) -> Matrix {
+    let clock_width = (screen_width as f32) / state.aspect_ratio;
+    let ((midpoint_x, midpoint_y), circle_radius) = match options.quadrant {
+        None => (
+            (clock_width / 2.0, height as f32 / 2.0),
+            (clock_width / 2.0).min(height as f32 / 2.0) / 1.1,
+        ),
+        Some(quadrant) => (
+            quadrant.corner(clock_width, height as f32),
+            clock_width.min(height as f32) / 1.1,
+        ),
+    };
+    // Explicit geometry takes precedence; columns are divided by the aspect ratio,
+    // because the dial is drawn before it is stretched to the screen width
+    let midpoint_x = options
+        .dial_x
+        .map_or(midpoint_x, |x| x as f32 / state.aspect_ratio);
+    let midpoint_y = options.dial_y.map_or(midpoint_y, |y| y as f32);
+    let circle_radius = options
+        .dial_radius
+        .map_or(circle_radius, |radius| radius as f32);
+    let ((midpoint_x, midpoint_y), circle_radius) = match state.drift_since {
+        Some(since) => {
+            let radius = circle_radius * SCREENSAVER_SCALE;
+            let seconds = (datetime - since).num_milliseconds().max(0) as f32 / 1000.0;
+            let distance = seconds * SCREENSAVER_SPEED;
+            (
+                (
+                    radius + bounce(distance, clock_width - radius * 2.0),
+                    radius + bounce(distance, height as f32 - radius * 2.0),
+                ),
+                radius,
+            )
+        }
+        None => ((midpoint_x, midpoint_y), circle_radius),
+    };
+    Matrix::with_dial(
+        clock_width as usize,
+        height,
+        midpoint_x + state.dial_offset.0 / state.aspect_ratio,
+        midpoint_y + state.dial_offset.1,
+        circle_radius * state.dial_zoom,
+    )
+    .rotate(state.rotation)
+    .reshape(options.dial_shape)
+}
+
+/// Size of the dial of `--screensaver`, relative to a dial filling the screen.
+const SCREENSAVER_SCALE: f32 = 0.4;
+
+/// How fast the dial of `--screensaver` drifts in each direction, in rows per second.
+const SCREENSAVER_SPEED: f32 = 1.5;
+
+/// Where something that moved `distance` from 0 is, when it bounces back and forth between 0
+/// and `range`.
+fn bounce(distance: f32, range: f32) -> f32 {
+    if range <= 0.0 {
+        return 0.0;
+    }
+    let distance = distance % (range * 2.0);
+    if distance > range {
+        range * 2.0 - distance
+    } else {
+        distance
+    }
+}
+
+/// Colors of the day and night sectors of `--mode daydial`.
+const DAY_COLOR: &str = "#ECEFF4";
+const NIGHT_COLOR: &str = "#3B4252";
+
+/// The angle of the given time on a 24-hour dial, where noon is at the top and midnight at the
+/// bottom.
+fn day_dial_degree(datetime: NaiveDateTime) -> f32 {
+    let hour = datetime.hour() as f32 + datetime.minute() as f32 / 60.0;
+    ((hour - 12.0) / 24.0 * 360.0).rem_euclid(360.0)
+}
+
+/// Draw a 24-hour dial, where the sector between sunrise and sunset is light and the rest is
+/// dark, with a single hand showing the current time.
+fn draw_day_dial(
+    state: &UiState,
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    (screen_width, height): (usize, usize),
+) -> Matrix {
+    let theme = options.themes[state.theme_index];
+    let day = Rgb::from_hex_str(DAY_COLOR).unwrap();
+    let night = Rgb::from_hex_str(NIGHT_COLOR).unwrap();
+    let (latitude, longitude) = options.location.unwrap_or((0.0, 0.0));
+
+    let matrix = empty_dial(state, options, datetime, (screen_width, height));
+    let matrix = match daylight(datetime.date(), latitude, longitude) {
+        Daylight::Normal { sunrise, sunset } => matrix
+            .fill_sector(0.0, 360.0, night, Element::Night)
+            .fill_sector(
+                day_dial_degree(sunrise),
+                day_dial_degree(sunset),
+                day,
+                Element::Day,
+            ),
+        Daylight::PolarDay => matrix.fill_sector(0.0, 360.0, day, Element::Day),
+        Daylight::PolarNight => matrix.fill_sector(0.0, 360.0, night, Element::Night),
+    };
+    let matrix = matrix.draw_circle(Rgb::from_hex_str(theme.clock_face).unwrap());
+
+    // Long labels every 6 hours, short labels for the other hours
+    let matrix = (0..24).fold(matrix, |matrix, n| {
+        matrix.draw_hand(Hand {
+            degree: (n as f32) / 24.0 * 360.0,
+            thickness: HandThickness::Thin,
+            length: if n % 6 == 0 { 0.15 } else { 0.05 },
+            style: HandStyle::Plain,
+            line_start: HandLineStart::FromCircumference,
+            color: Rgb::from_hex_str(theme.clock_face).unwrap(),
+            tip_color: None,
+            element: Element::HourLabel,
+        })
+    });
+
+    matrix
+        .draw_hand(Hand {
+            degree: day_dial_degree(datetime),
+            thickness: HandThickness::Bold,
+            length: 0.9,
+            style: HandStyle::Plain,
+            line_start: HandLineStart::FromCenter,
+            color: theme.hour.color(),
+            tip_color: theme.hour.tip_color(),
+            element: Element::HourHand,
+        })
+        .rescale(screen_width)
+}
+
+/// Size of the dial drawn by `--mini-seconds`, in characters (before aspect ratio correction).
+const MINI_DIAL_SIZE: usize = 7;
+
+/// Draw a tiny dial at the top left corner with nothing but a sweeping second hand.
+fn draw_mini_seconds(
+    state: &UiState,
+    options: &RunClockOptions,
+    datetime: NaiveDateTime,
+    (screen_width, height): (usize, usize),
+) -> Matrix {
+    let theme = options.themes[state.theme_index];
+    let midpoint = MINI_DIAL_SIZE as f32 / 2.0;
+    let second = datetime.second() as f32 + datetime.timestamp_subsec_millis() as f32 / 1000.0;
+    let dial = Matrix::with_dial(
+        MINI_DIAL_SIZE,
+        MINI_DIAL_SIZE,
+        midpoint,
+        midpoint,
+        (MINI_DIAL_SIZE / 2) as f32,
+    )
+    .rotate(state.rotation)
+    .draw_circle(Rgb::from_hex_str(theme.clock_face).unwrap())
+    .draw_hand(Hand {
+        degree: second / 60.0 * 360.0,
+        thickness: HandThickness::Thin,
+        length: 0.9,
+        style: HandStyle::Plain,
+        line_start: HandLineStart::FromCenter,
+        color: theme.second.color(),
+        tip_color: theme.second.tip_color(),
+        element: Element::SecondHand,
+    })
+    .rescale((MINI_DIAL_SIZE as f32 * state.aspect_ratio) as usize);
+
+    Matrix::new(screen_width, height).paste(&dial, 0, 0)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Cell {
+    color: Rgb,
+    element: Element,
+    /// Color behind the character, only meaningful for text.
+    background: Option<Rgb>,
+    /// Direction of the line that this cell is part of, including the rotation of the dial.
+    degree: Option<f32>,
+}
+
+impl Cell {
+    fn glyph(&self, glyphs: &Glyphs) -> char {
+        glyphs.get(self.element, self.degree)
+    }
+
+    /// Whether nothing is printed for this cell, as it is the right half of a wide character,
+    /// unless the wide character is replaced by a narrow one.
+    fn hidden(&self, glyphs: &Glyphs) -> bool {
+        self.element == Element::Covered && !glyphs.ascii_only
+    }
+}
+
+thread_local! {
+    /// Buffers of matrices that are no longer needed, such as the frame that was replaced on
+    /// the screen, which new matrices reuse instead of allocating their own every frame.
+    static SPARE_GRIDS: RefCell<Vec<Grid<Cell>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// How many spare buffers are kept, which is more than the matrices drawn in a frame.
+const MAX_SPARE_GRIDS: usize = 4;
+
+/// An empty grid of the given size, in a spare buffer if there is one.
+fn empty_grid(width: usize, height: usize) -> Grid<Cell> {
+    match SPARE_GRIDS.with(|spare| spare.borrow_mut().pop()) {
+        Some(mut grid) => {
+            grid.reset(width, height);
+            grid
+        }
+        None => Grid::new(width, height),
+    }
+}
+
+fn recycle_grid(grid: Grid<Cell>) {
+    SPARE_GRIDS.with(|spare| {
+        let mut spare = spare.borrow_mut();
+        if spare.len() < MAX_SPARE_GRIDS {
+            spare.push(grid)
+        }
+    })
+}
+
+pub(crate) struct Matrix {
+    cells: Grid<Cell>,
+    dial: Dial,
+    /// If not empty, nothing is drawn outside of these regions, so that diffs skip the rest.
+    tiles: Vec<Region>,
+    /// Width of lines in cells, so that they are as wide as usual once a matrix drawn by
+    /// `upsample` is shrunk.
+    pen: usize,
+}
+
+impl Matrix {
+    fn new(width: usize, height: usize) -> Matrix {
+        let midpoint_x = (width as f32) / 2.0;
+        let midpoint_y = (height as f32) / 2.0;
+        Matrix::with_dial(
+            width,
+            height,
+            midpoint_x,
+            midpoint_y,
+            midpoint_x.min(midpoint_y) / 1.1,
+        )
+    }
+
+    /// Create a matrix where the center of the dial is not necessarily the center of the matrix.
+    /// Anything drawn outside of the matrix will be clipped.
+    fn with_dial(
+        width: usize,
+        height: usize,
+        midpoint_x: f32,
+        midpoint_y: f32,
+        circle_radius: f32,
+    ) -> Matrix {
+        Matrix {
+            cells: empty_grid(width, height),
+            dial: Dial {
+                midpoint_x,
+                midpoint_y,
+                radius: circle_radius,
+                rotation: 0.0,
+                shape: Shape::Circle,
+            },
+            tiles: Vec::new(),
+            pen: 1,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// Replace the color of every cell, including the background of text.
+    fn map_colors(mut self, map: impl Fn(Rgb) -> Rgb) -> Matrix {
+        for cell in self.cells.cells_mut() {
+            cell.color = map(cell.color);
+            cell.background = cell.background.map(&map);
+        }
+        self
+    }
+
+    /// Rotate everything drawn on the dial clockwise by `degree`.
+    fn rotate(self, degree: f32) -> Matrix {
+        Matrix {
+            dial: Dial {
+                rotation: degree,
+                ..self.dial
+            },
+            ..self
+        }
+    }
+
+    /// Change the outline of the dial to `shape`.
+    fn reshape(self, shape: Shape) -> Matrix {
+        Matrix {
+            dial: Dial { shape, ..self.dial },
+            ..self
+        }
+    }
+
+    /// Copy every non-empty cell of `other` onto this matrix, with the top left corner of `other` at (x, y).
+    fn paste(mut self, other: &Matrix, x: isize, y: isize) -> Matrix {
+        self.cells.paste(&other.cells, x, y);
+        self
+    }
+
+    fn get_cell(&self, x: isize, y: isize) -> Option<&Cell> {
+        self.cells.get(x, y)
+    }
+
+    /// Locate the character at (column, row) of the screen on the dial of this matrix, which is
+    /// not yet resized to `screen_width`.
+    ///
+    /// Returns the angle relative to the center of the dial, in the same convention as
+    /// `Hand::degree`, and the distance from the center relative to the radius,
+    /// or `None` if the point is not on the dial.
+    fn point_at(&self, column: u16, row: u16, screen_width: usize) -> Option<(f32, f32)> {
+        let x = column as f32 * self.width() as f32 / screen_width as f32;
+        let y = row as f32;
+        let distance = self.dial.distance(x, y) / self.dial.radius;
+        if distance > 1.1 {
+            return None;
+        }
+        Some((self.dial.angle(x, y), distance))
+    }
+
+    /// Paint every cell inside or outside of the dial.
+    fn fill_background(mut self, background: &Background) -> Matrix {
+        for (x, y) in self.cells.points() {
+            let inside = self.dial.contains(x as f32, y as f32);
+            let paint = match background.area {
+                BackgroundArea::Inside => inside,
+                BackgroundArea::Outside => !inside,
+                BackgroundArea::All => true,
+            };
+            if paint {
+                self.set_cell(
+                    x as isize,
+                    y as isize,
+                    Cell {
+                        color: background.color,
+                        element: Element::Background,
+                        background: None,
+                        degree: None,
+                    },
+                )
+            }
+        }
+        self
+    }
+
+    /// Draw `image`, which is square as `load_face_image` crops it, inside the dial, scaled to
+    /// the dial. Each cell has the average color of the pixels that it covers, and its brightness is
+    /// dithered to the characters of `Element::Image`.
+    ///
+    /// So that the hands stay readable, the picture is darkened when the hands are light, and
+    /// lightened otherwise.
+    fn draw_face_image(mut self, image: &RgbImage, light_hands: bool) -> Matrix {
+        let side = image.width();
+        if side == 0 {
+            return self;
+        }
+        let diameter = self.dial.radius * 2.0;
+        // The pixels under the cell at `cell` in one direction, where the dial is at `midpoint`
+        let pixels = |cell: f32, midpoint: f32| {
+            let to_pixel = |cell: f32| {
+                (((cell - midpoint) / diameter + 0.5) * side as f32).clamp(0.0, side as f32 - 1.0)
+                    as u32
+            };
+            let (first, last) = (to_pixel(cell - 0.5), to_pixel(cell + 0.5));
+            first..last.max(first + 1)
+        };
+        for (x, y) in self.cells.points() {
+            if !self.dial.contains(x as f32, y as f32) {
+                continue;
+            }
+            let (columns, rows) = (
+                pixels(x as f32, self.dial.midpoint_x),
+                pixels(y as f32, self.dial.midpoint_y),
+            );
+            let count = (columns.len() * rows.len()) as f32;
+            let (red, green, blue) = rows
+                .flat_map(|row| columns.clone().map(move |column| (column, row)))
+                .map(|(column, row)| image.get_pixel(column, row).0)
+                .fold((0.0, 0.0, 0.0), |(r, g, b), [red, green, blue]| {
+                    (r + red as f32, g + green as f32, b + blue as f32)
+                });
+            let color = Rgb::from(red / count, green / count, blue / count);
+            let color = if light_hands {
+                darken(color, IMAGE_CONTRAST)
+            } else {
+                mix(Rgb::from(255.0, 255.0, 255.0), color, IMAGE_CONTRAST)
+            };
+            let luminance =
+                (0.2126 * color.get_red() + 0.7152 * color.get_green() + 0.0722 * color.get_blue())
+                    / 255.0;
+            // Ordered dithering spreads the levels between cells, instead of banding
+            let threshold = (BAYER[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5;
+            let level = (luminance * 4.0 + threshold).round().clamp(0.0, 4.0) as u8;
+            if level > 0 {
+                self.set_cell(
+                    x as isize,
+                    y as isize,
+                    Cell {
+                        color,
+                        element: Element::Image(level),
+                        background: None,
+                        degree: None,
+                    },
+                )
+            }
+        }
+        self
+    }
 
-    loop {
-        // Read for user input in a non-blocking manner
-        // Refer https://docs.rs/crossterm/latest/crossterm/event/index.html#examples
-        if poll(options.tick_interval)? {
-            match read()? {
-                Event::Key(event) => {
-                    // Increase width
-                    if event.code == KeyCode::Char('+') || event.code == KeyCode::Char('=') {
-                        state.aspect_ratio += 0.1;
-                    }
-                    // Decrease width
-                    else if event.code == KeyCode::Char('-') {
-                        if state.aspect_ratio > 1.0 {
-                            state.aspect_ratio -= 0.1;
-                        }
-                    }
-                    // Reset width
-                    else if event.code == KeyCode::Char('0') {
-                        state.aspect_ratio = 2.0;
-                    }
-                    // Quit
-                    else if event.code == KeyCode::Char('q')
-                        || event == KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
-                    {
-                        stdout
-                            .execute(terminal::Clear(terminal::ClearType::All))?
-                            .execute(cursor::Show)?;
-                        process::exit(0)
-                    }
-                    // Next theme
-                    else if event.code == KeyCode::Char('j') {
-                        state.theme_index = (state.theme_index + 1) % THEMES.len()
-                    }
-                    // Previous theme
-                    else if event.code == KeyCode::Char('k') {
-                        state.theme_index = if state.theme_index == 0 {
-                            THEMES.len() - 1
-                        } else {
-                            state.theme_index - 1
-                        }
+    /// Paint every empty cell and the background of text with `color`.
+    ///
+    /// Cells outside of the tiles are painted too, so the whole matrix has to be diffed,
+    /// otherwise they would keep what was printed before.
+    fn fill_chroma(mut self, color: Rgb) -> Matrix {
+        for (x, y) in self.cells.points() {
+            let (x, y) = (x as isize, y as isize);
+            let cell = match self.get_cell(x, y) {
+                None => Cell {
+                    color,
+                    element: Element::Background,
+                    background: None,
+                    degree: None,
+                },
+                Some(cell)
+                    if matches!(cell.element, Element::Text(_) | Element::Covered)
+                        && cell.background.is_none() =>
+                {
+                    Cell {
+                        background: Some(color),
+                        degree: None,
+                        ..cell.clone()
                     }
                 }
-                Event::Mouse(event) => println!("{:?}", event),
-                Event::Resize(width, height) => {
-                    stdout.execute(terminal::Clear(terminal::ClearType::All))?;
-                    current_matrix = Matrix::new(width as usize, height as usize)
-                }
-            }
+                Some(_) => continue,
+            };
+            self.set_cell(x, y, cell)
+        }
+        Matrix {
+            tiles: Vec::new(),
+            ..self
         }
-        let new_matrix = draw_clock(&state, &options);
+    }
 
-        // Print based on diff, this is to improve rendering performance
-        let diff = current_matrix.diff(&new_matrix);
+    /// Set the cell at (x, y), unless it lies outside of this matrix.
+    fn set_cell(&mut self, x: isize, y: isize, cell: Cell) {
+        self.cells.set(x, y, cell)
+    }
 
-        Matrix::print(diff)?;
+    /// Set the cells of a line passing through (x, y), which is a square as wide as the pen.
+    fn stroke_cell(&mut self, x: isize, y: isize, cell: Cell) {
+        let pen = self.pen as isize;
+        for dy in 0..pen {
+            for dx in 0..pen {
+                self.set_cell(x + dx - (pen - 1) / 2, y + dy - (pen - 1) / 2, cell.clone())
+            }
+        }
+    }
 
-        // Update current_matrix
-        current_matrix = new_matrix;
+    /// Draw the outline of the dial, which is a circle unless it is reshaped.
+    fn draw_circle(mut self, color: Rgb) -> Matrix {
+        for (x, y) in self.dial.outline(self.height()) {
+            self.stroke_cell(
+                x,
+                y,
+                Cell {
+                    color,
+                    element: Element::Face,
+                    background: None,
+                    degree: None,
+                },
+            )
+        }
+        self
     }
-}
 
-fn draw_clock(state: &UiState, options: &RunClockOptions) -> Matrix {
-    let (screen_width, height) = term_size::dimensions()
-        .ok_or_else(|| new_error("Unable to get term size :(".to_string()))
-        .unwrap();
-    let clock_width = (screen_width as f32) / state.aspect_ratio;
-    let matrix = Matrix::new(clock_width as usize, height);
-    let datetime: DateTime<Local> = Local::now();
+    /// Draw with `draw` on a smaller dial whose center is `distance` away from the center of this
+    /// dial towards `degree`, where `distance` and `radius` are relative to the radius of this
+    /// dial. Sub-dials too small to be seen are not drawn.
+    fn draw_subdial(
+        self,
+        degree: f32,
+        distance: f32,
+        radius: f32,
+        draw: impl FnOnce(Matrix) -> Matrix,
+    ) -> Matrix {
+        let dial = self.dial;
+        if dial.radius * radius < 2.0 {
+            return self;
+        }
+        let (sin, cos) = (degree + dial.rotation).to_radians().sin_cos();
+        let subdial = Dial {
+            midpoint_x: dial.midpoint_x + dial.radius * distance * sin,
+            midpoint_y: dial.midpoint_y - dial.radius * distance * cos,
+            radius: dial.radius * radius,
+            rotation: dial.rotation,
+            shape: Shape::Circle,
+        };
+        Matrix {
+            dial,
+            ..draw(Matrix {
+                dial: subdial,
+                ..self
+            })
+        }
+    }
 
-    let theme = THEMES[state.theme_index];
-    let matrix = matrix.draw_circle(Rgb::from_hex_str(theme.clock_face).unwrap());
+    /// Fill the dial like the moon at `phase` (see `moon_phase`), which is lit from the right as it
+    /// waxes and from the left as it wanes, or the other way round if `mirrored`.
+    fn fill_moon(mut self, phase: f64, mirrored: bool, lit: Rgb, dark: Rgb) -> Matrix {
+        // Where the terminator crosses the horizontal diameter, from -1 (left) to 1 (right)
+        let terminator = (phase * std::f64::consts::TAU).cos() as f32;
+        // Rounded the same way as `Dial::circle`, so that the disc fits in the circle
+        let (midpoint_x, midpoint_y, radius) = (
+            self.dial.midpoint_x.trunc(),
+            self.dial.midpoint_y.trunc(),
+            self.dial.radius.trunc(),
+        );
+        for (x, y) in self.cells.points() {
+            let dx = (x as f32 - midpoint_x) / radius;
+            let dy = (y as f32 - midpoint_y) / radius;
+            if dx * dx + dy * dy > 1.0 {
+                continue;
+            }
+            let dx = if mirrored { -dx } else { dx };
+            let half_width = (1.0 - dy * dy).sqrt();
+            let is_lit = if phase < 0.5 {
+                dx > terminator * half_width
+            } else {
+                dx < -terminator * half_width
+            };
+            let (color, element) = if is_lit {
+                (lit, Element::Complication)
+            } else {
+                (dark, Element::Night)
+            };
+            self.set_cell(
+                x as isize,
+                y as isize,
+                Cell {
+                    color,
+                    element,
+                    background: None,
+                    degree: None,
+                },
+            )
+        }
+        self
+    }
 
-    // Draw clock face: hour labels
-    let matrix = if options.show_hour_labels {
-        (0..12).into_iter().fold(matrix, |matrix, n| {
-            matrix.draw_hand(Hand {
-                degree: (n as f32) / 12.0 * 360.0,
-                thickness: HandThickness::Thin,
-                length: 0.15,
-                line_start: HandLineStart::FromCircumference,
-                color: Rgb::from_hex_str(theme.clock_face).unwrap(),
-            })
-        })
-    } else {
-        matrix
-    };
+    /// Fill the part of the dial clockwise from `from_degree` to `to_degree`.
+    fn fill_sector(
+        mut self,
+        from_degree: f32,
+        to_degree: f32,
+        color: Rgb,
+        element: Element,
+    ) -> Matrix {
+        for (x, y) in self.cells.points() {
+            if self
+                .dial
+                .sector_contains(x as f32, y as f32, from_degree, to_degree)
+            {
+                self.set_cell(
+                    x as isize,
+                    y as isize,
+                    Cell {
+                        color,
+                        element,
+                        background: None,
+                        degree: None,
+                    },
+                )
+            }
+        }
+        self
+    }
 
-    // Draw clock face: minute/seconds labels
-    let matrix = if options.show_minute_labels {
-        (0..60).into_iter().fold(matrix, |matrix, n| {
-            matrix.draw_hand(Hand {
-                degree: (n as f32) / 60.0 * 360.0,
-                thickness: HandThickness::Thin,
-                length: 0.05,
-                line_start: HandLineStart::FromCircumference,
-                color: Rgb::from_hex_str("#4C566A").unwrap(),
+    /// Draw an arc clockwise from `from_degree` to `to_degree` inside the outline, where `inset`
+    /// is how far inside, relative to the distance from the center to the outline. A gradient
+    /// fades from its first color at the start of the arc.
+    fn draw_arc(self, from_degree: f32, to_degree: f32, inset: f32, paint: Paint) -> Matrix {
+        let sweep = (to_degree - from_degree).max(0.0);
+        // Enough points that no cell along the arc is skipped
+        let count = (self.dial.radius * sweep.to_radians() * 2.0).ceil() as usize;
+        let height = self.height();
+        let points = (0..=count)
+            .map(|index| {
+                let progress = index as f32 / count.max(1) as f32;
+                let degree = from_degree + sweep * progress;
+                // Scaled by the extent, so that the arc stays parallel to the sides of a polygon
+                let distance = self.dial.extent(degree) * (1.0 - inset);
+                let (x, y) = self.dial.point(height, degree, distance, (0.0, 0.0));
+                Point {
+                    x,
+                    y,
+                    color: match paint.tip_color() {
+                        Some(tip_color) => mix(tip_color, paint.color(), progress),
+                        None => paint.color(),
+                    },
+                    element: Element::Complication,
+                    degree: None,
+                }
             })
-        })
-    } else {
-        matrix
-    };
+            .collect();
+        self.draw_using_points(points)
+    }
 
-    let millisecond = datetime.timestamp_millis() % 1000;
-    let second = datetime.second() as f32;
-    let minute = datetime.minute() as f32;
-    let hour = (datetime.hour() % 12) as f32;
+    /// Draw the ring of `--show-elapsed`, which fills up over an hour and starts again.
+    fn draw_elapsed(self, elapsed: Duration, paint: Paint) -> Matrix {
+        let progress = (elapsed.as_secs_f32() % 3600.0) / 3600.0;
+        self.draw_arc(0.0, progress * 360.0, ELAPSED_RING_INSET, paint)
+    }
 
-    let second = if options.tick_interval.as_millis() < 1000 {
-        second + (millisecond as f32) / 1000.0
-    } else {
-        second
-    };
-    let degree_second = second / 60.0 * 360.0;
-    let degree_minute = (minute + second / 60.0) / 60.0 * 360.0;
-    let degree_hour = (hour + minute / 60.0) / 12.0 * 360.0;
+    /// Draw a single cell at `distance` relative to the radius from the center, in the direction
+    /// of `degree`.
+    fn draw_dot(self, degree: f32, distance: f32, color: Rgb, element: Element) -> Matrix {
+        let (x, y) = self.dial.point(self.height(), degree, distance, (0.0, 0.0));
+        self.draw_using_points(vec![Point {
+            x,
+            y,
+            color,
+            element,
+            degree: None,
+        }])
+    }
 
-    // Firstly, draw minute hand
-    let matrix = matrix.draw_hand(Hand {
-        degree: degree_minute,
-        thickness: HandThickness::Bold,
-        length: 0.9,
-        line_start: HandLineStart::FromCenter,
-        color: Rgb::from_hex_str(theme.minute).unwrap(),
-    });
+    /// Fill a circle of the given radius around the center of the dial.
+    fn fill_circle(mut self, radius: f32, color: Rgb, element: Element) -> Matrix {
+        for (x, y) in self.cells.points() {
+            if self.dial.distance(x as f32, y as f32) <= radius {
+                self.set_cell(
+                    x as isize,
+                    y as isize,
+                    Cell {
+                        color,
+                        element,
+                        background: None,
+                        degree: None,
+                    },
+                )
+            }
+        }
+        self
+    }
 
-    // Secondly, draw hour hand, as hour hand must be on top of minute hand
-    let matrix = matrix.draw_hand(Hand {
-        degree: degree_hour,
-        thickness: HandThickness::Bold,
-        length: 0.5,
-        line_start: HandLineStart::FromCenter,
-        color: Rgb::from_hex_str(theme.hour).unwrap(),
-    });
+    /// Draw bitmap glyphs side by side with a blank column between them, where (x, y) is the top
+    /// left corner of the first glyph. Each bit of the glyphs is drawn as `scale` x `scale` cells.
+    fn draw_bitmaps(
+        mut self,
+        x: isize,
+        y: isize,
+        bitmaps: &[Bitmap],
+        scale: usize,
+        color: Rgb,
+    ) -> Matrix {
+        for (index, bitmap) in bitmaps.iter().enumerate() {
+            let left = x + (index * (GLYPH_WIDTH + 1) * scale) as isize;
+            for (row, bits) in bitmap.iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                        for (dx, dy) in (0..scale).flat_map(|dx| (0..scale).map(move |dy| (dx, dy)))
+                        {
+                            self.set_cell(
+                                left + (column * scale + dx) as isize,
+                                y + (row * scale + dy) as isize,
+                                Cell {
+                                    color,
+                                    element: Element::Complication,
+                                    background: None,
+                                    degree: None,
+                                },
+                            )
+                        }
+                    }
+                }
+            }
+        }
+        self
+    }
 
-    // Thirdly, draw second hand, which should be on top of hour hand & minute hand
-    let matrix = if options.show_second_hand {
-        matrix.draw_hand(Hand {
-            degree: degree_second,
+    /// Draw a hand originated from the center in its style.
+    fn draw_hand(self, hand: Hand) -> Matrix {
+        let pen = self.pen as f32;
+        // Half widths of the outline along the hand, from its start to its end, in cells
+        let widths = match hand.style {
+            HandStyle::Plain => return self.draw_lines(hand),
+            HandStyle::Arrow => vec![
+                (0.0, ARROW_SHAFT_WIDTH * pen),
+                (ARROW_NECK, ARROW_SHAFT_WIDTH * pen),
+                (ARROW_NECK, ARROW_HEAD_WIDTH * pen),
+                (1.0, 0.0),
+            ],
+            HandStyle::Tapered => vec![(0.0, TAPER_WIDTH * pen), (1.0, 0.0)],
+        };
+        // The outline may be too narrow near the tip to cover any cell, so a line runs through it
+        self.fill_outline(&hand, &widths).draw_lines(Hand {
             thickness: HandThickness::Thin,
-            length: 0.9,
-            line_start: HandLineStart::FromCenter,
-            color: Rgb::from_hex_str(theme.second).unwrap(),
+            ..hand
         })
-    } else {
-        matrix
-    };
-
-    // After computing the final matrix, we have to resize it
-    matrix.rescale(screen_width)
-}
-
-#[derive(Clone, Debug, PartialEq)]
-struct Cell {
-    color: Rgb,
-}
-
-struct Matrix {
-    cells: Vec<Vec<Option<Cell>>>,
-    width: usize,
-    height: usize,
-    midpoint_x: f32,
-    midpoint_y: f32,
-    circle_radius: f32,
-}
+    }
 
-impl Matrix {
-    fn new(width: usize, height: usize) -> Matrix {
-        let midpoint_x = (width as f32) / 2.0;
-        let midpoint_y = (height as f32) / 2.0;
-        Matrix {
-            cells: vec![vec![None; width as usize]; height],
-            width: width as usize,
-            height,
-            midpoint_x,
-            midpoint_y,
-            circle_radius: midpoint_x.min(midpoint_y) / 1.1,
+    /// Where `hand` starts and ends, relative to the radius from the center.
+    fn hand_span(&self, hand: &Hand) -> (f32, f32) {
+        match hand.line_start {
+            HandLineStart::FromCenter => (0.0, hand.length),
+            HandLineStart::FromCircumference => {
+                let extent = self.dial.extent(hand.degree);
+                (extent - hand.length, extent)
+            }
+            HandLineStart::BehindCenter(tail) => (-tail, hand.length),
         }
     }
 
-    fn draw_circle(mut self, color: Rgb) -> Matrix {
-        let points = BresenhamCircle::new(
-            self.midpoint_x as i32,
-            self.midpoint_y as i32,
-            self.circle_radius as i32,
-        );
-        for (x, y) in points {
-            self.cells[y as usize][x as usize] = Some(Cell { color })
+    /// Fill the outline of `hand`, given by how wide it is at points along it, where 0 is its
+    /// start and 1 is its end. A cell is filled when its center lies within the outline.
+    fn fill_outline(mut self, hand: &Hand, widths: &[(f32, f32)]) -> Matrix {
+        let (start, end) = self.hand_span(hand);
+        let radian = (hand.degree + self.dial.rotation).to_radians();
+        // Rows grow downwards, so north is negative y
+        let (along, across) = ((radian.sin(), -radian.cos()), (radian.cos(), radian.sin()));
+        let (midpoint_x, midpoint_y) = (self.dial.midpoint_x, self.dial.midpoint_y);
+        let corner = |(position, width): (f32, f32), side: f32| {
+            let distance = self.dial.radius * (start + (end - start) * position);
+            (
+                midpoint_x + distance * along.0 + side * width * across.0,
+                midpoint_y + distance * along.1 + side * width * across.1,
+            )
+        };
+        let outline: Vec<(f32, f32)> = widths
+            .iter()
+            .map(|&width| corner(width, 1.0))
+            .chain(widths.iter().rev().map(|&width| corner(width, -1.0)))
+            .collect();
+        let length = (self.dial.radius * (end - start)).max(1.0);
+        for (x, y) in self.cells.points() {
+            // Lines round points up to the next row, see `Dial::line`, so row y covers y - 1 to y
+            let (center_x, center_y) = (x as f32 + 0.5, y as f32 - 0.5);
+            if !polygon_contains(&outline, center_x, center_y) {
+                continue;
+            }
+            let color = match hand.tip_color {
+                Some(tip_color) => {
+                    let distance =
+                        (center_x - midpoint_x) * along.0 + (center_y - midpoint_y) * along.1;
+                    let progress = (distance - self.dial.radius * start) / length;
+                    mix(tip_color, hand.color, progress.clamp(0.0, 1.0))
+                }
+                None => hand.color,
+            };
+            self.set_cell(
+                x as isize,
+                y as isize,
+                Cell {
+                    color,
+                    element: hand.element,
+                    background: None,
+                    degree: Some(hand.degree + self.dial.rotation),
+                },
+            )
         }
         self
     }
 
-    /// Draw a line originated from the center.
-    /// We will be using [Bresenham Line Algorithm](https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm#History).
-    fn draw_hand(self, hand: Hand) -> Matrix {
-        let degree = hand.degree;
-        let radian = PI / 2.0 - (degree).to_radians();
-        let radius = self.circle_radius;
-
-        let origins = {
-            let x = self.midpoint_x;
-            let y = self.midpoint_y;
-
-            match hand.thickness {
-                HandThickness::Thin => vec![(x, y)],
-                HandThickness::Bold => vec![
-                    (x - 1.0, y + 1.0), // top left
-                    (x, y + 1.0),       // top
-                    (x + 1.0, y + 1.0), // top right
-                    (x - 1.0, y),       // left
-                    (x, y),             // center
-                    (x + 1.0, y),       // right
-                    (x - 1.0, y - 1.0), // top left
-                    (x, y - 1.0),       // top
-                    (x + 1.0, y - 1.0), // top right
-                ],
-            }
+    /// Draw a hand as lines originated from the center, side by side for its thickness.
+    fn draw_lines(self, hand: Hand) -> Matrix {
+        let offsets = match hand.thickness {
+            HandThickness::Thin => vec![(0.0, 0.0)],
+            HandThickness::Bold => BOLD_OFFSETS.to_vec(),
+            HandThickness::Double => vec![(-1.0, 0.0), (1.0, 0.0)],
         };
+        let pen = self.pen as f32;
+        let (start, end) = self.hand_span(&hand);
 
-        origins
-            .into_iter()
-            .fold(self, |matrix, (midpoint_x, midpoint_y)| {
-                // We treat radius as the hypotenuse
-                // Trigonometry hints:
-                // Adjacent = Hypotenuse * cos theta
-                // Opposite = Hypotenuse * sin theta
-
-                let get_point = |hypotenuse: f32| -> (isize, isize) {
-                    let x = midpoint_x + hypotenuse * radian.cos();
-                    let y = midpoint_y + hypotenuse * radian.sin();
-                    (x as isize, y as isize)
-                };
-
-                // Calculate startpoint based on line_start
-                let startpoint = match hand.line_start {
-                    HandLineStart::FromCenter => get_point(0.0),
-                    HandLineStart::FromCircumference => get_point(radius * (1.0 - hand.length)),
-                };
-
-                // Calculate endpoint based on line_start
-                let endpoint = match hand.line_start {
-                    HandLineStart::FromCenter => get_point(radius * hand.length),
-                    HandLineStart::FromCircumference => get_point(radius),
-                };
-
-                let points = Bresenham::new(startpoint, endpoint)
-                    .map(|(x, y)| Point {
-                        x,
-
-                        // We have to invert y because the result returned by Bresenham is based on Cartesian plane
-                        // where (0, 0) is at the bottom left corner.
-                        // However for our matrix, (0, 0) is at the top left corner, which is like the Cartesian
-                        // plane flip around the x-axis.
-                        y: matrix.height as isize - y,
-                        color: hand.color,
-                    })
-                    .collect();
+        offsets.into_iter().fold(self, |matrix, (x, y)| {
+            let line =
+                matrix
+                    .dial
+                    .line(matrix.height(), hand.degree, start, end, (x * pen, y * pen));
+            let length = line.len().max(2);
+            let points = line
+                .into_iter()
+                .enumerate()
+                .map(|(index, (x, y))| Point {
+                    x,
+                    y,
+                    color: match hand.tip_color {
+                        Some(tip_color) => {
+                            mix(tip_color, hand.color, index as f32 / (length - 1) as f32)
+                        }
+                        None => hand.color,
+                    },
+                    element: hand.element,
+                    degree: Some(hand.degree + matrix.dial.rotation),
+                })
+                .collect();
 
-                matrix.draw_using_points(points)
-            })
+            matrix.draw_using_points(points)
+        })
     }
 
-    /// Apply vertical/horizontal scaling to the given matrix,
-    /// such that the clock will look like a circle instead of an ellipse.
-    /// This is because each "pixel" (or character) on a terminal is not square-ish, but a
-    /// vertical rectangle instead.
-    fn rescale(self, screen_width: usize) -> Matrix {
-        let img = matrix_to_luma_image_buffer(&self);
-        let img = resize(
-            &img,
-            screen_width as u32,
-            self.height as u32,
-            image::imageops::FilterType::Nearest,
-        );
+    /// Draw the shadow of `hand` in `color`, one cell to the right of and below the hand.
+    fn draw_shadow(self, hand: &Hand, color: Rgb) -> Matrix {
+        let dial = self.dial;
+        let offset = self.pen as f32;
+        let shifted = Matrix {
+            dial: Dial {
+                midpoint_x: dial.midpoint_x + offset,
+                midpoint_y: dial.midpoint_y + offset,
+                ..dial
+            },
+            ..self
+        };
+        let matrix = shifted.draw_hand(Hand {
+            color,
+            tip_color: None,
+            element: Element::Shadow,
+            ..*hand
+        });
+        Matrix { dial, ..matrix }
+    }
+
+    /// An empty matrix `factor` times larger in each direction with the dial scaled to match,
+    /// to be drawn on and shrunk back with `downsample`.
+    fn upsample(mut self, factor: usize) -> Matrix {
+        if factor <= 1 {
+            return self;
+        }
+        let cells = empty_grid(self.width() * factor, self.height() * factor);
+        recycle_grid(mem::replace(&mut self.cells, cells));
         Matrix {
-            cells: luma_image_buffer_to_matrix(img),
-            width: screen_width,
+            dial: self.dial.scale(factor as f32),
+            pen: factor,
             ..self
         }
     }
 
-    /// Compute the diff between two matrices.
-    /// This is for reducing unnecessary re-renders.
-    fn diff(&self, new: &Matrix) -> Vec<DiffUpdate> {
-        let old = self;
-        if old.width != new.width {
-            panic!(
-                "Diff error: old width != new width, old.width = {}, new.width = {}",
-                old.width, new.width
-            )
+    /// Shrink a matrix drawn by `upsample` back by `factor`. Each cell shows the front-most
+    /// element of the cells that it covers in their average color, or a shade when they cover
+    /// only part of it.
+    fn downsample(mut self, factor: usize) -> Matrix {
+        if factor <= 1 {
+            return self;
         }
-        if old.height != new.height {
-            panic!(
-                "Diff error: old height != new height, old.height = {}, new.height = {}",
-                old.height, new.height
-            )
+        let cells = self.cells.downsample(factor, |block, size| {
+            let front = block.iter().fold(block[0], |front, cell| {
+                if cell.element > front.element {
+                    cell
+                } else {
+                    front
+                }
+            });
+            let coverage = block.len() as f32 / size as f32;
+            let element = match front.element {
+                Element::Text(_) | Element::Covered => front.element,
+                _ if coverage < 0.375 => Element::Shade(1),
+                _ if coverage < 0.625 => Element::Shade(2),
+                _ if coverage < 0.875 => Element::Shade(3),
+                element => element,
+            };
+            let (red, green, blue) = block.iter().fold((0.0, 0.0, 0.0), |(r, g, b), cell| {
+                (
+                    r + cell.color.get_red(),
+                    g + cell.color.get_green(),
+                    b + cell.color.get_blue(),
+                )
+            });
+            let count = block.len() as f32;
+            Some(Cell {
+                color: Rgb::from(red / count, green / count, blue / count),
+                element,
+                ..front.clone()
+            })
+        });
+        recycle_grid(mem::replace(&mut self.cells, cells));
+        Matrix {
+            dial: self.dial.scale(1.0 / factor as f32),
+            pen: 1,
+            ..self
         }
-        let points = generate_points(old.width, old.height);
+    }
 
-        points
-            .into_iter()
-            .filter_map(|(x, y)| {
-                let old_cell = &old.cells[y][x];
-                let new_cell = &new.cells[y][x];
-                if old_cell == new_cell {
-                    None
-                } else {
-                    Some(DiffUpdate {
-                        x,
-                        y,
-                        cell: new_cell.clone(),
+    /// Apply vertical/horizontal scaling to the given matrix,
+    /// such that the clock will look like a circle instead of an ellipse.
+    /// This is because each "pixel" (or character) on a terminal is not square-ish, but a
+    /// vertical rectangle instead.
+    ///
+    /// Nearest-neighbor sampling is used, so that every cell keeps the element it belongs to.
+    fn rescale(mut self, screen_width: usize) -> Matrix {
+        let mut cells = empty_grid(0, 0);
+        self.cells.rescale_width_into(screen_width, &mut cells);
+        recycle_grid(mem::replace(&mut self.cells, cells));
+        self
+    }
+
+    /// Give the buffer of this matrix back, so that a later matrix is drawn into it.
+    pub(crate) fn recycle(self) {
+        recycle_grid(self.cells)
+    }
+
+    /// The characters of this matrix without colors, one line per row.
+    pub(crate) fn to_text(&self, glyphs: &Glyphs) -> String {
+        self.cells
+            .rows()
+            .map(|row| {
+                let mut line: String = row
+                    .iter()
+                    .filter_map(|cell| match cell {
+                        Some(cell) if cell.hidden(glyphs) => None,
+                        Some(cell) => Some(cell.glyph(glyphs)),
+                        None => Some(' '),
                     })
-                }
+                    .collect();
+                line.push('\n');
+                line
             })
             .collect()
     }
 
-    fn print(updates: Vec<DiffUpdate>) -> Result<()> {
-        let mut stdout = stdout();
+    /// A stable hash of the characters and colors of this matrix, see `Grid::signature`.
+    pub(crate) fn signature(&self, glyphs: &Glyphs) -> u64 {
+        self.cells.signature(|cell, signature| {
+            let mut character = [0; 4];
+            signature.write(cell.glyph(glyphs).encode_utf8(&mut character).as_bytes());
+            for color in [Some(cell.color), cell.background] {
+                match color {
+                    Some(color) => signature.write(&[
+                        1,
+                        color.get_red() as u8,
+                        color.get_green() as u8,
+                        color.get_blue() as u8,
+                    ]),
+                    None => signature.write(&[0]),
+                }
+            }
+        })
+    }
 
-        for update in updates {
-            let x = update.x as u16;
-            let y = update.y as u16;
-            let character = match update.cell {
-                Some(cell) => "█".with(style::Color::Rgb {
-                    r: cell.color.get_red() as u8,
-                    g: cell.color.get_green() as u8,
-                    b: cell.color.get_blue() as u8,
-                }),
-                None => " ".stylize(),
-            };
+    /// Compute the diff between two matrices.
+    /// This is for reducing unnecessary re-renders.
+    pub(crate) fn diff(&self, new: &Matrix) -> std::result::Result<Vec<DiffUpdate>, SizeMismatch> {
+        if new.tiles.is_empty() {
+            self.cells.diff(&new.cells)
+        } else {
+            self.cells.diff_regions(&new.cells, &new.tiles)
+        }
+    }
+
+    /// The character of the given cell, in its colors.
+    fn styled(
+        cell: &Option<Cell>,
+        color_mode: ColorMode,
+        glyphs: &Glyphs,
+    ) -> StyledContent<String> {
+        let character = match cell {
+            Some(cell) => cell.glyph(glyphs),
+            None => ' ',
+        };
+        paint(character.to_string(), Matrix::colors(cell, color_mode))
+    }
 
-            stdout
-                .queue(cursor::MoveTo(x, y))?
-                .queue(style::PrintStyledContent(character))?;
+    /// The foreground and background color of the given cell, if it has any.
+    fn colors(cell: &Option<Cell>, color_mode: ColorMode) -> CellColors {
+        match cell {
+            Some(cell) if color_mode != ColorMode::Monochrome => (
+                Some(color_mode.to_crossterm_color(cell.color)),
+                cell.background
+                    .map(|background| color_mode.to_crossterm_color(background)),
+            ),
+            _ => (None, None),
         }
+    }
+
+    /// Write the cells that changed since `previous` with ANSI escape codes. The whole matrix is
+    /// written over a cleared screen if there is no previous frame, or if its size differs.
+    pub(crate) fn write_changes(
+        &self,
+        writer: &mut impl Write,
+        previous: Option<&Matrix>,
+        color_mode: ColorMode,
+        glyphs: &Glyphs,
+    ) -> Result<()> {
+        let diff = match previous.map(|previous| previous.diff(self)) {
+            Some(Ok(diff)) => diff,
+            _ => {
+                writer.queue(terminal::Clear(terminal::ClearType::All))?;
+                Matrix::new(self.width(), self.height())
+                    .diff(self)
+                    .map_err(|error| new_error(error.to_string()))?
+            }
+        };
+        Matrix::print(writer, diff, color_mode, glyphs)
+    }
 
-        stdout.flush()?;
+    /// Every row of this matrix as a line with ANSI colors, without trailing empty cells.
+    pub(crate) fn to_lines(&self, color_mode: ColorMode, glyphs: &Glyphs) -> Vec<String> {
+        self.cells
+            .rows()
+            .map(|row| {
+                let length = row
+                    .iter()
+                    .rposition(|cell| cell.is_some())
+                    .map_or(0, |index| index + 1);
+                row[..length]
+                    .iter()
+                    .filter(|cell| !cell.as_ref().is_some_and(|cell| cell.hidden(glyphs)))
+                    .map(|cell| Matrix::styled(cell, color_mode, glyphs).to_string())
+                    .collect()
+            })
+            .collect()
+    }
 
-        Ok(())
+    /// Print the changed cells. Cells that are next to each other on a row are printed after a
+    /// single cursor move, with one string for each color, and the whole frame is written at
+    /// once, which is much faster over slow connections and does not tear.
+    fn print(
+        writer: &mut impl Write,
+        mut updates: Vec<DiffUpdate>,
+        color_mode: ColorMode,
+        glyphs: &Glyphs,
+    ) -> Result<()> {
+        updates.sort_by_key(|update| (update.y, update.x));
+        let mut buffer = Vec::new();
+        // Where the cursor is after the cells printed so far, if it is known
+        let mut cursor_at = None;
+        let mut run = String::new();
+        let mut run_colors = (None, None);
+        for update in &updates {
+            // The right half of a wide character was printed with its left half
+            if update.cell.as_ref().is_some_and(|cell| cell.hidden(glyphs)) {
+                continue;
+            }
+            let colors = Matrix::colors(&update.cell, color_mode);
+            let contiguous = cursor_at == Some((update.x, update.y));
+            if !run.is_empty() && (!contiguous || colors != run_colors) {
+                buffer.queue(style::PrintStyledContent(paint(
+                    mem::take(&mut run),
+                    run_colors,
+                )))?;
+            }
+            if !contiguous {
+                buffer.queue(cursor::MoveTo(update.x as u16, update.y as u16))?;
+            }
+            let character = match &update.cell {
+                Some(cell) => cell.glyph(glyphs),
+                None => ' ',
+            };
+            run.push(character);
+            run_colors = colors;
+            // A wide character moves the cursor by two columns, over the next cell
+            cursor_at = if is_wide(character) {
+                None
+            } else {
+                Some((update.x + 1, update.y))
+            };
+        }
+        if !run.is_empty() {
+            buffer.queue(style::PrintStyledContent(paint(run, run_colors)))?;
+        }
+        writer.write_all(&buffer)?;
+        writer.flush()
     }
 
     fn draw_using_points(mut self, points: Vec<Point>) -> Matrix {
         for point in points {
-            self.cells[point.y as usize][point.x as usize] = Some(Cell { color: point.color })
+            self.stroke_cell(
+                point.x,
+                point.y,
+                Cell {
+                    color: point.color,
+                    element: point.element,
+                    background: None,
+                    degree: point.degree,
+                },
+            )
+        }
+        self
+    }
+
+    /// Write `text` on a single row, starting from (x, y).
+    fn draw_text(mut self, x: isize, y: isize, text: &str, color: Rgb) -> Matrix {
+        let mut x = x;
+        for character in text.chars() {
+            // Keep whatever was painted behind the text
+            let background = self.get_cell(x, y).map(|cell| cell.color);
+            self.set_cell(
+                x,
+                y,
+                Cell {
+                    color,
+                    element: Element::Text(character),
+                    background,
+                    degree: None,
+                },
+            );
+            if is_wide(character) {
+                x += 1;
+                let background = self.get_cell(x, y).map(|cell| cell.color);
+                self.set_cell(
+                    x,
+                    y,
+                    Cell {
+                        color,
+                        element: Element::Covered,
+                        background,
+                        degree: None,
+                    },
+                )
+            }
+            x += 1;
         }
         self
     }
+
+    /// Write `text` horizontally centered on row `y`.
+    fn draw_centered_text(self, y: isize, text: &str, color: Rgb) -> Matrix {
+        let x = (self.width() as isize - text_width(text) as isize) / 2;
+        self.draw_text(x, y, text, color)
+    }
+
+    /// Write the numerals of the 12 hours just inside the hour labels, on a matrix that is
+    /// already resized to the screen from `clock_width` columns. A numeral that a hand or its
+    /// shadow passes through is left out, so that the hands stay on top.
+    fn draw_numerals(self, numerals: Numerals, clock_width: usize, color: Rgb) -> Matrix {
+        let scale = self.width() as f32 / clock_width.max(1) as f32;
+        (1..=12).fold(self, |matrix, hour| {
+            let degree = hour as f32 / 12.0 * 360.0;
+            let distance = matrix.dial.extent(degree) - NUMERAL_INSET;
+            let (x, y) = matrix
+                .dial
+                .point(matrix.height(), degree, distance, (0.0, 0.0));
+            let label = numerals.label(hour);
+            let width = text_width(&label) as isize;
+            let x = ((x as f32 + 0.5) * scale) as isize - width / 2;
+            let covered = (x..x + width).any(|x| {
+                matrix
+                    .get_cell(x, y)
+                    .is_some_and(|cell| cell.element > Element::Complication)
+            });
+            if covered {
+                matrix
+            } else {
+                matrix.draw_text(x, y, &label, color)
+            }
+        })
+    }
+
+    /// Convert this matrix into an image where each character occupies
+    /// `cell_width` x `cell_height` pixels, and empty characters are painted with `background`.
+    /// Text is not rasterized, so only its background is painted.
+    pub(crate) fn to_image(&self, cell_width: u32, cell_height: u32, background: Rgb) -> RgbaImage {
+        ImageBuffer::from_fn(
+            self.width() as u32 * cell_width,
+            self.height() as u32 * cell_height,
+            |x, y| {
+                let color =
+                    match self.get_cell((x / cell_width) as isize, (y / cell_height) as isize) {
+                        Some(Cell {
+                            element: Element::Text(_) | Element::Covered,
+                            background: text_background,
+                            degree: None,
+                            ..
+                        }) => text_background.unwrap_or(background),
+                        Some(cell) => cell.color,
+                        None => background,
+                    };
+                RgbaPixel([
+                    color.get_red() as u8,
+                    color.get_green() as u8,
+                    color.get_blue() as u8,
+                    255,
+                ])
+            },
+        )
+    }
 }
 
-fn generate_points(width: usize, height: usize) -> Vec<(usize, usize)> {
-    (0..height)
-        .into_iter()
-        .flat_map(|y| (0..width).into_iter().map(move |x| (x, y)))
-        .collect()
+pub(crate) type DiffUpdate = Change<Cell>;
+
+/// The foreground and background color of a cell in the terminal.
+type CellColors = (Option<style::Color>, Option<style::Color>);
+
+fn paint(text: String, (foreground, background): CellColors) -> StyledContent<String> {
+    let content = text.stylize();
+    let content = match foreground {
+        Some(color) => content.with(color),
+        None => content,
+    };
+    match background {
+        Some(color) => content.on(color),
+        None => content,
+    }
 }
 
-struct DiffUpdate {
-    x: usize,
-    y: usize,
-    cell: Option<Cell>,
+/// Whether (x, y) lies within the polygon with the given corners, by counting how many of its
+/// edges a ray from the point to the right crosses.
+fn polygon_contains(corners: &[(f32, f32)], x: f32, y: f32) -> bool {
+    let edges = corners.iter().zip(corners.iter().cycle().skip(1));
+    edges
+        .filter(|((x1, y1), (x2, y2))| {
+            (*y1 > y) != (*y2 > y) && x < x1 + (y - y1) / (y2 - y1) * (x2 - x1)
+        })
+        .count()
+        % 2
+        == 1
 }
 
-fn matrix_to_luma_image_buffer(matrix: &Matrix) -> ImageBuffer<RgbPixel<u8>, Vec<u8>> {
-    ImageBuffer::from_fn(
-        matrix.width as u32,
-        matrix.height as u32,
-        |x, y| match &matrix.cells[y as usize][x as usize] {
-            Some(cell) => RgbPixel([
-                cell.color.get_red() as u8,
-                cell.color.get_green() as u8,
-                cell.color.get_blue() as u8,
-            ]),
-            None => RgbPixel([255, 255, 255]),
-        },
+/// How many columns `text` takes in a terminal.
+fn text_width(text: &str) -> usize {
+    text.chars()
+        .map(|character| if is_wide(character) { 2 } else { 1 })
+        .sum()
+}
+
+/// Whether `character` takes two columns in a terminal, which is the case for the CJK
+/// characters of translated text and for emoji.
+fn is_wide(character: char) -> bool {
+    matches!(
+        character as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
     )
 }
 
-fn luma_image_buffer_to_matrix(img: ImageBuffer<RgbPixel<u8>, Vec<u8>>) -> Vec<Vec<Option<Cell>>> {
-    let width = img.width() as usize;
-    let mut cells = vec![vec![None; width]; img.height() as usize];
-    img.pixels().enumerate().for_each(|(index, pixel)| {
-        let y = (index as f32 / width as f32).floor() as usize;
-        let x = index % width;
-        cells[y][x] = if pixel != &RgbPixel([255, 255, 255]) {
-            Some(Cell {
-                color: Rgb::from(pixel.0[0] as f32, pixel.0[1] as f32, pixel.0[2] as f32),
-            })
-        } else {
-            None
-        }
-    });
-    cells
+/// One line per update, such as `12,3 '█' #88c0d0`, or `12,3 clear` for erased cells.
+pub(crate) fn describe_updates(updates: &[DiffUpdate], glyphs: &Glyphs) -> String {
+    updates
+        .iter()
+        .map(|update| match &update.cell {
+            Some(cell) => format!(
+                "{},{} '{}' {}\n",
+                update.x,
+                update.y,
+                cell.glyph(glyphs),
+                cell.color.to_css_hex_string()
+            ),
+            None => format!("{},{} clear\n", update.x, update.y),
+        })
+        .collect()
 }
+
 struct Point {
     x: isize,
     y: isize,
     color: Rgb,
+    element: Element,
+    degree: Option<f32>,
 }
+#[derive(Clone, Copy)]
 struct Hand {
     /// 0 to 360, where:
     /// 0 = North,
@@ -472,14 +3703,66 @@ struct Hand {
     thickness: HandThickness,
     /// In terms of percentage. 0 is shortest, 1 is longest.
     length: f32,
+    style: HandStyle,
     line_start: HandLineStart,
     color: Rgb,
+    /// If defined, the hand fades from `color` at its start to this color at its end.
+    tip_color: Option<Rgb>,
+    element: Element,
 }
+#[derive(Clone, Copy)]
 enum HandThickness {
     Thin,
     Bold,
+    /// Two lines side by side, with a gap between them.
+    Double,
 }
+#[derive(Clone, Copy)]
 enum HandLineStart {
     FromCenter,
     FromCircumference,
+    /// Starts on the opposite side of the center, this far from it relative to the radius,
+    /// like the counterweight tail of a second hand.
+    BehindCenter(f32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cells of the ring of `--show-elapsed` after `elapsed`, on a square dial.
+    fn elapsed_ring(elapsed: Duration) -> Vec<(isize, isize)> {
+        let matrix = Matrix::new(41, 41).draw_elapsed(elapsed, Paint::Solid("#FFFFFF"));
+        let cells = (0..41)
+            .flat_map(|y| (0..41).map(move |x| (x, y)))
+            .filter(|&(x, y)| matrix.get_cell(x, y).is_some())
+            .collect();
+        matrix.recycle();
+        cells
+    }
+
+    #[test]
+    fn elapsed_ring_fills_up_over_an_hour() {
+        let full = elapsed_ring(Duration::from_secs(3599));
+        let half = elapsed_ring(Duration::from_secs(30 * 60));
+        let quarter = elapsed_ring(Duration::from_secs(15 * 60));
+        assert!(quarter.len() < half.len() && half.len() < full.len());
+        assert!(quarter.iter().all(|cell| half.contains(cell)));
+        assert!(half.iter().all(|cell| full.contains(cell)));
+        // Clockwise from 12 o'clock, half an hour covers the right side of the dial
+        assert!(half.iter().all(|&(x, _)| x >= 20));
+        assert!(full.iter().any(|&(x, _)| x < 20));
+    }
+
+    #[test]
+    fn elapsed_ring_starts_again_every_hour() {
+        assert_eq!(
+            elapsed_ring(Duration::from_secs(90 * 60)),
+            elapsed_ring(Duration::from_secs(30 * 60))
+        );
+        assert_eq!(
+            elapsed_ring(Duration::from_secs(60 * 60)),
+            elapsed_ring(Duration::ZERO)
+        );
+    }
 }
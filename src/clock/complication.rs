@@ -0,0 +1,343 @@
+use super::{
+    date_text, Hand, HandLineStart, HandStyle, HandThickness, Matrix, RunClockOptions, UiState,
+    DAY_COLOR, NIGHT_COLOR,
+};
+use crate::astro::moon_phase;
+use crate::font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::glyph::Element;
+
+use chrono::NaiveDateTime;
+use colors_transform::Rgb;
+use std::fmt;
+use std::str::FromStr;
+
+/// Something drawn on a small dial inside the dial, like the registers of a chronograph, see
+/// `--complication`.
+pub(crate) trait Complication {
+    /// Draw on `matrix`, whose dial is the small dial of the slot this complication is in.
+    fn draw(
+        &self,
+        matrix: Matrix,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+    ) -> Matrix;
+
+    /// Whether this complication changes within a second, so that the screen has to be redrawn
+    /// more often.
+    fn animated(&self) -> bool {
+        false
+    }
+}
+
+/// Where a complication is drawn, named after the hour it points to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Slot {
+    Three,
+    Six,
+    Nine,
+    Twelve,
+}
+
+impl Slot {
+    /// The hour this slot points to.
+    pub fn hour(self) -> u32 {
+        match self {
+            Slot::Three => 3,
+            Slot::Six => 6,
+            Slot::Nine => 9,
+            Slot::Twelve => 12,
+        }
+    }
+
+    fn degree(self) -> f32 {
+        match self {
+            Slot::Twelve => 0.0,
+            Slot::Three => 90.0,
+            Slot::Six => 180.0,
+            Slot::Nine => 270.0,
+        }
+    }
+}
+
+/// The built-in complications.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ComplicationKind {
+    /// The day of the month, which rolls over like an odometer at midnight.
+    Date,
+    /// A hand that turns once per second.
+    Subdial(Subdial),
+    /// The current phase of the moon.
+    Moon,
+    /// How charged the battery is.
+    Battery,
+}
+
+impl ComplicationKind {
+    pub(crate) fn complication(&self) -> &dyn Complication {
+        match self {
+            ComplicationKind::Date => &DateComplication,
+            ComplicationKind::Subdial(subdial) => subdial,
+            ComplicationKind::Moon => &MoonComplication,
+            ComplicationKind::Battery => &BatteryComplication,
+        }
+    }
+}
+
+/// A complication in a slot, parsed from `SLOT=NAME` such as `3=date`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlacedComplication {
+    pub slot: Slot,
+    pub kind: ComplicationKind,
+}
+
+impl FromStr for PlacedComplication {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<PlacedComplication, String> {
+        let invalid = || {
+            format!(
+                "Invalid complication '{}', expected a slot of 3, 6, 9 or 12 and one of date, ms, ds, moon or battery, such as 3=date",
+                text
+            )
+        };
+        let (slot, name) = text.split_once('=').ok_or_else(invalid)?;
+        let slot = match slot {
+            "3" => Slot::Three,
+            "6" => Slot::Six,
+            "9" => Slot::Nine,
+            "12" => Slot::Twelve,
+            _ => return Err(invalid()),
+        };
+        let kind = match name {
+            "date" => ComplicationKind::Date,
+            "moon" => ComplicationKind::Moon,
+            "battery" => ComplicationKind::Battery,
+            _ => ComplicationKind::Subdial(name.parse().map_err(|_| invalid())?),
+        };
+        Ok(PlacedComplication { slot, kind })
+    }
+}
+
+impl fmt::Display for PlacedComplication {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self.kind {
+            ComplicationKind::Date => "date",
+            ComplicationKind::Subdial(Subdial::Millis) => "ms",
+            ComplicationKind::Subdial(Subdial::Deciseconds) => "ds",
+            ComplicationKind::Moon => "moon",
+            ComplicationKind::Battery => "battery",
+        };
+        write!(formatter, "{}={}", self.slot.hour(), name)
+    }
+}
+
+/// Where the center of each slot is, halfway from the center of the dial to the circumference,
+/// and the radius of its small dial, both relative to the radius of the dial.
+const SLOT_DISTANCE: f32 = 0.5;
+const SLOT_RADIUS: f32 = 0.25;
+
+/// Small dials narrower than this are not drawn, as their circle would be a smudge.
+const MIN_RADIUS: f32 = 2.0;
+
+impl Matrix {
+    /// Draw every complication in its slot.
+    pub(crate) fn draw_complications<'a>(
+        self,
+        complications: impl Iterator<Item = &'a PlacedComplication>,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+    ) -> Matrix {
+        complications.fold(self, |matrix, placed| {
+            matrix.draw_subdial(placed.slot.degree(), SLOT_DISTANCE, SLOT_RADIUS, |matrix| {
+                placed
+                    .kind
+                    .complication()
+                    .draw(matrix, state, options, datetime)
+            })
+        })
+    }
+}
+
+/// The day of the month in bitmap digits.
+struct DateComplication;
+
+impl Complication for DateComplication {
+    fn draw(
+        &self,
+        matrix: Matrix,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+    ) -> Matrix {
+        let theme = options.themes[state.theme_index];
+        let bitmaps = match state
+            .date_rollover
+            .as_ref()
+            .and_then(|rollover| Some(rollover.bitmaps(rollover.progress(datetime)?)))
+        {
+            Some(bitmaps) => bitmaps,
+            None => date_text(datetime.date()).chars().map(glyph).collect(),
+        };
+        let x = matrix.dial.midpoint_x - (bitmaps.len() * (GLYPH_WIDTH + 1) - 1) as f32 / 2.0;
+        let y = matrix.dial.midpoint_y - GLYPH_HEIGHT as f32 / 2.0;
+        matrix.draw_bitmaps(
+            x.round() as isize,
+            y.round() as isize,
+            &bitmaps,
+            1,
+            Rgb::from_hex_str(theme.clock_face).unwrap(),
+        )
+    }
+}
+
+/// How the hand of the sub-dial moves as it turns once per second.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Subdial {
+    /// Continuously, like a stopwatch counting milliseconds.
+    Millis,
+    /// In ten steps, one per tenth of a second.
+    Deciseconds,
+}
+
+impl Subdial {
+    /// The angle of the hand at the given time.
+    fn degree(self, datetime: NaiveDateTime) -> f32 {
+        let millis = datetime.timestamp_subsec_millis().min(999);
+        let millis = match self {
+            Subdial::Millis => millis,
+            Subdial::Deciseconds => millis / 100 * 100,
+        };
+        millis as f32 / 1000.0 * 360.0
+    }
+}
+
+impl FromStr for Subdial {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Subdial, String> {
+        match text {
+            "ms" => Ok(Subdial::Millis),
+            "ds" => Ok(Subdial::Deciseconds),
+            _ => Err(format!(
+                "Invalid sub-dial '{}', expected one of ms or ds",
+                text
+            )),
+        }
+    }
+}
+
+impl Complication for Subdial {
+    fn draw(
+        &self,
+        matrix: Matrix,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+    ) -> Matrix {
+        if matrix.dial.radius < MIN_RADIUS {
+            return matrix;
+        }
+        let theme = options.themes[state.theme_index];
+        let matrix = matrix.draw_circle(Rgb::from_hex_str(theme.clock_face).unwrap());
+        (0..10)
+            .fold(matrix, |matrix, n| {
+                matrix.draw_hand(Hand {
+                    degree: (n as f32) / 10.0 * 360.0,
+                    thickness: HandThickness::Thin,
+                    length: 0.2,
+                    style: HandStyle::Plain,
+                    line_start: HandLineStart::FromCircumference,
+                    color: Rgb::from_hex_str("#4C566A").unwrap(),
+                    tip_color: None,
+                    element: Element::MinuteLabel,
+                })
+            })
+            .draw_hand(Hand {
+                degree: self.degree(datetime),
+                thickness: HandThickness::Thin,
+                length: 0.8,
+                style: HandStyle::Plain,
+                line_start: HandLineStart::FromCenter,
+                color: theme.second.color(),
+                tip_color: theme.second.tip_color(),
+                element: Element::SecondHand,
+            })
+    }
+
+    fn animated(&self) -> bool {
+        true
+    }
+}
+
+/// The moon, lit as it is today.
+struct MoonComplication;
+
+impl Complication for MoonComplication {
+    fn draw(
+        &self,
+        matrix: Matrix,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+    ) -> Matrix {
+        if matrix.dial.radius < MIN_RADIUS {
+            return matrix;
+        }
+        let theme = options.themes[state.theme_index];
+        // The moon is seen upside down from the southern hemisphere
+        let southern = options.location.is_some_and(|(latitude, _)| latitude < 0.0);
+        matrix
+            .fill_moon(
+                moon_phase(datetime),
+                southern,
+                Rgb::from_hex_str(DAY_COLOR).unwrap(),
+                Rgb::from_hex_str(NIGHT_COLOR).unwrap(),
+            )
+            .draw_circle(Rgb::from_hex_str(theme.clock_face).unwrap())
+    }
+}
+
+/// Colors of the charge drawn by the battery complication.
+const BATTERY_COLOR: &str = "#A3BE8C";
+const BATTERY_LOW_COLOR: &str = "#BF616A";
+
+/// Below this charge the battery complication turns red.
+const BATTERY_LOW_LEVEL: f32 = 0.2;
+
+/// The charge of the battery as a sector growing clockwise from 12 o'clock, or an empty circle
+/// if there is no battery.
+struct BatteryComplication;
+
+impl Complication for BatteryComplication {
+    fn draw(
+        &self,
+        matrix: Matrix,
+        state: &UiState,
+        options: &RunClockOptions,
+        _datetime: NaiveDateTime,
+    ) -> Matrix {
+        if matrix.dial.radius < MIN_RADIUS {
+            return matrix;
+        }
+        let theme = options.themes[state.theme_index];
+        let matrix = match state.battery {
+            Some(level) => {
+                let color = if level < BATTERY_LOW_LEVEL {
+                    BATTERY_LOW_COLOR
+                } else {
+                    BATTERY_COLOR
+                };
+                matrix.fill_sector(
+                    0.0,
+                    level * 360.0,
+                    Rgb::from_hex_str(color).unwrap(),
+                    Element::Complication,
+                )
+            }
+            None => matrix,
+        };
+        matrix.draw_circle(Rgb::from_hex_str(theme.clock_face).unwrap())
+    }
+}
@@ -0,0 +1,290 @@
+use super::{draw_dial, draw_overlays, Cell, Matrix, RunClockOptions, UiState};
+use crate::color::ColorMode;
+use crate::glyph::Element;
+use crate::segment::SegmentSize;
+use crate::words::{Phrase, Word, LETTERS, LETTERS_WIDTH};
+
+use chrono::{NaiveDateTime, Timelike};
+use colors_transform::Rgb;
+use std::str::FromStr;
+
+/// Draws the time in the area of a dial, so that every kind of face shares the same diffing and
+/// printing of the matrix.
+pub(crate) trait DialRenderer {
+    fn draw(
+        &self,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+        screen_size: (usize, usize),
+    ) -> Matrix;
+}
+
+/// What the clock looks like, see `--face`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Face {
+    Analog,
+    Binary,
+    Digital,
+    Words,
+}
+
+impl Face {
+    pub(crate) fn renderer(self) -> &'static dyn DialRenderer {
+        match self {
+            Face::Analog => &AnalogFace,
+            Face::Binary => &BinaryFace,
+            Face::Digital => &DigitalFace,
+            Face::Words => &WordsFace,
+        }
+    }
+}
+
+impl FromStr for Face {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Face, String> {
+        match text {
+            "analog" => Ok(Face::Analog),
+            "binary" => Ok(Face::Binary),
+            "digital" => Ok(Face::Digital),
+            "words" => Ok(Face::Words),
+            _ => Err(format!(
+                "Invalid face '{}', expected one of analog, binary, digital or words",
+                text
+            )),
+        }
+    }
+}
+
+/// The dial with hands, or whatever else `--mode` asks for.
+struct AnalogFace;
+
+impl DialRenderer for AnalogFace {
+    fn draw(
+        &self,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+        screen_size: (usize, usize),
+    ) -> Matrix {
+        draw_dial(state, options, datetime, screen_size)
+    }
+}
+
+/// Bits of the largest digit, which is the bottom of each column of the binary face.
+const BINARY_BITS: usize = 4;
+
+/// A column of dots for each digit of the time, where lit dots add up to the digit, counting 8,
+/// 4, 2 and 1 from the top.
+struct BinaryFace;
+
+impl DialRenderer for BinaryFace {
+    fn draw(
+        &self,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+        (screen_width, height): (usize, usize),
+    ) -> Matrix {
+        let theme = options.themes[state.theme_index];
+        let unlit = Rgb::from_hex_str(theme.clock_face).unwrap();
+        let (hour, minute, second) = (datetime.hour(), datetime.minute(), datetime.second());
+        // Each digit with the bits it can ever use
+        let hour_minute = [
+            (hour / 10, 2, theme.hour.color(), Element::HourHand),
+            (hour % 10, 4, theme.hour.color(), Element::HourHand),
+            (minute / 10, 3, theme.minute.color(), Element::MinuteHand),
+            (minute % 10, 4, theme.minute.color(), Element::MinuteHand),
+        ];
+        let seconds = [
+            (second / 10, 3, theme.second.color(), Element::SecondHand),
+            (second % 10, 4, theme.second.color(), Element::SecondHand),
+        ];
+        let digits = if state.show_second_hand {
+            [&hour_minute[..], &seconds[..]].concat()
+        } else {
+            hour_minute.to_vec()
+        };
+
+        // Like the dial, the dots are drawn with square cells and widened afterwards
+        let width = (screen_width as f32 / state.aspect_ratio) as usize;
+        let columns = digits.len();
+        // Dots are separated by a gap of half their size, and pairs of digits by another gap
+        let gap = |size: usize| (size / 2).max(1);
+        let face_width = |size: usize| columns * size + (columns + columns / 2 - 2) * gap(size);
+        let face_height = |size: usize| BINARY_BITS * size + (BINARY_BITS - 1) * gap(size);
+        // The top row is left for messages
+        let size = (1..=height)
+            .rev()
+            .find(|size| face_width(*size) <= width && face_height(*size) + 2 <= height)
+            .unwrap_or(1);
+        let left = width.saturating_sub(face_width(size)) / 2;
+        let top = height.saturating_sub(face_height(size)) / 2;
+
+        let mut matrix = Matrix::new(width, height);
+        for (column, (digit, bits, color, element)) in digits.into_iter().enumerate() {
+            let x = left + column * (size + gap(size)) + column / 2 * gap(size);
+            for row in BINARY_BITS - bits..BINARY_BITS {
+                let y = top + row * (size + gap(size));
+                let cell = if digit & (1 << (BINARY_BITS - 1 - row)) != 0 {
+                    Cell {
+                        color,
+                        element,
+                        background: None,
+                        degree: None,
+                    }
+                } else {
+                    Cell {
+                        color: unlit,
+                        element: Element::Face,
+                        background: None,
+                        degree: None,
+                    }
+                };
+                for (dx, dy) in (0..size).flat_map(|dx| (0..size).map(move |dy| (dx, dy))) {
+                    matrix.set_cell((x + dx) as isize, (y + dy) as isize, cell.clone())
+                }
+            }
+        }
+        draw_overlays(
+            matrix.rescale(screen_width),
+            state,
+            options,
+            datetime,
+            None,
+            height,
+        )
+    }
+}
+
+/// The time in seven-segment digits filling the screen, in the colors of the hands.
+struct DigitalFace;
+
+impl DialRenderer for DigitalFace {
+    fn draw(
+        &self,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+        (screen_width, height): (usize, usize),
+    ) -> Matrix {
+        let theme = options.themes[state.theme_index];
+        let text = if state.show_second_hand {
+            datetime.format("%H:%M:%S").to_string()
+        } else {
+            datetime.format("%H:%M").to_string()
+        };
+        // Hours, minutes and seconds are each two digits followed by a colon
+        let colors =
+            text.chars()
+                .enumerate()
+                .map(|(index, character)| match (character, index / 3) {
+                    (':', _) => (Rgb::from_hex_str(theme.clock_face).unwrap(), Element::Face),
+                    (_, 0) => (theme.hour.color(), Element::HourHand),
+                    (_, 1) => (theme.minute.color(), Element::MinuteHand),
+                    _ => (theme.second.color(), Element::SecondHand),
+                });
+
+        // Like the dial, the digits are drawn with square cells and widened afterwards.
+        // The top row is left for messages.
+        let width = (screen_width as f32 / state.aspect_ratio) as usize;
+        let mut matrix = Matrix::new(width, height);
+        if let Some(size) = SegmentSize::fitting(&text, width, height.saturating_sub(2)) {
+            let left = width.saturating_sub(size.text_width(&text)) / 2;
+            let top = height.saturating_sub(size.height) / 2;
+            let glyphs = text.chars().zip(colors).zip(size.offsets(&text));
+            for ((character, (color, element)), offset) in glyphs {
+                for (x, y) in size.cells(character) {
+                    matrix.set_cell(
+                        (left + offset + x) as isize,
+                        (top + y) as isize,
+                        Cell {
+                            color,
+                            element,
+                            background: None,
+                            degree: None,
+                        },
+                    )
+                }
+            }
+        }
+        draw_overlays(
+            matrix.rescale(screen_width),
+            state,
+            options,
+            datetime,
+            None,
+            height,
+        )
+    }
+}
+
+/// Color of the letters of the word clock that are not part of the time.
+const UNLIT_LETTER_COLOR: &str = "#4C566A";
+
+/// A grid of letters, where the words telling the time are lit in the colors of the hands.
+struct WordsFace;
+
+impl DialRenderer for WordsFace {
+    fn draw(
+        &self,
+        state: &UiState,
+        options: &RunClockOptions,
+        datetime: NaiveDateTime,
+        (width, height): (usize, usize),
+    ) -> Matrix {
+        let theme = options.themes[state.theme_index];
+        let phrase = Phrase::at(datetime.time());
+        let lit = |words: &[Word], color: Rgb| {
+            words
+                .iter()
+                .flat_map(|word| {
+                    (0..word.length).map(move |offset| (word.row, word.column + offset))
+                })
+                .map(move |position| (position, color))
+                .collect::<Vec<_>>()
+        };
+        let lit = [
+            lit(&phrase.prefix, Rgb::from_hex_str(theme.clock_face).unwrap()),
+            lit(&phrase.minutes, theme.minute.color()),
+            lit(&[phrase.hour], theme.hour.color()),
+        ]
+        .concat();
+
+        // Letters are spread out to fill the screen, keeping the grid square.
+        // The top row is left for messages.
+        let rows = LETTERS.len();
+        let row_spacing = (height.saturating_sub(2) / rows)
+            .min((width as f32 / state.aspect_ratio / LETTERS_WIDTH as f32) as usize)
+            .max(1);
+        let column_spacing = ((row_spacing as f32 * state.aspect_ratio) as usize).max(2);
+        let left = width.saturating_sub((LETTERS_WIDTH - 1) * column_spacing + 1) / 2;
+        let top = height.saturating_sub((rows - 1) * row_spacing + 1) / 2;
+
+        let unlit = Rgb::from_hex_str(UNLIT_LETTER_COLOR).unwrap();
+        let mut matrix = Matrix::new(width, height);
+        for (row, letters) in LETTERS.iter().enumerate() {
+            for (column, letter) in letters.chars().enumerate() {
+                let color = lit
+                    .iter()
+                    .find(|(position, _)| *position == (row, column))
+                    .map(|(_, color)| *color);
+                // Without colors, only the case tells lit letters apart
+                let letter = match color {
+                    None if options.color_mode == ColorMode::Monochrome => {
+                        letter.to_ascii_lowercase()
+                    }
+                    _ => letter,
+                };
+                matrix = matrix.draw_text(
+                    (left + column * column_spacing) as isize,
+                    (top + row * row_spacing) as isize,
+                    &letter.to_string(),
+                    color.unwrap_or(unlit),
+                )
+            }
+        }
+        draw_overlays(matrix, state, options, datetime, None, height)
+    }
+}
@@ -0,0 +1,145 @@
+use colors_transform::{Color, Rgb};
+use crossterm::style;
+use std::env;
+use std::str::FromStr;
+
+/// How many colors the terminal can display.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorMode {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    /// No colors at all, see https://no-color.org
+    Monochrome,
+}
+
+impl ColorMode {
+    /// Guess the color mode of the terminal from `NO_COLOR`, `COLORTERM` and `TERM`.
+    pub fn detect() -> ColorMode {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorMode::Monochrome;
+        }
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor"
+            || colorterm == "24bit"
+            || term.ends_with("-direct")
+            // Windows Terminal supports truecolor but does not set COLORTERM
+            || env::var_os("WT_SESSION").is_some()
+        {
+            ColorMode::TrueColor
+        } else if term.contains("256color") {
+            ColorMode::Ansi256
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+
+    /// Convert `color` into the closest color that the terminal can display.
+    pub fn to_crossterm_color(self, color: Rgb) -> style::Color {
+        let (r, g, b) = (
+            color.get_red() as u8,
+            color.get_green() as u8,
+            color.get_blue() as u8,
+        );
+        match self {
+            ColorMode::TrueColor => style::Color::Rgb { r, g, b },
+            ColorMode::Ansi256 => style::Color::AnsiValue(closest_ansi_256(r, g, b)),
+            ColorMode::Ansi16 => closest_ansi_16(r, g, b),
+            ColorMode::Monochrome => style::Color::Reset,
+        }
+    }
+}
+
+/// Parses `auto`, `truecolor`, `256`, `16` or `none`, where `auto` is resolved by `ColorMode::detect`.
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<ColorMode, String> {
+        match text {
+            "auto" => Ok(ColorMode::detect()),
+            "truecolor" => Ok(ColorMode::TrueColor),
+            "256" => Ok(ColorMode::Ansi256),
+            "16" => Ok(ColorMode::Ansi16),
+            "none" => Ok(ColorMode::Monochrome),
+            _ => Err(format!(
+                "Invalid color mode '{}', expected one of auto, truecolor, 256, 16 or none",
+                text
+            )),
+        }
+    }
+}
+
+/// Make `color` darker by mixing it with black, where `amount` is 0 for `color` and 1 for black.
+pub fn darken(color: Rgb, amount: f32) -> Rgb {
+    mix(Rgb::from(0.0, 0.0, 0.0), color, amount)
+}
+
+/// Mix `color` over `background`, where `opacity` is 0 for `background` only and 1 for `color` only.
+pub fn mix(color: Rgb, background: Rgb, opacity: f32) -> Rgb {
+    let channel = |color: f32, background: f32| background + (color - background) * opacity;
+    Rgb::from(
+        channel(color.get_red(), background.get_red()),
+        channel(color.get_green(), background.get_green()),
+        channel(color.get_blue(), background.get_blue()),
+    )
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let square = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    square(r1, r2) + square(g1, g2) + square(b1, b2)
+}
+
+/// Intensities of each channel in the 6x6x6 color cube of the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The closest entry among the color cube (16 to 231) and the grayscale ramp (232 to 255).
+fn closest_ansi_256(r: u8, g: u8, b: u8) -> u8 {
+    let closest_level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&index| (CUBE_LEVELS[index] as i32 - value as i32).abs())
+            .unwrap()
+    };
+    let (ri, gi, bi) = (closest_level(r), closest_level(g), closest_level(b));
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+    let cube_color = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = ((average.saturating_sub(8) + 5) / 10).min(23) as u8;
+    let gray_value = 8 + 10 * gray_step;
+    let gray_color = (gray_value, gray_value, gray_value);
+
+    if distance((r, g, b), gray_color) < distance((r, g, b), cube_color) {
+        232 + gray_step
+    } else {
+        cube_index as u8
+    }
+}
+
+/// The 16 basic colors, with the RGB values used by xterm.
+const ANSI_16: [(style::Color, (u8, u8, u8)); 16] = [
+    (style::Color::Black, (0, 0, 0)),
+    (style::Color::DarkRed, (205, 0, 0)),
+    (style::Color::DarkGreen, (0, 205, 0)),
+    (style::Color::DarkYellow, (205, 205, 0)),
+    (style::Color::DarkBlue, (0, 0, 238)),
+    (style::Color::DarkMagenta, (205, 0, 205)),
+    (style::Color::DarkCyan, (0, 205, 205)),
+    (style::Color::Grey, (229, 229, 229)),
+    (style::Color::DarkGrey, (127, 127, 127)),
+    (style::Color::Red, (255, 0, 0)),
+    (style::Color::Green, (0, 255, 0)),
+    (style::Color::Yellow, (255, 255, 0)),
+    (style::Color::Blue, (92, 92, 255)),
+    (style::Color::Magenta, (255, 0, 255)),
+    (style::Color::Cyan, (0, 255, 255)),
+    (style::Color::White, (255, 255, 255)),
+];
+
+fn closest_ansi_16(r: u8, g: u8, b: u8) -> style::Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .unwrap()
+        .0
+}
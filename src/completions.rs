@@ -0,0 +1,80 @@
+use crate::cli::CliOptions;
+
+use std::io::{self, Write};
+use structopt::clap::Shell;
+use structopt::StructOpt;
+
+const BIN_NAME: &str = "analog-clock";
+
+/// Options that take the name of a theme.
+const THEME_OPTIONS: [&str; 3] = ["theme", "theme-day", "theme-night"];
+
+/// Print the completion script generated by clap for `shell`, where theme names are completed by
+/// running `analog-clock --list-themes` when completing, so that themes added later are included.
+pub fn print_completions(shell: Shell) -> io::Result<()> {
+    let mut script = Vec::new();
+    CliOptions::clap().gen_completions_to(BIN_NAME, shell, &mut script);
+    let script = String::from_utf8_lossy(&script);
+    let script = THEME_OPTIONS
+        .iter()
+        .fold(script.into_owned(), |script, option| {
+            complete_themes(shell, &script, option)
+        });
+    // The function that zsh completes themes with is defined before the script starts
+    let script = match shell {
+        Shell::Zsh => script.replacen(ZSH_MAIN, &format!("{}\n{}", ZSH_THEMES, ZSH_MAIN), 1),
+        _ => script,
+    };
+    io::stdout().write_all(script.as_bytes())
+}
+
+/// Themes are listed by the program itself, as they include the themes in the theme directory.
+const LIST_THEMES: &str = "analog-clock --list-themes --no-color 2>/dev/null";
+
+const ZSH_THEMES: &str = r#"_analog-clock_themes() {
+    local themes
+    themes=(auto ${(f)"$(analog-clock --list-themes --no-color 2>/dev/null)"})
+    _describe 'theme' themes
+}
+"#;
+
+/// The last line of the zsh script, which starts completing.
+const ZSH_MAIN: &str = "_analog-clock \"$@\"";
+
+/// Replace the file name completion that clap generates for `--<option>` with theme names.
+fn complete_themes(shell: Shell, script: &str, option: &str) -> String {
+    match shell {
+        Shell::Bash => script.replacen(
+            &format!(
+                "--{})\n                    COMPREPLY=($(compgen -f \"${{cur}}\"))",
+                option
+            ),
+            &format!(
+                "--{})\n                    COMPREPLY=($(compgen -W \"auto $({})\" -- \"${{cur}}\"))",
+                option, LIST_THEMES
+            ),
+            1,
+        ),
+        Shell::Zsh => {
+            // The option is followed by its description in brackets
+            let start = format!("'--{}=[", option);
+            let end = script
+                .find(&start)
+                .and_then(|index| Some(index + script[index..].find("]' \\")? + 1));
+            match end {
+                Some(end) => format!(
+                    "{}:theme:_analog-clock_themes{}",
+                    &script[..end],
+                    &script[end..]
+                ),
+                None => script.to_string(),
+            }
+        }
+        Shell::Fish => script.replacen(
+            &format!("-l {} -d", option),
+            &format!("-l {} -x -a \"auto ({})\" -d", option, LIST_THEMES),
+            1,
+        ),
+        _ => script.to_string(),
+    }
+}
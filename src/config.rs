@@ -0,0 +1,110 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Content of `~/.config/analog-clock/config.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Defaults of command line options.
+    pub defaults: Defaults,
+
+    /// Maps the name of an action to one or more keys, for example `theme-next = "t"`.
+    pub keymap: HashMap<String, Keys>,
+
+    /// Rules that show or hide parts of the clock depending on the time or the size of the dial.
+    pub visibility: Vec<VisibilityRule>,
+}
+
+/// Defaults of command line options of the same name, which take precedence over them.
+/// For example:
+///
+/// ```toml
+/// [defaults]
+/// theme = "gruvbox-dark"
+/// fps = 4
+/// hide-second-hand = true
+/// aspect-ratio = 2.2
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Defaults {
+    pub theme: Option<String>,
+    pub fps: Option<f64>,
+    /// Deprecated, use `fps` instead.
+    pub tick: Option<usize>,
+    pub hide_second_hand: Option<bool>,
+    pub hide_hour_labels: Option<bool>,
+    pub show_minute_labels: Option<bool>,
+    pub aspect_ratio: Option<f32>,
+}
+
+/// For example, hiding the second hand at night:
+///
+/// ```toml
+/// [[visibility]]
+/// element = "second-hand"
+/// visible = false
+/// between = ["00:00", "06:00"]
+/// ```
+///
+/// Every condition that is given has to hold for the rule to apply.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct VisibilityRule {
+    pub element: String,
+    pub visible: bool,
+    /// Start and end time, such as `["22:00", "06:00"]`, which may wrap around midnight.
+    pub between: Option<[String; 2]>,
+    /// In rows.
+    pub radius_above: Option<f32>,
+    /// In rows.
+    pub radius_below: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Keys {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            Keys::One(key) => vec![key.clone()],
+            Keys::Many(keys) => keys.clone(),
+        }
+    }
+}
+
+/// The directory where the configurations of this program are stored,
+/// which is `$XDG_CONFIG_HOME/analog-clock`, or `~/.config/analog-clock` by default.
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .map(|dir| dir.join("analog-clock"))
+}
+
+/// Read the config file, returning the default config if it does not exist.
+pub fn load_config() -> Result<Config, String> {
+    let path = match config_dir() {
+        Some(dir) => dir.join("config.toml"),
+        None => return Ok(Config::default()),
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => toml::from_str(&content)
+            .map_err(|error| format!("Invalid config file {}: {}", path.display(), error)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(Config::default()),
+        Err(error) => Err(format!(
+            "Unable to read config file {}: {}",
+            path.display(),
+            error
+        )),
+    }
+}
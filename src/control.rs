@@ -0,0 +1,122 @@
+use crate::cli::parse_duration;
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How long a client may take to send its commands before it is disconnected, so that a stuck
+/// client does not stop the clock.
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Where the socket of `--control-socket` is created if no path is given, which is in
+/// `XDG_RUNTIME_DIR`, or the temporary directory if it is not set.
+pub fn default_path() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map_or_else(env::temp_dir, PathBuf::from)
+        .join("analog-clock.sock")
+}
+
+/// A line sent to the control socket.
+#[derive(Debug, PartialEq)]
+pub enum ControlCommand {
+    /// `theme NAME` switches to the theme with this name.
+    Theme(String),
+    /// `timer DURATION`, such as `timer 10m`, rings the bell after the duration.
+    Timer(Duration),
+    /// `pause` stops the hands at the time shown.
+    Pause,
+    /// `resume` shows the current time again.
+    Resume,
+}
+
+impl FromStr for ControlCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<ControlCommand, String> {
+        let mut words = line.split_whitespace();
+        let command = match (words.next(), words.next()) {
+            (Some("theme"), Some(name)) => ControlCommand::Theme(name.to_string()),
+            (Some("timer"), Some(duration)) => ControlCommand::Timer(parse_duration(duration)?),
+            (Some("pause"), None) => ControlCommand::Pause,
+            (Some("resume"), None) => ControlCommand::Resume,
+            _ => {
+                return Err(format!(
+                "Invalid command '{}', expected one of theme NAME, timer DURATION, pause or resume",
+                line.trim()
+            ))
+            }
+        };
+        match words.next() {
+            Some(_) => Err(format!("Too many arguments in '{}'", line.trim())),
+            None => Ok(command),
+        }
+    }
+}
+
+/// A Unix domain socket accepting one command per line, which is answered with `ok` or the
+/// reason it failed, for example `echo 'theme monokai' | nc -U $XDG_RUNTIME_DIR/analog-clock.sock`.
+///
+/// The socket file is removed when this is dropped.
+pub struct ControlSocket {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl ControlSocket {
+    /// Listen on `path`, replacing a socket that is left behind by a clock that has exited,
+    /// but not one that is still in use.
+    pub fn bind(path: PathBuf) -> io::Result<ControlSocket> {
+        if UnixStream::connect(&path).is_ok() {
+            return Err(io::Error::new(
+                ErrorKind::AddrInUse,
+                format!("{} is used by another clock", path.display()),
+            ));
+        }
+        match fs::remove_file(&path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => return Err(error),
+            _ => (),
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        Ok(ControlSocket { listener, path })
+    }
+
+    /// Read the commands of every client that is waiting, without blocking if there is none,
+    /// and answer each with the result of `apply`.
+    pub fn serve(&self, mut apply: impl FnMut(ControlCommand) -> Result<(), String>) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            // A client that misbehaves is disconnected without affecting the clock
+            let _ = serve_client(stream, &mut apply);
+        }
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn serve_client(
+    stream: UnixStream,
+    apply: &mut impl FnMut(ControlCommand) -> Result<(), String>,
+) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.parse().and_then(&mut *apply) {
+            Ok(()) => writeln!(writer, "ok")?,
+            Err(error) => writeln!(writer, "{}", error)?,
+        }
+    }
+    Ok(())
+}
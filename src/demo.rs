@@ -0,0 +1,87 @@
+use crate::theme::{find_theme, Theme};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use std::time::Duration;
+
+/// One part of the tour started by `--demo`.
+pub struct Step {
+    /// Shown on the top row while this step is running.
+    pub caption: &'static str,
+    pub duration: Duration,
+    /// How many times faster than real time the clock runs.
+    pub speed: u32,
+    pub theme: &'static str,
+    pub show_hour_labels: bool,
+    pub show_minute_labels: bool,
+    pub show_second_hand: bool,
+    /// Hour and minute of the ghost hands, if any.
+    pub target: Option<(u32, u32)>,
+}
+
+impl Step {
+    pub fn theme_index(&self, themes: &[Theme]) -> usize {
+        find_theme(themes, self.theme).unwrap_or(0)
+    }
+}
+
+const fn step(caption: &'static str, seconds: u64, speed: u32, theme: &'static str) -> Step {
+    Step {
+        caption,
+        duration: Duration::from_secs(seconds),
+        speed,
+        theme,
+        show_hour_labels: true,
+        show_minute_labels: false,
+        show_second_hand: true,
+        target: None,
+    }
+}
+
+const STEPS: [Step; 10] = [
+    step("analog-clock", 4, 1, "nord-frost"),
+    Step {
+        show_minute_labels: true,
+        ..step("--show-minute-labels", 4, 1, "nord-frost")
+    },
+    step("60x speed", 4, 60, "nord-frost"),
+    step("--theme nord-aurora", 3, 60, "nord-aurora"),
+    step("--theme gruvbox-light", 3, 60, "gruvbox-light"),
+    step("--theme gruvbox-dark", 3, 60, "gruvbox-dark"),
+    Step {
+        show_second_hand: false,
+        ..step("3600x speed, --hide-second-hand", 4, 3600, "nord-frost")
+    },
+    Step {
+        show_hour_labels: false,
+        show_second_hand: false,
+        ..step("--hide-hour-labels", 3, 3600, "nord-frost")
+    },
+    Step {
+        target: Some((12, 0)),
+        ..step("--target 12:00", 4, 600, "nord-frost")
+    },
+    step("back to real time", 3, 1, "nord-frost"),
+];
+
+/// The time shown when the tour starts, which is fixed so that every run looks the same.
+fn start() -> NaiveDateTime {
+    NaiveDate::from_ymd(2022, 1, 1).and_hms(10, 8, 0)
+}
+
+/// The step of the tour and the time shown after `elapsed` of real time.
+/// The tour starts over after the last step.
+pub fn scene(elapsed: Duration) -> (&'static Step, NaiveDateTime) {
+    let total: Duration = STEPS.iter().map(|step| step.duration).sum();
+    let mut remaining = Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64);
+    let mut datetime = start();
+    for step in STEPS.iter() {
+        let shown = remaining.min(step.duration);
+        datetime += chrono::Duration::from_std(shown * step.speed)
+            .unwrap_or_else(|_| chrono::Duration::zero());
+        if remaining < step.duration {
+            return (step, datetime);
+        }
+        remaining -= step.duration;
+    }
+    (&STEPS[STEPS.len() - 1], datetime)
+}
@@ -0,0 +1,66 @@
+use crate::clock::{draw_clock, RunClockOptions, UiState};
+use crate::demo;
+
+use chrono::Duration as ChronoDuration;
+use colors_transform::Rgb;
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame, ImageError,
+};
+use std::fs::File;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Size of each character in the exported image, in pixels.
+/// Characters of a terminal are roughly twice as tall as they are wide.
+pub(crate) const CELL_WIDTH: u32 = 6;
+pub(crate) const CELL_HEIGHT: u32 = 12;
+
+/// Color of the empty characters in the exported image.
+pub(crate) const BACKGROUND: &str = "#2E3440";
+
+pub struct ExportOptions {
+    pub output: PathBuf,
+    pub width: usize,
+    pub height: usize,
+    /// If defined, export an animation of this length instead of a still image.
+    pub animation_duration: Option<Duration>,
+}
+
+pub fn export_clock(options: RunClockOptions, export: ExportOptions) -> Result<(), ImageError> {
+    let mut state = UiState::new(&options);
+    let background = Rgb::from_hex_str(BACKGROUND).unwrap();
+    let start = options.now();
+    let mut render = |frame_index: u32| {
+        let elapsed = options.tick_interval * frame_index;
+        let datetime = if options.demo {
+            let (step, datetime) = demo::scene(elapsed);
+            state.show_demo_step(step, &options.themes);
+            datetime
+        } else {
+            start + ChronoDuration::from_std(elapsed).unwrap_or_else(|_| ChronoDuration::zero())
+        };
+        state.follow_theme_schedule(options.theme_schedule, datetime);
+        state.roll_date(datetime);
+        draw_clock(&state, &options, datetime, (export.width, export.height)).to_image(
+            CELL_WIDTH,
+            CELL_HEIGHT,
+            background,
+        )
+    };
+
+    match export.animation_duration {
+        None => render(0).save(&export.output),
+        Some(duration) => {
+            let tick = options.tick_interval.as_millis().max(1);
+            let frame_count = (duration.as_millis() / tick).max(1) as u32;
+            let delay = Delay::from_numer_denom_ms(tick as u32, 1);
+            let mut encoder = GifEncoder::new(File::create(&export.output)?);
+            encoder.set_repeat(Repeat::Infinite)?;
+            encoder.encode_frames(
+                (0..frame_count)
+                    .map(|frame_index| Frame::from_parts(render(frame_index), 0, 0, delay)),
+            )
+        }
+    }
+}
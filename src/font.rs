@@ -0,0 +1,40 @@
+/// A tiny bitmap font, for drawing text that is large enough to be read inside the dial.
+///
+/// Each glyph is `GLYPH_HEIGHT` rows, and each row is `GLYPH_WIDTH` bits with the most
+/// significant bit on the left.
+pub const GLYPH_WIDTH: usize = 3;
+pub const GLYPH_HEIGHT: usize = 5;
+
+pub type Bitmap = [u8; GLYPH_HEIGHT];
+
+pub fn glyph(character: char) -> Bitmap {
+    match character {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => [0b000; GLYPH_HEIGHT],
+    }
+}
+
+/// A glyph in the middle of rolling from `from` to `to`, like the wheel of an odometer,
+/// after `offset` rows out of `GLYPH_HEIGHT + 1` (one blank row separates the glyphs).
+pub fn rolling_glyph(from: char, to: char, offset: usize) -> Bitmap {
+    let strip: Vec<u8> = glyph(from)
+        .iter()
+        .copied()
+        .chain(Some(0))
+        .chain(glyph(to).iter().copied())
+        .collect();
+    let mut bitmap = [0; GLYPH_HEIGHT];
+    bitmap.copy_from_slice(&strip[offset.min(GLYPH_HEIGHT + 1)..][..GLYPH_HEIGHT]);
+    bitmap
+}
@@ -0,0 +1,219 @@
+use crate::color::ColorMode;
+
+/// Which part of the clock a cell belongs to, declared from the back to the front.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Element {
+    Background,
+    Face,
+    /// The part of a day dial between sunrise and sunset.
+    Day,
+    /// The part of a day dial between sunset and sunrise, and the dark part of the moon.
+    Night,
+    /// Pixels of the picture of `--face-image`, from 1 for the darkest to 4 for the brightest.
+    Image(u8),
+    HourLabel,
+    MinuteLabel,
+    Marker,
+    /// Pixels of the bitmap text drawn inside the dial, such as the date, and the lit part of
+    /// the moon.
+    Complication,
+    /// Shadows of the hands drawn by `--shadow`.
+    Shadow,
+    /// Hands drawn by `--target`.
+    GhostHand,
+    HourHand,
+    MinuteHand,
+    SecondHand,
+    /// The 24-hour hand of `--gmt-hand`.
+    GmtHand,
+    /// The filled circle at the center drawn by `--center-cap`.
+    CenterCap,
+    /// The edge of an element that covers only part of a cell of `--supersample`, from 1 when
+    /// it covers little of it to 3 when it covers most of it.
+    Shade(u8),
+    Text(char),
+    /// The right half of a wide character of text, which is printed with the character.
+    Covered,
+}
+
+/// The character printed for each element of the clock.
+pub struct Glyphs {
+    pub background: char,
+    pub face: char,
+    pub day: char,
+    pub night: char,
+    pub label: char,
+    pub marker: char,
+    pub complication: char,
+    pub shadow: char,
+    /// Pixels of `--face-image` by brightness, from the darkest.
+    pub image: [char; 4],
+    pub ghost_hand: char,
+    pub hour_hand: char,
+    pub minute_hand: char,
+    pub second_hand: char,
+    pub gmt_hand: char,
+    pub center_cap: char,
+    /// Characters of `Element::Shade`, from the lightest to the darkest.
+    pub shades: [char; 3],
+    /// Draw hands and labels with `|`, `/`, `-` or `\` following their direction, instead of
+    /// their character.
+    pub follow_direction: bool,
+    /// Replace the characters of text that are not ASCII with `?`.
+    pub ascii_only: bool,
+}
+
+impl Glyphs {
+    /// Every element is a block when colors are available. Otherwise each element is drawn with
+    /// a distinct character so that the hands can still be told apart.
+    pub fn new(color_mode: ColorMode) -> Glyphs {
+        match color_mode {
+            ColorMode::Monochrome => Glyphs {
+                background: ' ',
+                face: '░',
+                day: '·',
+                night: ' ',
+                label: '░',
+                marker: '▒',
+                complication: '▒',
+                shadow: '·',
+                image: ['░', '▒', '▓', '█'],
+                ghost_hand: '▒',
+                hour_hand: '█',
+                minute_hand: '▓',
+                second_hand: '│',
+                gmt_hand: '╎',
+                center_cap: '●',
+                shades: ['░', '▒', '▓'],
+                follow_direction: false,
+                ascii_only: false,
+            },
+            _ => Glyphs {
+                background: '█',
+                face: '█',
+                day: '█',
+                night: '█',
+                label: '█',
+                marker: '█',
+                complication: '█',
+                shadow: '█',
+                image: ['█'; 4],
+                ghost_hand: '█',
+                hour_hand: '█',
+                minute_hand: '█',
+                second_hand: '█',
+                gmt_hand: '█',
+                center_cap: '█',
+                shades: ['░', '▒', '▓'],
+                follow_direction: false,
+                ascii_only: false,
+            },
+        }
+    }
+
+    /// Only ASCII characters, for serial consoles and old terminals, where hands and labels are
+    /// drawn with characters that follow their direction.
+    pub fn ascii() -> Glyphs {
+        Glyphs {
+            background: ' ',
+            face: 'o',
+            day: '.',
+            night: ' ',
+            label: '+',
+            marker: '*',
+            complication: '#',
+            shadow: '.',
+            image: ['.', ':', '%', '#'],
+            ghost_hand: ':',
+            hour_hand: '#',
+            minute_hand: '=',
+            second_hand: '|',
+            gmt_hand: '!',
+            center_cap: '@',
+            shades: ['.', ':', '%'],
+            follow_direction: true,
+            ascii_only: true,
+        }
+    }
+
+    /// Use `character` for every hand, whatever their direction.
+    pub fn with_hand(self, character: char) -> Glyphs {
+        Glyphs {
+            follow_direction: false,
+            ghost_hand: character,
+            hour_hand: character,
+            minute_hand: character,
+            second_hand: character,
+            gmt_hand: character,
+            ..self
+        }
+    }
+
+    pub fn with_face(self, character: char) -> Glyphs {
+        Glyphs {
+            face: character,
+            ..self
+        }
+    }
+
+    /// Use `character` for the hour labels, minute labels and markers.
+    pub fn with_label(self, character: char) -> Glyphs {
+        Glyphs {
+            label: character,
+            marker: character,
+            ..self
+        }
+    }
+
+    /// The character of `element`, where `degree` is the direction of the line that it is part
+    /// of, if any.
+    pub fn get(&self, element: Element, degree: Option<f32>) -> char {
+        match (element, degree) {
+            (
+                Element::GhostHand
+                | Element::HourHand
+                | Element::MinuteHand
+                | Element::SecondHand
+                | Element::GmtHand
+                | Element::HourLabel
+                | Element::MinuteLabel,
+                Some(degree),
+            ) if self.follow_direction => return line_character(degree),
+            (Element::Text(character), _) if self.ascii_only && !character.is_ascii() => {
+                return '?'
+            }
+            _ => (),
+        }
+        match element {
+            Element::Background => self.background,
+            Element::Face => self.face,
+            Element::Day => self.day,
+            Element::Night => self.night,
+            Element::Image(level) => self.image[(level.clamp(1, 4) - 1) as usize],
+            Element::HourLabel | Element::MinuteLabel => self.label,
+            Element::Marker => self.marker,
+            Element::Complication => self.complication,
+            Element::Shadow => self.shadow,
+            Element::GhostHand => self.ghost_hand,
+            Element::HourHand => self.hour_hand,
+            Element::MinuteHand => self.minute_hand,
+            Element::SecondHand => self.second_hand,
+            Element::GmtHand => self.gmt_hand,
+            Element::CenterCap => self.center_cap,
+            Element::Shade(level) => self.shades[(level.clamp(1, 3) - 1) as usize],
+            Element::Text(character) => character,
+            Element::Covered => ' ',
+        }
+    }
+}
+
+/// The character closest to a line in the direction of `degree`, clockwise from 12 o'clock.
+fn line_character(degree: f32) -> char {
+    // A line looks the same in the opposite direction
+    match (degree.rem_euclid(180.0) / 45.0).round() as u32 {
+        1 => '/',
+        2 => '-',
+        3 => '\\',
+        _ => '|',
+    }
+}
@@ -0,0 +1,248 @@
+use std::env;
+
+/// Languages that the messages of this program are translated into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lang {
+    English,
+    Chinese,
+    Malay,
+}
+
+impl Lang {
+    /// Parse a language tag such as `zh`, `ms_MY` or `en_US.UTF-8`.
+    pub fn from_tag(tag: &str) -> Option<Lang> {
+        let code = tag
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match code.as_str() {
+            "en" => Some(Lang::English),
+            "zh" => Some(Lang::Chinese),
+            "ms" => Some(Lang::Malay),
+            _ => None,
+        }
+    }
+
+    /// Determine the language from the `--lang` flag, falling back to the locale
+    /// environment variables, and finally to English.
+    ///
+    /// This has to be done before the CLI options are parsed, because the help text
+    /// itself is translated.
+    pub fn detect() -> Lang {
+        let args: Vec<String> = env::args().collect();
+        let from_args = args.iter().enumerate().find_map(|(index, arg)| {
+            if let Some(tag) = arg.strip_prefix("--lang=") {
+                Some(tag.to_string())
+            } else if arg == "--lang" {
+                args.get(index + 1).cloned()
+            } else {
+                None
+            }
+        });
+        from_args
+            .into_iter()
+            .chain(
+                ["LC_ALL", "LC_MESSAGES", "LANG"]
+                    .iter()
+                    .filter_map(|name| env::var(name).ok()),
+            )
+            .find_map(|tag| Lang::from_tag(&tag))
+            .unwrap_or(Lang::English)
+    }
+
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Lang::English => &ENGLISH,
+            Lang::Chinese => &CHINESE,
+            Lang::Malay => &MALAY,
+        }
+    }
+}
+
+/// Every user-facing sentence of this program.
+///
+/// Placeholders are written as `{name}` and substituted with `str::replace`.
+pub struct Messages {
+    /// Shown at the top of `--help`.
+    pub about: &'static str,
+    /// Placeholders: `{theme}` and `{themes}`.
+    pub unknown_theme: &'static str,
+    /// Placeholders: `{typo}` and `{theme}`.
+    pub theme_corrected: &'static str,
+    /// Placeholder: `{themes}`.
+    pub did_you_mean: &'static str,
+    pub term_size_unavailable: &'static str,
+    /// Placeholder: `{lang}`.
+    pub unknown_lang: &'static str,
+    /// Placeholder: `{fps}`.
+    pub tick_deprecated: &'static str,
+    /// Placeholders: `{days}` and `{hours}`.
+    pub days_hours: &'static str,
+    /// Placeholder: `{time}`.
+    pub confirm_alarm: &'static str,
+    /// Placeholders: `{time}` and `{snooze}`.
+    pub alarm_ringing: &'static str,
+    /// Shown in the corner while the hands are stopped.
+    pub paused: &'static str,
+    /// The bottom row of `--status-line`. Placeholders: `{theme}`, `{zone}`, `{fps}` and `{quit}`.
+    pub status_line: &'static str,
+    /// The timezone in the status line when the clock shows the time of this computer.
+    pub local_zone: &'static str,
+    /// Placeholders: `{count}` and `{path}`.
+    pub frames_captured: &'static str,
+    /// Placeholders: `{error}` and `{seconds}`.
+    pub restarting: &'static str,
+    /// Placeholders: `{server}` and `{error}`.
+    pub ntp_failed: &'static str,
+    /// Placeholders: `{command}` and `{error}`.
+    pub command_failed: &'static str,
+    /// Placeholders: `{uptime}`, `{timers}`, `{alarms}`, `{frames}` and `{frame_time}`.
+    pub stats_summary: &'static str,
+    /// Names of the days of the week from Monday, at most two columns wide each.
+    pub weekdays: [&'static str; 7],
+}
+
+const ENGLISH: Messages = Messages {
+    about: "
+Key bindings:
+  'q'     : quit
+  '-'     : decrease clock width
+  '='/'+' : increase clock width
+  'j'     : next theme
+  'k'     : previous theme
+  'r'     : rotate the dial by 90 degrees
+  'c'     : show or hide the calendar
+  Enter   : confirm
+  Esc     : cancel
+  'd'     : write the frames recorded by --debug-capture
+
+Keys can be remapped in the [keymap] section of ~/.config/analog-clock/config.toml.
+
+For more info, please refer https://github.com/wongjiahau/analog-clock
+",
+    unknown_theme: "
+  No theme has the name of '{theme}'.
+
+  Available themes are: {themes}
+
+  Themes can be added to ~/.config/analog-clock/themes, or feel free to contribute more theme at https://github.com/wongjiahau/analog-clock/blob/master/src/theme.rs
+",
+    theme_corrected: "No theme has the name of '{typo}', using '{theme}' instead",
+    did_you_mean: "  Did you mean {themes}?
+",
+    term_size_unavailable: "Unable to get term size :(",
+    unknown_lang: "Unsupported language '{lang}', available languages are: en, zh, ms",
+    tick_deprecated: "--tick is deprecated, use --fps {fps} instead",
+    days_hours: "{days}d {hours}h",
+    confirm_alarm: "Set an alarm at {time}? [Enter] yes [Esc] no",
+    alarm_ringing: "Alarm: {time} [Enter] dismiss [{snooze}] snooze",
+    paused: "paused",
+    status_line: "theme: {theme} · TZ: {zone} · {fps} fps · {quit}:quit",
+    local_zone: "local",
+    frames_captured: "{count} frames written to {path} [Esc] close",
+    restarting: "{error}, restarting in {seconds}s",
+    ntp_failed: "Unable to get the time from {server}: {error}",
+    command_failed: "Unable to run '{command}': {error}",
+    stats_summary: "Uptime: {uptime}
+Countdowns completed: {timers}
+Alarms fired: {alarms}
+Frames drawn: {frames}, {frame_time}ms each on average",
+    weekdays: ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+};
+
+const CHINESE: Messages = Messages {
+    about: "
+快捷键：
+  'q'     : 退出
+  '-'     : 缩小时钟宽度
+  '='/'+' : 增加时钟宽度
+  'j'     : 下一个主题
+  'k'     : 上一个主题
+  'r'     : 将表盘旋转 90 度
+  'c'     : 显示或隐藏日历
+  Enter   : 确认
+  Esc     : 取消
+  'd'     : 写出 --debug-capture 记录的画面
+
+快捷键可以在 ~/.config/analog-clock/config.toml 的 [keymap] 部分重新设置。
+
+更多信息请参考 https://github.com/wongjiahau/analog-clock
+",
+    unknown_theme: "
+  找不到名为 '{theme}' 的主题。
+
+  可用的主题有：{themes}
+
+  主题可以添加到 ~/.config/analog-clock/themes，也欢迎在 https://github.com/wongjiahau/analog-clock/blob/master/src/theme.rs 贡献更多主题
+",
+    theme_corrected: "找不到名为 '{typo}' 的主题，改用 '{theme}'",
+    did_you_mean: "  您是不是要找 {themes}？
+",
+    term_size_unavailable: "无法获取终端大小 :(",
+    unknown_lang: "不支持的语言 '{lang}'，可用的语言有：en, zh, ms",
+    tick_deprecated: "--tick 已弃用，请改用 --fps {fps}",
+    days_hours: "{days}天{hours}小时",
+    confirm_alarm: "设置 {time} 的闹钟？[Enter] 是 [Esc] 否",
+    alarm_ringing: "闹钟：{time} [Enter] 关闭 [{snooze}] 稍后提醒",
+    paused: "已暂停",
+    status_line: "主题：{theme} · 时区：{zone} · {fps} fps · {quit}:退出",
+    local_zone: "本地",
+    frames_captured: "已将 {count} 个画面写入 {path} [Esc] 关闭",
+    restarting: "{error}，{seconds} 秒后重新启动",
+    ntp_failed: "无法从 {server} 获取时间：{error}",
+    command_failed: "无法运行 '{command}'：{error}",
+    stats_summary: "运行时间：{uptime}
+完成的倒计时：{timers}
+响起的闹钟：{alarms}
+绘制的画面：{frames}，平均每个 {frame_time} 毫秒",
+    weekdays: ["一", "二", "三", "四", "五", "六", "日"],
+};
+
+const MALAY: Messages = Messages {
+    about: "
+Kekunci:
+  'q'     : keluar
+  '-'     : kurangkan lebar jam
+  '='/'+' : tambahkan lebar jam
+  'j'     : tema seterusnya
+  'k'     : tema sebelumnya
+  'r'     : putar dail sebanyak 90 darjah
+  'c'     : tunjuk atau sembunyikan kalendar
+  Enter   : sahkan
+  Esc     : batal
+  'd'     : tulis bingkai yang dirakam oleh --debug-capture
+
+Kekunci boleh ditukar dalam bahagian [keymap] fail ~/.config/analog-clock/config.toml.
+
+Untuk maklumat lanjut, sila rujuk https://github.com/wongjiahau/analog-clock
+",
+    unknown_theme: "
+  Tiada tema bernama '{theme}'.
+
+  Tema yang tersedia ialah: {themes}
+
+  Tema boleh ditambah ke ~/.config/analog-clock/themes, atau sila sumbangkan lebih banyak tema di https://github.com/wongjiahau/analog-clock/blob/master/src/theme.rs
+",
+    theme_corrected: "Tiada tema bernama '{typo}', menggunakan '{theme}' sebagai gantinya",
+    did_you_mean: "  Adakah anda maksudkan {themes}?
+",
+    term_size_unavailable: "Tidak dapat mendapatkan saiz terminal :(",
+    unknown_lang: "Bahasa '{lang}' tidak disokong, bahasa yang tersedia ialah: en, zh, ms",
+    tick_deprecated: "--tick sudah lapuk, gunakan --fps {fps} sebagai gantinya",
+    days_hours: "{days}h {hours}j",
+    confirm_alarm: "Tetapkan penggera pada {time}? [Enter] ya [Esc] tidak",
+    alarm_ringing: "Penggera: {time} [Enter] tutup [{snooze}] tunda",
+    paused: "dijeda",
+    status_line: "tema: {theme} · zon: {zone} · {fps} fps · {quit}:keluar",
+    local_zone: "tempatan",
+    frames_captured: "{count} bingkai ditulis ke {path} [Esc] tutup",
+    restarting: "{error}, dimulakan semula dalam {seconds}s",
+    ntp_failed: "Tidak dapat mendapatkan masa daripada {server}: {error}",
+    command_failed: "Tidak dapat menjalankan '{command}': {error}",
+    stats_summary: "Masa berjalan: {uptime}
+Kiraan detik selesai: {timers}
+Penggera berbunyi: {alarms}
+Bingkai dilukis: {frames}, purata {frame_time}ms setiap satu",
+    weekdays: ["Is", "Se", "Ra", "Kh", "Ju", "Sa", "Ah"],
+};
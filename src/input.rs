@@ -0,0 +1,39 @@
+use crossterm::event::{read, Event};
+use crossterm::Result;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Keys, mouse events and resizes of the terminal, read on a thread of their own, so that the
+/// render loop can sleep until its next frame and still be woken as soon as a key is pressed.
+pub struct Input {
+    events: Receiver<Result<Event>>,
+}
+
+impl Input {
+    pub fn start() -> Input {
+        let (sender, events) = mpsc::channel();
+        thread::spawn(move || loop {
+            let event = read();
+            let failed = event.is_err();
+            // Stop when the clock has quit, or after telling it that the terminal failed
+            if sender.send(event).is_err() || failed {
+                return;
+            }
+        });
+        Input { events }
+    }
+
+    /// Wait up to `timeout` for the next event. An error reading the terminal is returned once,
+    /// after which reading starts again, as the terminal may recover.
+    pub fn next(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => event.map(Some),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => {
+                *self = Input::start();
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::config::Keys;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+/// Everything that can be triggered by a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Wider,
+    Narrower,
+    ResetWidth,
+    ThemeNext,
+    ThemePrevious,
+    Confirm,
+    Cancel,
+    DebugCapture,
+    Rotate,
+    ToggleCalendar,
+    ToggleSecondHand,
+    ToggleHourLabels,
+    ToggleMinuteLabels,
+    /// Stop the hands at the time shown, or start them again.
+    Pause,
+    /// Ring a ringing alarm again a few minutes later.
+    Snooze,
+}
+
+impl Action {
+    const ALL: [Action; 16] = [
+        Action::Quit,
+        Action::Wider,
+        Action::Narrower,
+        Action::ResetWidth,
+        Action::ThemeNext,
+        Action::ThemePrevious,
+        Action::Confirm,
+        Action::Cancel,
+        Action::DebugCapture,
+        Action::Rotate,
+        Action::ToggleCalendar,
+        Action::ToggleSecondHand,
+        Action::ToggleHourLabels,
+        Action::ToggleMinuteLabels,
+        Action::Pause,
+        Action::Snooze,
+    ];
+
+    /// Name of this action in the `[keymap]` section of the config file.
+    fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Wider => "wider",
+            Action::Narrower => "narrower",
+            Action::ResetWidth => "reset-width",
+            Action::ThemeNext => "theme-next",
+            Action::ThemePrevious => "theme-previous",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::DebugCapture => "debug-capture",
+            Action::Rotate => "rotate",
+            Action::ToggleCalendar => "toggle-calendar",
+            Action::ToggleSecondHand => "toggle-second-hand",
+            Action::ToggleHourLabels => "toggle-hour-labels",
+            Action::ToggleMinuteLabels => "toggle-minute-labels",
+            Action::Pause => "pause",
+            Action::Snooze => "snooze",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Wider => &["+", "="],
+            Action::Narrower => &["-"],
+            Action::ResetWidth => &["0"],
+            Action::ThemeNext => &["j"],
+            Action::ThemePrevious => &["k"],
+            Action::Confirm => &["enter"],
+            Action::Cancel => &["esc"],
+            Action::DebugCapture => &["d"],
+            Action::Rotate => &["r"],
+            Action::ToggleCalendar => &["c"],
+            Action::ToggleSecondHand => &["s"],
+            Action::ToggleHourLabels => &["h"],
+            Action::ToggleMinuteLabels => &["m"],
+            Action::Pause => &["p", "space"],
+            Action::Snooze => &["z"],
+        }
+    }
+}
+
+/// Lookup table from keys to actions.
+pub struct Keymap {
+    bindings: HashMap<(KeyCode, KeyModifiers), Action>,
+    /// The first key of each action as it is written in the config file, to show it.
+    names: HashMap<Action, String>,
+}
+
+impl Keymap {
+    /// Create a keymap where the keys of the actions in `overrides` replace their default keys.
+    /// A key given in `overrides` is no longer a default key of another action, and a key bound
+    /// to two actions of `overrides` is an error.
+    pub fn new(overrides: &HashMap<String, Keys>) -> Result<Keymap, String> {
+        if let Some(name) = overrides.keys().find(|name| {
+            Action::ALL
+                .iter()
+                .all(|action| action.name() != name.as_str())
+        }) {
+            return Err(format!(
+                "Unknown action '{}' in keymap, expected one of {}",
+                name,
+                Action::ALL
+                    .iter()
+                    .map(|action| action.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let overridden = overrides
+            .values()
+            .flat_map(Keys::to_vec)
+            .map(|key| parse_key(&key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut bindings: HashMap<_, Action> = HashMap::new();
+        let mut names = HashMap::new();
+        for action in Action::ALL {
+            let keys = match overrides.get(action.name()) {
+                Some(keys) => keys.to_vec(),
+                None => action
+                    .default_keys()
+                    .iter()
+                    .filter(|key| parse_key(key).is_ok_and(|key| !overridden.contains(&key)))
+                    .map(|key| key.to_string())
+                    .collect(),
+            };
+            if let Some(key) = keys.first() {
+                names.insert(action, key.clone());
+            }
+            for key in keys {
+                match bindings.insert(parse_key(&key)?, action) {
+                    Some(other) if other != action => {
+                        return Err(format!(
+                            "Key '{}' in keymap is bound to both {} and {}",
+                            key,
+                            other.name(),
+                            action.name()
+                        ))
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(Keymap { bindings, names })
+    }
+
+    /// The key to show for `action`, if it has any.
+    pub fn key_name(&self, action: Action) -> Option<&str> {
+        self.names.get(&action).map(String::as_str)
+    }
+
+    /// The action bound to the given key event, if any.
+    /// Ctrl-C always quits, so that the clock can be exited even with a broken keymap.
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        if *event == KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL) {
+            return Some(Action::Quit);
+        }
+        self.bindings
+            .get(&normalize(event.code, event.modifiers))
+            .copied()
+    }
+}
+
+/// Shift is implied by the character itself (e.g. '+'), and terminals disagree on whether
+/// to report it, so it is ignored for characters.
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(_) => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+/// Parse a key such as `q`, `+`, `space`, `left`, `f1` or `ctrl-t`.
+fn parse_key(text: &str) -> Result<(KeyCode, KeyModifiers), String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text;
+    loop {
+        if let Some(stripped) = rest.strip_prefix("ctrl-") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("alt-") {
+            modifiers |= KeyModifiers::ALT;
+            rest = stripped;
+        } else {
+            break;
+        }
+    }
+    let code = match rest {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        _ => {
+            let mut characters = rest.chars();
+            match (characters.next(), characters.next()) {
+                (Some(character), None) => KeyCode::Char(character),
+                _ => match rest
+                    .strip_prefix('f')
+                    .and_then(|number| number.parse().ok())
+                {
+                    Some(number) => KeyCode::F(number),
+                    None => return Err(format!("Invalid key '{}' in keymap", text)),
+                },
+            }
+        }
+    };
+    Ok(normalize(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(config: &str) -> Result<Keymap, String> {
+        let overrides: HashMap<String, Keys> = toml::from_str(config).unwrap();
+        Keymap::new(&overrides)
+    }
+
+    fn key(character: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(character), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn overrides_take_the_default_keys_of_other_actions() {
+        let keymap = keymap(r#"theme-next = "p""#).unwrap();
+        assert_eq!(keymap.action(&key('p')), Some(Action::ThemeNext));
+        assert_eq!(keymap.action(&key(' ')), Some(Action::Pause));
+        assert_eq!(keymap.key_name(Action::Pause), Some("space"));
+        assert_eq!(keymap.action(&key('j')), None);
+    }
+
+    #[test]
+    fn rejects_a_key_bound_to_two_actions() {
+        assert!(keymap("theme-next = \"x\"\npause = [\"space\", \"x\"]").is_err());
+        assert!(keymap(r#"pause = ["x", "x"]"#).is_ok());
+        assert!(keymap(r#"paws = "x""#).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::calendar::CALENDAR_WIDTH;
+
+use analog_clock_core::Region;
+use std::str::FromStr;
+
+/// Narrowest screen that is split by `--layout full`, narrower screens only show the dial.
+const MIN_FULL_WIDTH: usize = 60;
+
+/// Columns beside the dial taken by `--show-calendar`, including a margin on both sides.
+const CALENDAR_PANE_WIDTH: usize = CALENDAR_WIDTH + 4;
+
+/// How the screen is divided between the dial and the panels next to it, see `--layout`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+    /// The dial fills the screen.
+    Dial,
+    /// The dial on the left, and the digital time above the calendar of the month on the right.
+    Full,
+}
+
+/// What is drawn in an area of the screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pane {
+    Dial,
+    DigitalTime,
+    Calendar,
+}
+
+impl Layout {
+    /// The area of each pane on a screen of the given size, with or without the calendar.
+    pub fn panes(self, (width, height): (usize, usize), calendar: bool) -> Vec<(Pane, Region)> {
+        let screen = Region {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        };
+        match self {
+            Layout::Full if width >= MIN_FULL_WIDTH => {
+                let (dial, panel) = split_columns(screen, width / 2);
+                if calendar {
+                    let (digital_time, calendar) = split_rows(panel);
+                    vec![
+                        (Pane::Dial, dial),
+                        (Pane::DigitalTime, digital_time),
+                        (Pane::Calendar, calendar),
+                    ]
+                } else {
+                    vec![(Pane::Dial, dial), (Pane::DigitalTime, panel)]
+                }
+            }
+            Layout::Dial if calendar && width >= CALENDAR_PANE_WIDTH * 2 => {
+                let (dial, calendar) = split_columns(screen, width - CALENDAR_PANE_WIDTH);
+                vec![(Pane::Dial, dial), (Pane::Calendar, calendar)]
+            }
+            _ => vec![(Pane::Dial, screen)],
+        }
+    }
+}
+
+/// Split `region` into the given number of columns on the left, and the rest on the right.
+fn split_columns(region: Region, left: usize) -> (Region, Region) {
+    (
+        Region {
+            width: left,
+            ..region
+        },
+        Region {
+            x: region.x + left,
+            width: region.width - left,
+            ..region
+        },
+    )
+}
+
+/// Split `region` into a top and a bottom half.
+fn split_rows(region: Region) -> (Region, Region) {
+    let top = region.height / 2;
+    (
+        Region {
+            height: top,
+            ..region
+        },
+        Region {
+            y: region.y + top,
+            height: region.height - top,
+            ..region
+        },
+    )
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Layout, String> {
+        match text {
+            "dial" => Ok(Layout::Dial),
+            "full" => Ok(Layout::Full),
+            _ => Err(format!(
+                "Invalid layout '{}', expected one of dial or full",
+                text
+            )),
+        }
+    }
+}
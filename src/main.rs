@@ -1,39 +1,434 @@
+mod alarm;
+mod astro;
+#[cfg(feature = "audio")]
+mod audio;
+mod battery;
+mod bench;
+mod calendar;
+mod capture;
 mod cli;
 mod clock;
+mod color;
+mod completions;
+mod config;
+#[cfg(unix)]
+mod control;
+mod demo;
+mod export;
+mod font;
+mod glyph;
+mod i18n;
+mod input;
+mod keymap;
+mod layout;
+mod motion;
+mod ntp;
+mod osc;
+mod output;
+mod segment;
+mod serve;
+mod state;
+mod stats;
+mod statusbar;
+#[cfg(feature = "tasks")]
+mod tasks;
+mod temperature;
 mod theme;
-use clock::run_clock;
+mod uptime;
+mod visibility;
+mod wall;
+mod words;
+mod zone;
+use clock::{print_once, run_clock};
 use std::{process::exit, time::Duration};
 use structopt::StructOpt;
-use theme::THEMES;
 
-use crate::{cli::CliOptions, clock::RunClockOptions};
+use crate::{
+    bench::{run_bench, BenchOptions},
+    cli::{parse_color, CliOptions, Command},
+    clock::{
+        load_face_image, Background, Elapsed, Epoch, EpochDirection, RunClockOptions, TimeSource,
+    },
+    color::ColorMode,
+    completions::print_completions,
+    config::{config_dir, load_config},
+    export::{export_clock, ExportOptions},
+    glyph::Glyphs,
+    i18n::Lang,
+    keymap::Keymap,
+    layout::Layout,
+    motion::Movement,
+    ntp::NetworkTime,
+    output::{run_output, FrameFormat, OutputOptions},
+    serve::{serve_clock, ServeOptions},
+    state::State,
+    statusbar::{run_statusbar, StatusbarOptions},
+    theme::ThemeSchedule,
+    visibility::Visibility,
+};
 
 fn main() {
-    let opt = CliOptions::from_args();
-    let theme_index = IntoIterator::into_iter(THEMES)
-        .enumerate()
-        .find_map(|(index, theme)| {
-            if theme.name == opt.theme {
-                Some(index)
-            } else {
-                None
+    let lang = Lang::detect();
+    let messages = lang.messages();
+    let matches = CliOptions::clap().about(messages.about).get_matches();
+    let mut opt = CliOptions::from_clap(&matches);
+    if let Some(tag) = &opt.lang {
+        if Lang::from_tag(tag).is_none() {
+            eprintln!("{}", messages.unknown_lang.replace("{lang}", tag));
+            exit(1)
+        }
+    }
+    let config = load_config().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(1)
+    });
+    opt.apply_defaults(&config.defaults, &matches)
+        .unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            exit(1)
+        });
+    if let (true, Some(tick)) = (matches.occurrences_of("tick") > 0, opt.tick) {
+        let fps = (100_000.0 / tick.max(1) as f64).round() / 100.0;
+        eprintln!(
+            "{}",
+            messages.tick_deprecated.replace("{fps}", &fps.to_string())
+        );
+    }
+    // As --layout has a default value, clap would take it as given and reject every --wall
+    if opt.wall.is_some() && opt.layout == Layout::Full {
+        eprintln!("The argument '--layout full' cannot be used with '--wall'");
+        exit(1)
+    }
+    let keymap = Keymap::new(&config.keymap).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(1)
+    });
+    let visibility = Visibility::new(&config.visibility).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(1)
+    });
+    let themes = theme::load_themes().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(1)
+    });
+    let color_mode = if opt.no_color {
+        ColorMode::Monochrome
+    } else {
+        opt.color_mode
+    };
+    if opt.list_themes {
+        for line in theme::list_themes(&themes, color_mode) {
+            println!("{}", line);
+        }
+        return;
+    }
+    let find_theme = |name: &str| {
+        theme::find_theme(&themes, name).unwrap_or_else(|| {
+            // A typo of a single theme is corrected, otherwise the closest themes are suggested
+            let similar = theme::similar_themes(&themes, name);
+            if let [index] = similar[..] {
+                eprintln!(
+                    "{}",
+                    messages
+                        .theme_corrected
+                        .replace("{typo}", name)
+                        .replace("{theme}", themes[index].name)
+                );
+                return index;
             }
+            let names = themes
+                .iter()
+                .map(|theme| theme.name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            eprintln!(
+                "\n{}",
+                messages
+                    .unknown_theme
+                    .replace("{theme}", name)
+                    .replace("{themes}", &names)
+            );
+            if !similar.is_empty() {
+                let suggestions = similar
+                    .iter()
+                    .map(|&index| format!("'{}'", themes[index].name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                eprintln!(
+                    "{}",
+                    messages.did_you_mean.replace("{themes}", &suggestions)
+                );
+            }
+            exit(1)
         })
-        .unwrap_or_else(|| {
-            eprintln!("\n
-  No theme has the name of '{}'.
-
-  Feel free to contribute more theme at https://github.com/wongjiahau/analog-clock/blob/master/src/theme.rs
-", opt.theme);
+    };
+    let theme_index = if opt.theme == "auto" {
+        match osc::query_background_color() {
+            Some(background) if theme::is_light(background) => find_theme(theme::LIGHT_THEME),
+            _ => find_theme(theme::DARK_THEME),
+        }
+    } else {
+        find_theme(&opt.theme)
+    };
+    let theme_schedule =
+        opt.theme_day
+            .as_ref()
+            .zip(opt.theme_night.as_ref())
+            .map(|(day, night)| ThemeSchedule {
+                day: find_theme(day),
+                night: find_theme(night),
+                night_from: opt.night_from,
+                night_to: opt.night_to,
+            });
+    let glyphs = if opt.ascii {
+        Glyphs::ascii()
+    } else {
+        Glyphs::new(color_mode)
+    };
+    let glyphs = match opt.hand_char {
+        Some(character) => glyphs.with_hand(character),
+        None => glyphs,
+    };
+    let glyphs = match opt.face_char {
+        Some(character) => glyphs.with_face(character),
+        None => glyphs,
+    };
+    let glyphs = match opt.label_char {
+        Some(character) => glyphs.with_label(character),
+        None => glyphs,
+    };
+    let network_time = opt.ntp.as_ref().map(|server| {
+        let server = server.as_deref().unwrap_or(ntp::DEFAULT_SERVER);
+        NetworkTime::start(server).unwrap_or_else(|error| {
+            eprintln!(
+                "{}",
+                messages
+                    .ntp_failed
+                    .replace("{server}", server)
+                    .replace("{error}", &error.to_string())
+            );
             exit(1)
-        });
-    match run_clock(RunClockOptions {
+        })
+    });
+    let state_file = if opt.no_persist {
+        None
+    } else {
+        state::state_file()
+    };
+    let mut restored = state_file
+        .as_deref()
+        .map(State::load)
+        .and_then(|state| state.terminals.get(&state::terminal()).copied())
+        .map(state::Adjustments::valid)
+        .unwrap_or_default();
+    // An aspect ratio given on the command line is used as is
+    if matches.occurrences_of("aspect-ratio") > 0 {
+        restored.aspect_ratio = None
+    }
+    let show_elapsed = opt
+        .show_elapsed
+        .map(|elapsed| elapsed.unwrap_or(Elapsed::Session));
+    if show_elapsed == Some(Elapsed::Uptime) {
+        if let Err(error) = uptime::uptime() {
+            eprintln!("{}", error);
+            exit(1)
+        }
+    }
+    let options = RunClockOptions {
+        themes,
         theme_index,
-        tick_interval: Duration::from_millis(opt.tick as u64),
+        theme_schedule,
+        time_source: match (opt.time, opt.utc_offset) {
+            (Some(time), _) => TimeSource::Fixed(time),
+            (None, Some(offset)) => TimeSource::UtcOffset(offset),
+            (None, None) => TimeSource::Local,
+        },
+        network_time,
+        tick_interval: opt.frame_interval(),
+        aspect_ratio: opt.aspect_ratio,
+        movement: if opt.station_clock {
+            Movement::Station
+        } else if let Some(movement) = opt.second_style {
+            movement
+        } else if opt.frame_interval() < Duration::from_secs(1) {
+            Movement::Sweep
+        } else {
+            Movement::Tick
+        },
         show_second_hand: !opt.hide_second_hand,
         show_hour_labels: !opt.hide_hour_labels,
         show_minute_labels: opt.show_minute_labels,
-    }) {
+        show_calendar: opt.show_calendar,
+        show_elapsed,
+        lang,
+        #[cfg(feature = "audio")]
+        audio_pulse: opt.audio_pulse,
+        #[cfg(feature = "tasks")]
+        task_source: if opt.taskwarrior {
+            Some(tasks::TaskSource::Taskwarrior)
+        } else {
+            opt.todo_txt.clone().map(tasks::TaskSource::TodoTxt)
+        },
+        target: opt.target,
+        face_image: opt.face_image.as_ref().map(|path| {
+            load_face_image(path).unwrap_or_else(|error| {
+                eprintln!("{}", error);
+                exit(1)
+            })
+        }),
+        alarms: opt.alarm.clone(),
+        alarm_file: opt
+            .alarm_file
+            .clone()
+            .or_else(|| config_dir().map(|dir| dir.join("alarms.toml"))),
+        background: if opt.background == "transparent" {
+            None
+        } else {
+            Some(Background {
+                color: parse_color(&opt.background).unwrap_or_else(|error| {
+                    eprintln!("{}", error);
+                    exit(1)
+                }),
+                area: opt.background_area,
+            })
+        },
+        chroma: opt.chroma,
+        color_mode,
+        color_temperature: opt.color_temp,
+        glyphs,
+        mouse: opt.mouse,
+        set_title: opt.set_title,
+        status_line: opt.status_line,
+        keymap,
+        quadrant: opt.quadrant,
+        wall: opt.wall,
+        wall_zones: opt.wall_zones.clone(),
+        layout: opt.layout,
+        rotation: opt.rotate,
+        dial_x: opt.x,
+        dial_y: opt.y,
+        dial_radius: opt.radius,
+        epoch: opt
+            .since
+            .map(|datetime| (datetime, EpochDirection::Since))
+            .or_else(|| opt.until.map(|datetime| (datetime, EpochDirection::Until)))
+            .map(|(datetime, direction)| Epoch {
+                datetime,
+                direction,
+                label: opt.label.clone(),
+            }),
+        finale: opt.finale,
+        finale_bell: opt.finale_bell,
+        finish_banner: opt.finish_banner.clone(),
+        finish_command: opt.finish_command.clone(),
+        mini_seconds: opt.mini_seconds,
+        mode: opt.mode,
+        dial_shape: opt.dial_shape,
+        supersample: opt.supersample,
+        markers: opt.markers,
+        numerals: opt.numerals,
+        hand_style: opt.hand_style,
+        face: opt.face,
+        second_tail: opt.second_tail,
+        center_cap: opt.center_cap,
+        blink_center: opt.blink_center,
+        shadow: opt.shadow,
+        chime: opt.chime,
+        chime_flash: opt.chime_flash,
+        complications: opt.complications().unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            exit(1)
+        }),
+        gmt_zone: opt.gmt_hand.take(),
+        visibility,
+        location: opt.latitude.zip(opt.longitude),
+        demo: opt.demo,
+        theme_cycle: None,
+        #[cfg(unix)]
+        control_socket: opt
+            .control_socket
+            .clone()
+            .map(|path| path.unwrap_or_else(control::default_path)),
+        screensaver: opt.screensaver,
+        debug_capture: opt.debug_capture,
+        stats: opt.stats,
+        stats_file: opt.stats_file.clone(),
+        state_file,
+        restored,
+    };
+    let output_size = opt.output_size.unwrap_or((80, 24));
+    let output = OutputOptions {
+        #[cfg(unix)]
+        fifo: opt.output_fifo.clone(),
+        frames_dir: opt.output_frames.clone(),
+        format: opt.format.unwrap_or(FrameFormat::Png),
+        overwrite: opt.overwrite,
+        count: opt.frames,
+        width: output_size.0,
+        height: output_size.1,
+    };
+    let result = match opt.command {
+        None if output.enabled() => run_output(options, output).map_err(|error| error.to_string()),
+        None if opt.once => print_once(options, opt.print_hash).map_err(|error| error.to_string()),
+        None => run_clock(options).map_err(|error| error.to_string()),
+        Some(Command::Export {
+            output,
+            width,
+            height,
+            animate,
+            duration,
+        }) => export_clock(
+            options,
+            ExportOptions {
+                output,
+                width,
+                height,
+                animation_duration: if animate { Some(duration) } else { None },
+            },
+        )
+        .map_err(|error| error.to_string()),
+        Some(Command::Bench {
+            frames,
+            width,
+            height,
+        }) => run_bench(
+            options,
+            BenchOptions {
+                frames,
+                width,
+                height,
+            },
+        )
+        .map_err(|error| error.to_string()),
+        Some(Command::Themes { interval }) => run_clock(RunClockOptions {
+            theme_cycle: Some(interval),
+            ..options
+        })
+        .map_err(|error| error.to_string()),
+        Some(Command::Serve {
+            port,
+            host,
+            width,
+            height,
+        }) => serve_clock(
+            options,
+            ServeOptions {
+                host,
+                port,
+                width,
+                height,
+            },
+        )
+        .map_err(|error| error.to_string()),
+        Some(Command::Completions { shell }) => {
+            print_completions(shell).map_err(|error| error.to_string())
+        }
+        Some(Command::Statusbar { follow, format }) => {
+            run_statusbar(options, StatusbarOptions { follow, format })
+                .map_err(|error| error.to_string())
+        }
+    };
+    match result {
         Ok(_) => (),
         Err(error) => eprintln!("{}", error),
     }
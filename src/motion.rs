@@ -0,0 +1,85 @@
+use chrono::{NaiveDateTime, Timelike};
+use std::str::FromStr;
+
+/// How the hands move as time passes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Movement {
+    /// The second hand jumps once per second, and the other hands creep along with it.
+    Tick,
+    /// The second hand sweeps continuously.
+    Sweep,
+    /// Like a Swiss railway clock, the second hand sweeps the dial in 58.5 seconds and waits
+    /// at 12, then the minute hand jumps to the next minute.
+    Station,
+    /// The second hand jumps once per second like `Tick`, but the jump is animated with a
+    /// quick ease out, like a quartz movement.
+    QuartzJump,
+}
+
+impl Movement {
+    /// Whether the hands move between seconds even with a long tick, so that the screen has to
+    /// be redrawn more often.
+    pub fn animated(self) -> bool {
+        self == Movement::QuartzJump
+    }
+}
+
+/// Parses `sweep`, `tick` or `quartz-jump`, the styles of `--second-style`.
+impl FromStr for Movement {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Movement, String> {
+        match text {
+            "sweep" => Ok(Movement::Sweep),
+            "tick" => Ok(Movement::Tick),
+            "quartz-jump" => Ok(Movement::QuartzJump),
+            _ => Err(format!(
+                "Invalid second style '{}', expected one of sweep, tick or quartz-jump",
+                text
+            )),
+        }
+    }
+}
+
+/// Angles of the hands in degrees, in the same convention as `Hand::degree`.
+pub struct HandAngles {
+    pub hour: f32,
+    pub minute: f32,
+    pub second: f32,
+}
+
+/// How long the second hand of a station clock takes to go around the dial.
+const STATION_SWEEP_SECONDS: f32 = 58.5;
+
+/// How long the second hand of a quartz movement takes to jump to the next second.
+const QUARTZ_JUMP_MILLIS: f32 = 150.0;
+
+pub fn hand_angles(datetime: NaiveDateTime, movement: Movement) -> HandAngles {
+    let second = datetime.second() as f32;
+    let millisecond = datetime.timestamp_subsec_millis() as f32;
+    let minute = datetime.minute() as f32;
+    let hour = (datetime.hour() % 12) as f32;
+
+    let (second, minute) = match movement {
+        Movement::Tick => (second, minute + second / 60.0),
+        Movement::Sweep => {
+            let second = second + millisecond / 1000.0;
+            (second, minute + second / 60.0)
+        }
+        Movement::Station => {
+            let second = (second + millisecond / 1000.0) / STATION_SWEEP_SECONDS * 60.0;
+            (second.min(60.0), minute)
+        }
+        Movement::QuartzJump => {
+            let progress = (millisecond / QUARTZ_JUMP_MILLIS).min(1.0);
+            // Ease out cubic, the hand leaves quickly and settles softly
+            let second = second - 1.0 + (1.0 - (1.0 - progress).powi(3));
+            (second, minute + second / 60.0)
+        }
+    };
+    HandAngles {
+        hour: (hour + minute / 60.0) / 12.0 * 360.0,
+        minute: minute / 60.0 * 360.0,
+        second: second / 60.0 * 360.0,
+    }
+}
@@ -0,0 +1,209 @@
+use chrono::Duration as ChronoDuration;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Server queried by `--ntp` when none is given.
+pub const DEFAULT_SERVER: &str = "pool.ntp.org";
+
+/// How often the time is queried again after startup.
+const RESYNC_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Port of NTP servers, used when the server is given without one.
+const PORT: u16 = 123;
+
+/// How long to wait for the answer of the server.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Seconds from 1900-01-01, the epoch of NTP, to 1970-01-01, the epoch of Unix.
+const NTP_TO_UNIX_SECONDS: u64 = 2_208_988_800;
+
+/// The difference between network time and the clock of this computer, which is kept up to
+/// date by a background thread.
+#[derive(Clone)]
+pub struct NetworkTime {
+    offset: Arc<Mutex<ChronoDuration>>,
+}
+
+impl NetworkTime {
+    /// Query `server` once, failing if it does not answer, then keep querying it every
+    /// `RESYNC_INTERVAL` in the background. Later failures keep the last known offset.
+    pub fn start(server: &str) -> io::Result<NetworkTime> {
+        let network_time = NetworkTime {
+            offset: Arc::new(Mutex::new(query(server)?)),
+        };
+        let offset = network_time.offset.clone();
+        let server = server.to_string();
+        thread::spawn(move || loop {
+            thread::sleep(RESYNC_INTERVAL);
+            if let Ok(new_offset) = query(&server) {
+                *offset.lock().unwrap() = new_offset
+            }
+        });
+        Ok(network_time)
+    }
+
+    /// How far network time is ahead of the clock of this computer.
+    pub fn offset(&self) -> ChronoDuration {
+        *self.offset.lock().unwrap()
+    }
+}
+
+/// Ask an NTP server for the time with a single SNTP request, see RFC 4330, and return how far
+/// it is ahead of the clock of this computer.
+fn query(server: &str) -> io::Result<ChronoDuration> {
+    let address = resolve(server)?;
+    let unspecified = match address {
+        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    let socket = UdpSocket::bind((unspecified, 0))?;
+    socket.connect(address)?;
+
+    let mut request = [0; 48];
+    // Leap indicator 0, version 4, mode 3 (client)
+    request[0] = 0b00_100_011;
+    let sent = SystemTime::now();
+    request[40..48].copy_from_slice(&to_ntp_timestamp(sent));
+    socket.send(&request)?;
+
+    // Only a reply echoing our transmit timestamp as its originate timestamp answers this
+    // request, anything else arriving on the socket is stray or spoofed and skipped
+    let deadline = Instant::now() + TIMEOUT;
+    let mut response = [0; 48];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::from_secs(0) {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no NTP response to the request",
+            ));
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let length = socket.recv(&mut response)?;
+        if length == response.len() && response[24..32] == request[40..48] {
+            break;
+        }
+    }
+    let received = SystemTime::now();
+    let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason.to_string());
+    if response[0] & 0b111 != 4 {
+        return Err(invalid("unexpected NTP mode"));
+    }
+    // Stratum 0 is a "kiss-o'-death", asking the client to go away
+    if response[1] == 0 {
+        return Err(invalid("NTP server refused the request"));
+    }
+
+    let server_received = from_ntp_timestamp(&response[32..40]);
+    let server_sent = from_ntp_timestamp(&response[40..48]);
+    let (sent, received) = (to_micros(sent), to_micros(received));
+    // The usual clock offset of NTP, which cancels out the network delay if it is symmetric
+    let offset = ((server_received - sent) + (server_sent - received)) / 2;
+    Ok(ChronoDuration::microseconds(offset))
+}
+
+/// The address of `server`, which is a host name or IP address, optionally followed by a
+/// port. IPv6 addresses with a port are written in brackets, e.g. `[::1]:123`.
+fn resolve(server: &str) -> io::Result<SocketAddr> {
+    if let Ok(address) = server.parse::<SocketAddr>() {
+        return Ok(address);
+    }
+    if let Ok(ip) = server.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, PORT));
+    }
+    let (host, port) = match server.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (host, port),
+            Err(_) => (server, PORT),
+        },
+        _ => (server, PORT),
+    };
+    (host, port).to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} has no address", server),
+        )
+    })
+}
+
+fn to_micros(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_micros() as i64,
+        Err(error) => -(error.duration().as_micros() as i64),
+    }
+}
+
+/// Microseconds since the Unix epoch of a 64-bit NTP timestamp, which is the number of
+/// seconds since the NTP epoch followed by the fraction of a second, both big-endian.
+fn from_ntp_timestamp(bytes: &[u8]) -> i64 {
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as i64;
+    (seconds - NTP_TO_UNIX_SECONDS as i64) * 1_000_000 + ((fraction * 1_000_000) >> 32)
+}
+
+fn to_ntp_timestamp(time: SystemTime) -> [u8; 8] {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = (duration.as_secs() + NTP_TO_UNIX_SECONDS) as u32;
+    let fraction = ((duration.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    let mut bytes = [0; 8];
+    bytes[..4].copy_from_slice(&seconds.to_be_bytes());
+    bytes[4..].copy_from_slice(&(fraction as u32).to_be_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_ip_addresses() {
+        assert_eq!(
+            resolve("127.0.0.1").unwrap(),
+            "127.0.0.1:123".parse().unwrap()
+        );
+        assert_eq!(
+            resolve("127.0.0.1:4123").unwrap(),
+            "127.0.0.1:4123".parse().unwrap()
+        );
+        assert_eq!(resolve("::1").unwrap(), "[::1]:123".parse().unwrap());
+        assert_eq!(
+            resolve("2001:db8::1").unwrap(),
+            "[2001:db8::1]:123".parse().unwrap()
+        );
+        assert_eq!(
+            resolve("[::1]:4123").unwrap(),
+            "[::1]:4123".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn query_skips_replies_to_other_requests() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let mut request = [0; 48];
+            let (_, client) = server.recv_from(&mut request).unwrap();
+            let mut reply = [0; 48];
+            // Version 4, mode 4 (server), stratum 1
+            reply[0] = 0b00_100_100;
+            reply[1] = 1;
+            // Ten minutes ahead of the client
+            let ahead = to_ntp_timestamp(SystemTime::now() + Duration::from_secs(600));
+            reply[32..40].copy_from_slice(&ahead);
+            reply[40..48].copy_from_slice(&ahead);
+            // A reply to some other request comes first and claims the clock is on time
+            let mut stray = reply;
+            stray[24..32].copy_from_slice(&[1; 8]);
+            stray[32..40].copy_from_slice(&request[40..48]);
+            stray[40..48].copy_from_slice(&request[40..48]);
+            server.send_to(&stray, client).unwrap();
+            reply[24..32].copy_from_slice(&request[40..48]);
+            server.send_to(&reply, client).unwrap();
+        });
+        let offset = query(&address).unwrap();
+        assert!((offset - ChronoDuration::seconds(600)).num_seconds().abs() <= 1);
+    }
+}
@@ -0,0 +1,79 @@
+use colors_transform::Rgb;
+
+/// How long to wait for the terminal to answer. Terminals that do not support a query never
+/// answer it.
+#[cfg(unix)]
+const QUERY_TIMEOUT_MILLIS: i32 = 200;
+
+/// Ask the terminal for its background color with the OSC 11 escape sequence.
+///
+/// Returns `None` if there is no terminal, or it does not answer in time.
+/// Must be called before the clock takes over the terminal, as the answer arrives on stdin.
+#[cfg(unix)]
+pub fn query_background_color() -> Option<Rgb> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    // Raw mode, so that the answer is neither echoed nor held back until Enter is pressed
+    enable_raw_mode().ok()?;
+    let answer = (|| {
+        tty.write_all(b"\x1b]11;?\x07").ok()?;
+        tty.flush().ok()?;
+        let started = Instant::now();
+        let mut answer = Vec::new();
+        loop {
+            let remaining = QUERY_TIMEOUT_MILLIS - started.elapsed().as_millis() as i32;
+            let mut poll_fd = libc::pollfd {
+                fd: tty.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // Reading without polling first would block forever if the terminal never answers
+            if remaining <= 0 || unsafe { libc::poll(&mut poll_fd, 1, remaining) } <= 0 {
+                return None;
+            }
+            let mut buffer = [0; 64];
+            let length = tty.read(&mut buffer).ok()?;
+            answer.extend_from_slice(&buffer[..length]);
+            // The answer ends with either BEL or ST (ESC \)
+            if answer.ends_with(b"\x07") || answer.ends_with(b"\x1b\\") {
+                return Some(answer);
+            }
+        }
+    })();
+    disable_raw_mode().ok()?;
+    parse_color_answer(&String::from_utf8_lossy(&answer?))
+}
+
+#[cfg(not(unix))]
+pub fn query_background_color() -> Option<Rgb> {
+    None
+}
+
+/// Parse the color of an answer such as `ESC ] 11 ; rgb:2e2e/3434/4040 BEL`, where each channel
+/// has 1 to 4 hex digits.
+#[cfg(unix)]
+fn parse_color_answer(answer: &str) -> Option<Rgb> {
+    let start = answer.find("rgb:")? + "rgb:".len();
+    let channels: Vec<f32> = answer[start..]
+        .trim_end_matches(['\x07', '\x1b', '\\'])
+        .split('/')
+        .map(|channel| {
+            let value = u32::from_str_radix(channel, 16).ok()?;
+            let max = 16u32.checked_pow(channel.len() as u32)? - 1;
+            Some(value as f32 / max as f32 * 255.0)
+        })
+        .collect::<Option<_>>()?;
+    match channels[..] {
+        [red, green, blue] => Some(Rgb::from(red, green, blue)),
+        _ => None,
+    }
+}
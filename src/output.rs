@@ -0,0 +1,200 @@
+use crate::clock::{render_frames, Matrix, RunClockOptions};
+use crate::export::{BACKGROUND, CELL_HEIGHT, CELL_WIDTH};
+
+use colors_transform::Rgb;
+#[cfg(unix)]
+use crossterm::{cursor, QueueableCommand};
+use std::fs;
+#[cfg(unix)]
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Format of the files written by `--output-frames`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameFormat {
+    /// The frame as text with ANSI colors, as printed by `--once`.
+    Ansi,
+    Png,
+}
+
+impl FrameFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Ansi => "ansi",
+            FrameFormat::Png => "png",
+        }
+    }
+}
+
+impl FromStr for FrameFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<FrameFormat, String> {
+        match text {
+            "ansi" => Ok(FrameFormat::Ansi),
+            "png" => Ok(FrameFormat::Png),
+            _ => Err(format!("Invalid format '{}', expected ansi or png", text)),
+        }
+    }
+}
+
+pub struct OutputOptions {
+    /// Named pipe that frames are streamed to, as they are drawn on a terminal.
+    #[cfg(unix)]
+    pub fifo: Option<PathBuf>,
+    /// Directory where each frame is written to a file of its own.
+    pub frames_dir: Option<PathBuf>,
+    pub format: FrameFormat,
+    /// Whether every frame replaces the file of the one before it, instead of having its own.
+    pub overwrite: bool,
+    /// How many frames are written before stopping, if not forever.
+    pub count: Option<NonZeroU64>,
+    /// Size of the frames in characters.
+    pub width: usize,
+    pub height: usize,
+}
+
+impl OutputOptions {
+    /// Whether frames go anywhere other than the terminal.
+    pub fn enabled(&self) -> bool {
+        #[cfg(unix)]
+        if self.fifo.is_some() {
+            return true;
+        }
+        self.frames_dir.is_some()
+    }
+}
+
+/// Draw the clock every tick to a named pipe or to files instead of the terminal, so that other
+/// programs, such as the overlays of streaming software, can show it.
+pub fn run_output(options: RunClockOptions, output: OutputOptions) -> io::Result<()> {
+    #[cfg(unix)]
+    let mut fifo = match &output.fifo {
+        Some(path) => Some(Fifo::open(path.clone())?),
+        None => None,
+    };
+    if let Some(dir) = &output.frames_dir {
+        fs::create_dir_all(dir)?
+    }
+    let mut index: u64 = 0;
+    render_frames(
+        &options,
+        output.count,
+        || (output.width, output.height),
+        |matrix, _previous| {
+            #[cfg(unix)]
+            if let Some(fifo) = &mut fifo {
+                fifo.write(matrix, _previous, &options)?
+            }
+            if let Some(dir) = &output.frames_dir {
+                let extension = output.format.extension();
+                if output.overwrite {
+                    // Renaming replaces the file at once, so that readers never see half a frame
+                    let partial = dir.join(format!(".frame.{}", extension));
+                    write_frame(matrix, &partial, output.format, &options)?;
+                    fs::rename(partial, dir.join(format!("frame.{}", extension)))?
+                } else {
+                    index += 1;
+                    let path = dir.join(format!("frame-{:06}.{}", index, extension));
+                    write_frame(matrix, &path, output.format, &options)?
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Write `matrix` to a file of its own at `path`.
+fn write_frame(
+    matrix: &Matrix,
+    path: &Path,
+    format: FrameFormat,
+    options: &RunClockOptions,
+) -> io::Result<()> {
+    match format {
+        FrameFormat::Ansi => {
+            let text: String = matrix
+                .to_lines(options.color_mode, &options.glyphs)
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect();
+            fs::write(path, text)
+        }
+        FrameFormat::Png => matrix
+            .to_image(
+                CELL_WIDTH,
+                CELL_HEIGHT,
+                Rgb::from_hex_str(BACKGROUND).unwrap(),
+            )
+            .save(path)
+            .map_err(io::Error::other),
+    }
+}
+
+/// A named pipe that frames are streamed to. Opening it waits for a reader, such as
+/// `cat /tmp/clock.ansi`, and when the reader goes away, it waits for the next one.
+#[cfg(unix)]
+struct Fifo {
+    path: PathBuf,
+    file: File,
+    /// Whether nothing has been written since the reader started reading, so that the next
+    /// frame is written in full.
+    fresh: bool,
+}
+
+#[cfg(unix)]
+impl Fifo {
+    /// Open the named pipe at `path`, which is made if it does not exist.
+    fn open(path: PathBuf) -> io::Result<Fifo> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        if !path.exists() {
+            let name = CString::new(path.as_os_str().as_bytes()).map_err(io::Error::other)?;
+            if unsafe { libc::mkfifo(name.as_ptr(), 0o644) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        let file = OpenOptions::new().write(true).open(&path)?;
+        Ok(Fifo {
+            path,
+            file,
+            fresh: true,
+        })
+    }
+
+    fn write(
+        &mut self,
+        matrix: &Matrix,
+        previous: Option<&Matrix>,
+        options: &RunClockOptions,
+    ) -> io::Result<()> {
+        // A new reader gets the whole frame, without the cursor
+        let result = if self.fresh {
+            self.file.queue(cursor::Hide).map(drop).and_then(|()| {
+                matrix.write_changes(&mut self.file, None, options.color_mode, &options.glyphs)
+            })
+        } else {
+            matrix.write_changes(
+                &mut self.file,
+                previous,
+                options.color_mode,
+                &options.glyphs,
+            )
+        };
+        match result {
+            Ok(()) => {
+                self.fresh = false;
+                Ok(())
+            }
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {
+                *self = Fifo::open(self.path.clone())?;
+                Ok(())
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
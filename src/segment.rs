@@ -0,0 +1,128 @@
+/// A seven-segment font, for digits that are drawn as large as the screen.
+///
+/// Segments are named from `a` at the top, clockwise around the digit, to `g` in the middle.
+/// Bit 0 of a glyph is segment `a`.
+const A: u8 = 1;
+const B: u8 = 1 << 1;
+const C: u8 = 1 << 2;
+const D: u8 = 1 << 3;
+const E: u8 = 1 << 4;
+const F: u8 = 1 << 5;
+const G: u8 = 1 << 6;
+
+fn segments(digit: char) -> u8 {
+    match digit {
+        '0' => A | B | C | D | E | F,
+        '1' => B | C,
+        '2' => A | B | G | E | D,
+        '3' => A | B | G | C | D,
+        '4' => F | G | B | C,
+        '5' => A | F | G | C | D,
+        '6' => A | F | G | E | D | C,
+        '7' => A | B | C,
+        '8' => A | B | C | D | E | F | G,
+        '9' => A | B | C | D | F | G,
+        '-' => G,
+        _ => 0,
+    }
+}
+
+/// Size of a glyph in cells, where strokes are `thickness` cells thick.
+#[derive(Clone, Copy, Debug)]
+pub struct SegmentSize {
+    pub width: usize,
+    pub height: usize,
+    pub thickness: usize,
+}
+
+impl SegmentSize {
+    /// The largest glyphs whose `text` fits in `width` x `height` cells, if any.
+    /// Glyphs are separated by a gap as wide as a stroke.
+    pub fn fitting(text: &str, width: usize, height: usize) -> Option<SegmentSize> {
+        (3..=width)
+            .rev()
+            .map(SegmentSize::with_width)
+            .find(|size| size.text_width(text) <= width && size.height <= height)
+    }
+
+    /// Digits are almost twice as tall as they are wide.
+    fn with_width(width: usize) -> SegmentSize {
+        let thickness = (width / 5).max(1);
+        SegmentSize {
+            width,
+            height: width * 2 - thickness,
+            thickness,
+        }
+    }
+
+    /// Colons are as wide as a stroke.
+    fn glyph_width(&self, character: char) -> usize {
+        if character == ':' {
+            self.thickness
+        } else {
+            self.width
+        }
+    }
+
+    pub fn text_width(&self, text: &str) -> usize {
+        let glyphs: usize = text
+            .chars()
+            .map(|character| self.glyph_width(character))
+            .sum();
+        glyphs + text.chars().count().saturating_sub(1) * self.thickness
+    }
+
+    /// The cells of `character` relative to its top left corner.
+    pub fn cells(&self, character: char) -> Vec<(usize, usize)> {
+        let SegmentSize {
+            width,
+            height,
+            thickness,
+        } = *self;
+        let middle = (height - thickness) / 2;
+        let rectangles = if character == ':' {
+            vec![
+                (
+                    0..thickness,
+                    height / 3 - thickness / 2..height / 3 + thickness.div_ceil(2),
+                ),
+                (
+                    0..thickness,
+                    height * 2 / 3 - thickness / 2..height * 2 / 3 + thickness.div_ceil(2),
+                ),
+            ]
+        } else {
+            let lit = segments(character);
+            [
+                (A, (0..width, 0..thickness)),
+                (B, (width - thickness..width, 0..middle + thickness)),
+                (C, (width - thickness..width, middle..height)),
+                (D, (0..width, height - thickness..height)),
+                (E, (0..thickness, middle..height)),
+                (F, (0..thickness, 0..middle + thickness)),
+                (G, (0..width, middle..middle + thickness)),
+            ]
+            .iter()
+            .filter(|(segment, _)| lit & segment != 0)
+            .map(|(_, rectangle)| rectangle.clone())
+            .collect()
+        };
+        rectangles
+            .into_iter()
+            .flat_map(|(columns, rows)| {
+                columns.flat_map(move |x| rows.clone().map(move |y| (x, y)))
+            })
+            .collect()
+    }
+
+    /// The left edge of each character of `text`.
+    pub fn offsets(&self, text: &str) -> Vec<usize> {
+        text.chars()
+            .scan(0, |left, character| {
+                let offset = *left;
+                *left += self.glyph_width(character) + self.thickness;
+                Some(offset)
+            })
+            .collect()
+    }
+}
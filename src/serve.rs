@@ -0,0 +1,108 @@
+use crate::clock::{stream_frames, RunClockOptions};
+
+use std::io::{self, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+pub struct ServeOptions {
+    /// Address to listen on.
+    pub host: String,
+    pub port: u16,
+    /// Size of the screen of clients that do not tell their window size.
+    pub width: usize,
+    pub height: usize,
+}
+
+// Telnet commands and options, see RFC 854, RFC 857, RFC 858 and RFC 1073
+const IAC: u8 = 255;
+const WILL: u8 = 251;
+const DO: u8 = 253;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const NAWS: u8 = 31;
+
+/// Largest window that a client is drawn a clock for, as every frame is drawn at the size of
+/// the window, which a client could otherwise make too big to allocate.
+const MAX_WINDOW_SIZE: (usize, usize) = (500, 200);
+
+/// Bytes kept of a subnegotiation, which is more than the `NAWS width height IAC` that is read.
+/// The rest of a longer one is dropped.
+const MAX_SUBNEGOTIATION: usize = 8;
+
+/// Stream the clock to every client that connects with telnet or a raw TCP connection such as
+/// `nc`, each in its own thread. Telnet clients are asked for their window size, which they
+/// also send again when it changes.
+pub fn serve_clock(options: RunClockOptions, serve: ServeOptions) -> io::Result<()> {
+    let listener = TcpListener::bind((serve.host.as_str(), serve.port))?;
+    let options = Arc::new(options);
+    for stream in listener.incoming() {
+        // A client that fails to connect does not stop the others
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let options = options.clone();
+        let size = (serve.width, serve.height);
+        thread::spawn(move || serve_client(&options, stream, size));
+    }
+    Ok(())
+}
+
+fn serve_client(
+    options: &RunClockOptions,
+    mut stream: TcpStream,
+    size: (usize, usize),
+) -> io::Result<()> {
+    // Echoing and line mode are turned off, so that keys typed by the client are not drawn
+    // over the clock
+    stream.write_all(&[IAC, WILL, ECHO, IAC, WILL, SUPPRESS_GO_AHEAD, IAC, DO, NAWS])?;
+    let size = Arc::new(Mutex::new(size));
+    let reader = stream.try_clone()?;
+    let negotiated = size.clone();
+    thread::spawn(move || read_window_size(reader, &negotiated));
+    stream_frames(options, &mut stream, || *size.lock().unwrap())
+}
+
+/// Read what the client sends until it disconnects, keeping `size` up to date with the window
+/// size in its `IAC SB NAWS width height IAC SE` subnegotiations, up to `MAX_WINDOW_SIZE`.
+/// Anything else is ignored.
+fn read_window_size(stream: TcpStream, size: &Mutex<(usize, usize)>) {
+    let mut subnegotiation: Option<Vec<u8>> = None;
+    let mut previous = 0;
+    for byte in BufReader::new(stream).bytes() {
+        let byte = match byte {
+            Ok(byte) => byte,
+            Err(_) => return,
+        };
+        match (&mut subnegotiation, previous, byte) {
+            (None, IAC, SB) => subnegotiation = Some(Vec::new()),
+            (Some(data), IAC, SE) => {
+                // The last byte is the IAC before SE
+                data.pop();
+                if let [NAWS, width_high, width_low, height_high, height_low] = data[..] {
+                    let width = u16::from_be_bytes([width_high, width_low]) as usize;
+                    let height = u16::from_be_bytes([height_high, height_low]) as usize;
+                    if width > 0 && height > 0 {
+                        *size.lock().unwrap() =
+                            (width.min(MAX_WINDOW_SIZE.0), height.min(MAX_WINDOW_SIZE.1))
+                    }
+                }
+                subnegotiation = None
+            }
+            (Some(data), IAC, IAC) => {
+                // An escaped 255 in the data
+                data.pop();
+                data.push(IAC);
+                previous = 0;
+                continue;
+            }
+            (Some(data), _, _) if data.len() < MAX_SUBNEGOTIATION => data.push(byte),
+            (Some(_), _, _) => (),
+            (None, _, _) => (),
+        }
+        previous = byte
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Content of `~/.local/state/analog-clock/state.toml`, which keeps what was adjusted while the
+/// clock was running for the next time it starts, unless `--no-persist` is given. For example:
+///
+/// ```toml
+/// [terminals.xterm-256color]
+/// aspect-ratio = 2.2
+/// zoom = 1.21
+/// ```
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct State {
+    /// Adjustments by `$TERM`, as how wide a cell is depends on the terminal and its font.
+    pub terminals: BTreeMap<String, Adjustments>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Adjustments {
+    /// Set with the keys of `wider` and `narrower`, or by scrolling while holding Ctrl.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<f32>,
+    /// Size of the dial relative to the screen, set by scrolling.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<f32>,
+}
+
+impl Adjustments {
+    /// These adjustments without those that the clock could not have saved, such as from a
+    /// state file that was edited by hand, as they could keep the clock from starting.
+    pub fn valid(self) -> Adjustments {
+        Adjustments {
+            aspect_ratio: self
+                .aspect_ratio
+                .filter(|aspect_ratio| aspect_ratio.is_finite() && *aspect_ratio >= 1.0),
+            zoom: self.zoom.filter(|zoom| zoom.is_finite() && *zoom > 0.0),
+        }
+    }
+}
+
+/// The file where the state is kept, which is in `$XDG_STATE_HOME/analog-clock`, or
+/// `~/.local/state/analog-clock` by default.
+pub fn state_file() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .map(|dir| dir.join("analog-clock").join("state.toml"))
+}
+
+/// The terminal that adjustments are saved for.
+pub fn terminal() -> String {
+    env::var("TERM").unwrap_or_else(|_| "unknown".to_string())
+}
+
+impl State {
+    /// Read the state at `path`. A file that is missing or cannot be read is treated as empty,
+    /// as losing adjustments is better than not starting.
+    pub fn load(path: &Path) -> State {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write this state to `path`, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = toml::to_string(self).map_err(|error| error.to_string())?;
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, content))
+            .map_err(|error| format!("Unable to save state to {}: {}", path.display(), error))
+    }
+}
@@ -0,0 +1,91 @@
+use crate::i18n::Lang;
+
+use chrono::NaiveDateTime;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// What happened while the clock was running, see `--stats` and `--stats-file`.
+pub struct Stats {
+    started_at: NaiveDateTime,
+    started: Instant,
+    /// Countdowns of `--until` that reached zero.
+    pub timers_completed: u32,
+    pub alarms_fired: u32,
+    frames: u32,
+    /// Time spent drawing and printing all frames.
+    frame_time: Duration,
+}
+
+impl Stats {
+    pub fn new(started_at: NaiveDateTime) -> Stats {
+        Stats {
+            started_at,
+            started: Instant::now(),
+            timers_completed: 0,
+            alarms_fired: 0,
+            frames: 0,
+            frame_time: Duration::ZERO,
+        }
+    }
+
+    pub fn record_frame(&mut self, frame_time: Duration) {
+        self.frames += 1;
+        self.frame_time += frame_time
+    }
+
+    fn average_frame_time(&self) -> Duration {
+        self.frame_time / self.frames.max(1)
+    }
+
+    /// A few lines for people.
+    pub fn summary(&self, lang: Lang) -> String {
+        lang.messages()
+            .stats_summary
+            .replace("{uptime}", &format_uptime(self.started.elapsed()))
+            .replace("{timers}", &self.timers_completed.to_string())
+            .replace("{alarms}", &self.alarms_fired.to_string())
+            .replace("{frames}", &self.frames.to_string())
+            .replace(
+                "{frame_time}",
+                &format!("{:.2}", self.average_frame_time().as_secs_f64() * 1000.0),
+            )
+    }
+
+    /// Append one line of `key=value` pairs to the file at `path`, which is created if needed.
+    pub fn append_to(&self, path: &Path) -> Result<(), String> {
+        let line = format!(
+            "{} uptime={}s timers={} alarms={} frames={} frame-time={}us\n",
+            self.started_at.format("%Y-%m-%dT%H:%M:%S"),
+            self.started.elapsed().as_secs(),
+            self.timers_completed,
+            self.alarms_fired,
+            self.frames,
+            self.average_frame_time().as_micros()
+        );
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|error| {
+                format!(
+                    "Unable to write statistics to {}: {}",
+                    path.display(),
+                    error
+                )
+            })
+    }
+}
+
+/// Such as `1h 02m 03s`.
+fn format_uptime(uptime: Duration) -> String {
+    let seconds = uptime.as_secs();
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
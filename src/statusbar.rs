@@ -0,0 +1,45 @@
+use crate::clock::RunClockOptions;
+
+use chrono::{NaiveTime, Timelike};
+use std::io::{self, stdout, Write};
+use std::thread;
+
+pub struct StatusbarOptions {
+    /// Print a new line every tick instead of printing once.
+    pub follow: bool,
+    /// Format of the digital time, as in `strftime`.
+    pub format: String,
+}
+
+/// Print a one-line clock, such as `🕙 10:09`, for status bars like tmux and polybar.
+pub fn run_statusbar(options: RunClockOptions, statusbar: StatusbarOptions) -> io::Result<()> {
+    let mut stdout = stdout();
+    loop {
+        let now = options.now();
+        writeln!(
+            stdout,
+            "{} {}",
+            clock_emoji(now.time()),
+            now.format(&statusbar.format)
+        )?;
+        stdout.flush()?;
+        if !statusbar.follow {
+            return Ok(());
+        }
+        thread::sleep(options.tick_interval);
+    }
+}
+
+/// The clock face emoji closest to the given time, which only has a resolution of half an hour.
+pub fn clock_emoji(time: NaiveTime) -> char {
+    let minutes = (time.hour() % 12) * 60 + time.minute();
+    let halves = (minutes + 15) / 30 % 24;
+    // The emojis start from one o'clock, with the half hours in a separate block
+    let hour_index = (halves / 2 + 11) % 12;
+    let first = if halves.is_multiple_of(2) {
+        0x1F550
+    } else {
+        0x1F55C
+    };
+    char::from_u32(first + hour_index).unwrap_or('🕛')
+}
@@ -0,0 +1,243 @@
+use crate::cli::parse_datetime;
+use crate::clock::Marker;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use colors_transform::Rgb;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How often the tasks are read again.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Debug)]
+pub enum TaskSource {
+    /// Pending tasks reported by `task export`.
+    Taskwarrior,
+    /// A todo.txt file, where tasks are due at `due:YYYY-MM-DDTHH:MM`.
+    TodoTxt(PathBuf),
+}
+
+#[derive(Clone, Copy)]
+enum Priority {
+    High,
+    Medium,
+    Low,
+    None,
+}
+
+impl Priority {
+    fn color(self) -> Rgb {
+        let hex = match self {
+            Priority::High => "#BF616A",
+            Priority::Medium => "#EBCB8B",
+            Priority::Low => "#A3BE8C",
+            Priority::None => "#D8DEE9",
+        };
+        Rgb::from_hex_str(hex).unwrap()
+    }
+}
+
+struct Task {
+    due: NaiveDateTime,
+    priority: Priority,
+}
+
+/// Markers of the tasks due today, which are kept up to date by a background thread.
+#[derive(Clone)]
+pub struct TaskMarkers {
+    markers: Arc<Mutex<Vec<Marker>>>,
+}
+
+impl TaskMarkers {
+    /// Read the tasks once, failing if the source is unusable, then read them again every
+    /// `REFRESH_INTERVAL` in the background. Later failures keep the last markers read.
+    pub fn start(source: TaskSource) -> Result<TaskMarkers, String> {
+        let task_markers = TaskMarkers {
+            markers: Arc::new(Mutex::new(read_markers(&source)?)),
+        };
+        let markers = task_markers.markers.clone();
+        thread::spawn(move || loop {
+            thread::sleep(REFRESH_INTERVAL);
+            if let Ok(new_markers) = read_markers(&source) {
+                *markers.lock().unwrap() = new_markers
+            }
+        });
+        Ok(task_markers)
+    }
+
+    /// The markers of the tasks due today.
+    pub fn markers(&self) -> Vec<Marker> {
+        self.markers.lock().unwrap().clone()
+    }
+}
+
+fn read_markers(source: &TaskSource) -> Result<Vec<Marker>, String> {
+    let tasks = match source {
+        TaskSource::Taskwarrior => read_taskwarrior()?,
+        TaskSource::TodoTxt(path) => read_todo_txt(path)?,
+    };
+    Ok(markers_due(tasks, Local::today().naive_local()))
+}
+
+fn markers_due(tasks: Vec<Task>, day: NaiveDate) -> Vec<Marker> {
+    tasks
+        .into_iter()
+        .filter(|task| task.due.date() == day)
+        .map(|task| Marker {
+            time: task.due.time(),
+            color: task.priority.color(),
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct TaskwarriorTask {
+    due: Option<String>,
+    priority: Option<String>,
+}
+
+fn read_taskwarrior() -> Result<Vec<Task>, String> {
+    let output = Command::new("task")
+        .args(["status:pending", "export"])
+        .output()
+        .map_err(|error| format!("Unable to run `task export`: {}", error))?;
+    parse_taskwarrior(&output.stdout)
+}
+
+fn parse_taskwarrior(export: &[u8]) -> Result<Vec<Task>, String> {
+    let tasks: Vec<TaskwarriorTask> = serde_json::from_slice(export)
+        .map_err(|error| format!("Unable to parse the output of `task export`: {}", error))?;
+    Ok(tasks
+        .into_iter()
+        .filter_map(|task| {
+            // Taskwarrior reports dates in UTC, e.g. 20240115T093000Z
+            let due = Utc
+                .datetime_from_str(task.due.as_ref()?, "%Y%m%dT%H%M%SZ")
+                .ok()?;
+            Some(Task {
+                due: DateTime::<Local>::from(due).naive_local(),
+                priority: match task.priority.as_deref() {
+                    Some("H") => Priority::High,
+                    Some("M") => Priority::Medium,
+                    Some("L") => Priority::Low,
+                    _ => Priority::None,
+                },
+            })
+        })
+        .collect())
+}
+
+fn read_todo_txt(path: &Path) -> Result<Vec<Task>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("Unable to read {}: {}", path.display(), error))?;
+    Ok(parse_todo_txt(&content))
+}
+
+fn parse_todo_txt(content: &str) -> Vec<Task> {
+    content
+        .lines()
+        // Completed tasks start with "x "
+        .filter(|line| !line.starts_with("x "))
+        .filter_map(|line| {
+            // Tasks without a due time cannot be placed on the dial
+            let due = line
+                .split_whitespace()
+                .find_map(|word| word.strip_prefix("due:"))
+                .filter(|due| due.contains('T'))
+                .and_then(|due| parse_datetime(due).ok())?;
+            let priority = match line.get(0..4) {
+                Some("(A) ") => Priority::High,
+                Some("(B) ") => Priority::Medium,
+                Some(priority) if priority.starts_with('(') && priority.ends_with(") ") => {
+                    Priority::Low
+                }
+                _ => Priority::None,
+            };
+            Some(Task { due, priority })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd(2024, 1, 15)
+    }
+
+    fn described(markers: &[Marker]) -> Vec<(NaiveTime, String)> {
+        markers
+            .iter()
+            .map(|marker| (marker.time, marker.color.to_css_hex_string()))
+            .collect()
+    }
+
+    fn color(priority: Priority) -> String {
+        priority.color().to_css_hex_string()
+    }
+
+    #[test]
+    fn todo_txt_tasks_due_today() {
+        let content = "\
+(A) Call the bank due:2024-01-15T09:30
+(B) Water the plants due:2024-01-15T12:00
+(C) Read a book due:2024-01-15T18:45
+Pay the rent due:2024-01-15T20:00
+x 2024-01-14 Done already due:2024-01-15T10:00
+Tomorrow due:2024-01-16T09:00
+No time due:2024-01-15
+No due date at all
+";
+        assert_eq!(
+            described(&markers_due(parse_todo_txt(content), day())),
+            vec![
+                (NaiveTime::from_hms(9, 30, 0), color(Priority::High)),
+                (NaiveTime::from_hms(12, 0, 0), color(Priority::Medium)),
+                (NaiveTime::from_hms(18, 45, 0), color(Priority::Low)),
+                (NaiveTime::from_hms(20, 0, 0), color(Priority::None)),
+            ]
+        );
+    }
+
+    #[test]
+    fn taskwarrior_tasks_due_today() {
+        // Taskwarrior reports UTC, so pick times that are on the same local day
+        let due =
+            |hour| DateTime::<Local>::from(Utc.ymd(2024, 1, 15).and_hms(hour, 0, 0)).naive_local();
+        let export = br#"[
+            {"description": "High", "due": "20240115T100000Z", "priority": "H"},
+            {"description": "Medium", "due": "20240115T110000Z", "priority": "M"},
+            {"description": "Low", "due": "20240115T120000Z", "priority": "L"},
+            {"description": "None", "due": "20240115T130000Z"},
+            {"description": "No due date", "priority": "H"},
+            {"description": "Invalid due date", "due": "tomorrow"}
+        ]"#;
+        let today = due(12).date();
+        let expected: Vec<_> = [
+            (due(10), Priority::High),
+            (due(11), Priority::Medium),
+            (due(12), Priority::Low),
+            (due(13), Priority::None),
+        ]
+        .iter()
+        .filter(|(due, _)| due.date() == today)
+        .map(|&(due, priority)| (due.time(), color(priority)))
+        .collect();
+        assert_eq!(
+            described(&markers_due(parse_taskwarrior(export).unwrap(), today)),
+            expected
+        );
+    }
+
+    #[test]
+    fn taskwarrior_invalid_export() {
+        assert!(parse_taskwarrior(b"not json").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use colors_transform::{Color, Rgb};
+use std::process::Command;
+use std::str::FromStr;
+
+/// Color temperature of the screen, which the colors of the clock are shifted to match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorTemperature {
+    /// Ask redshift or gammastep for the current temperature.
+    Auto,
+    /// In kelvin.
+    Fixed(f32),
+}
+
+/// Parses `auto`, or a temperature in kelvin such as `3500K` or `3500`.
+impl FromStr for ColorTemperature {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<ColorTemperature, String> {
+        if text == "auto" {
+            return Ok(ColorTemperature::Auto);
+        }
+        text.trim_end_matches(['K', 'k'])
+            .parse()
+            .ok()
+            .filter(|kelvin| (1000.0..=25000.0).contains(kelvin))
+            .map(ColorTemperature::Fixed)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid color temperature '{}', expected auto or a temperature between 1000K and 25000K",
+                    text
+                )
+            })
+    }
+}
+
+impl ColorTemperature {
+    /// The temperature in kelvin, or `None` if it is `Auto` and neither redshift nor gammastep
+    /// is running.
+    pub fn kelvin(self) -> Option<f32> {
+        match self {
+            ColorTemperature::Fixed(kelvin) => Some(kelvin),
+            ColorTemperature::Auto => ["redshift", "gammastep"].iter().find_map(|program| {
+                let output = Command::new(program).arg("-p").output().ok()?;
+                parse_print_mode(&String::from_utf8_lossy(&output.stdout))
+            }),
+        }
+    }
+}
+
+/// Find the temperature in the output of `redshift -p` or `gammastep -p`,
+/// which has a line such as `Color temperature: 3500K`.
+fn parse_print_mode(output: &str) -> Option<f32> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.trim().eq_ignore_ascii_case("color temperature") {
+            value.trim().trim_end_matches('K').parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Shift `color` from daylight (6500K) to the given temperature, the same way redshift scales
+/// the gamma ramps of the screen.
+pub fn shift(color: Rgb, kelvin: f32) -> Rgb {
+    let (red, green, blue) = whitepoint(kelvin);
+    let (daylight_red, daylight_green, daylight_blue) = whitepoint(6500.0);
+    let scale = |channel: f32, factor: f32| (channel * factor).min(255.0);
+    Rgb::from(
+        scale(color.get_red(), red / daylight_red),
+        scale(color.get_green(), green / daylight_green),
+        scale(color.get_blue(), blue / daylight_blue),
+    )
+}
+
+/// The color of a black body at the given temperature, from 0 to 1 per channel.
+///
+/// Refer https://tannerhelland.com/2012/09/18/convert-temperature-rgb-algorithm-code.html
+fn whitepoint(kelvin: f32) -> (f32, f32, f32) {
+    let temperature = kelvin / 100.0;
+    let red = if temperature <= 66.0 {
+        255.0
+    } else {
+        329.69873 * (temperature - 60.0).powf(-0.13320476)
+    };
+    let green = if temperature <= 66.0 {
+        99.4708 * temperature.ln() - 161.11957
+    } else {
+        288.12216 * (temperature - 60.0).powf(-0.075514846)
+    };
+    let blue = if temperature >= 66.0 {
+        255.0
+    } else if temperature <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (temperature - 10.0).ln() - 305.0448
+    };
+    let clamp = |channel: f32| channel.clamp(0.0, 255.0) / 255.0;
+    (clamp(red), clamp(green), clamp(blue))
+}
@@ -1,72 +1,354 @@
+use crate::color::ColorMode;
+use crate::config::config_dir;
+
+use chrono::NaiveTime;
+use colors_transform::{Color, Rgb};
+use crossterm::style::Stylize;
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use Paint::{Gradient, Solid};
+
 #[derive(Clone, Copy)]
 pub struct Theme {
     pub name: &'static str,
-    pub hour: &'static str,
-    pub minute: &'static str,
-    pub second: &'static str,
+    pub hour: Paint,
+    pub minute: Paint,
+    pub second: Paint,
+    /// The 24-hour hand of `--gmt-hand`.
+    pub gmt: Paint,
+    /// Anything that shows progress, such as the ring of `--show-elapsed`.
+    pub secondary: Paint,
     pub clock_face: &'static str,
 }
 
-pub const THEMES: [Theme; 8] = [
+/// The color of a hand.
+#[derive(Clone, Copy)]
+pub enum Paint {
+    Solid(&'static str),
+    /// Fades from the first color at the center to the second color at the tip.
+    Gradient(&'static str, &'static str),
+}
+
+impl Paint {
+    /// The color at the center, also used for text and anything else that is not a hand.
+    pub fn color(self) -> Rgb {
+        match self {
+            Solid(color) | Gradient(color, _) => Rgb::from_hex_str(color).unwrap(),
+        }
+    }
+
+    /// The color at the tip, if it differs from the color at the center.
+    pub fn tip_color(self) -> Option<Rgb> {
+        match self {
+            Solid(_) => None,
+            Gradient(_, tip) => Some(Rgb::from_hex_str(tip).unwrap()),
+        }
+    }
+}
+
+pub const THEMES: [Theme; 9] = [
     // Nord themes, https://www.nordtheme.com/
     Theme {
         name: "nord-frost",
-        hour: "#5E81AC",
-        minute: "#81A1C1",
-        second: "#88C0D0",
+        hour: Solid("#5E81AC"),
+        minute: Solid("#81A1C1"),
+        second: Solid("#88C0D0"),
+        gmt: Solid("#B48EAD"),
+        secondary: Solid("#A3BE8C"),
         clock_face: "#8FBCBB",
     },
     Theme {
         name: "nord-aurora",
-        hour: "#BF616A",
-        minute: "#D08770",
-        second: "#EBCB8B",
+        hour: Solid("#BF616A"),
+        minute: Solid("#D08770"),
+        second: Solid("#EBCB8B"),
+        gmt: Solid("#A3BE8C"),
+        secondary: Solid("#88C0D0"),
         clock_face: "#B48EAD",
     },
     // Gruvbox. https://github.com/morhetz/gruvbox
     Theme {
         name: "gruvbox-light",
-        hour: "#928374",
-        minute: "#a89984",
-        second: "#bdae93",
+        hour: Solid("#928374"),
+        minute: Solid("#a89984"),
+        second: Solid("#bdae93"),
+        gmt: Solid("#d65d0e"),
+        secondary: Solid("#689d6a"),
         clock_face: "#d5c4a1",
     },
     Theme {
         name: "gruvbox-dark",
-        hour: "#d5c4a1",
-        minute: "#bdae93",
-        second: "#a89984",
+        hour: Solid("#d5c4a1"),
+        minute: Solid("#bdae93"),
+        second: Solid("#a89984"),
+        gmt: Solid("#fe8019"),
+        secondary: Solid("#8ec07c"),
         clock_face: "#928374",
     },
     // Monokai. https://gist.github.com/r-malon/8fc669332215c8028697a0bbfbfbb32a
     Theme {
         name: "monokai",
-        hour: "#66d9ef",
-        minute: "#ae81ff",
-        second: "#f92672",
+        hour: Solid("#66d9ef"),
+        minute: Solid("#ae81ff"),
+        second: Solid("#f92672"),
+        gmt: Solid("#fd971f"),
+        secondary: Solid("#e6db74"),
         clock_face: "#a6e22e",
     },
     // Lime. https://encycolorpedia.com/b3cd4f#:~:text=The%20hexadecimal%20color%20code%20%23b3cd4f,%25%20saturation%20and%2056%25%20lightness.
     Theme {
         name: "lime-light",
-        hour: "#8da729",
-        minute: "#99b436",
-        second: "#a6c043",
+        hour: Solid("#8da729"),
+        minute: Solid("#99b436"),
+        second: Solid("#a6c043"),
+        gmt: Solid("#e07b39"),
+        secondary: Solid("#2a9d8f"),
         clock_face: "#b3cd4f",
     },
     Theme {
         name: "lime-dark",
-        hour: "#dbf474",
-        minute: "#cde768",
-        second: "#c0da5b",
+        hour: Solid("#dbf474"),
+        minute: Solid("#cde768"),
+        second: Solid("#c0da5b"),
+        gmt: Solid("#f4a261"),
+        secondary: Solid("#90e0ef"),
         clock_face: "#b3cd4f",
     },
     // dim-neon. Contributed by https://github.com/GervinFung
     Theme {
         name: "dim-neon",
-        hour: "#67CBE7",
-        minute: "#E5C76B",
-        second: "#E06E6E",
+        hour: Solid("#67CBE7"),
+        minute: Solid("#E5C76B"),
+        second: Solid("#E06E6E"),
+        gmt: Solid("#C678DD"),
+        secondary: Solid("#98C379"),
         clock_face: "#00FA9A",
     },
+    // Sunset, showing off gradient hands
+    Theme {
+        name: "sunset",
+        hour: Gradient("#7209B7", "#F72585"),
+        minute: Gradient("#3A0CA3", "#4CC9F0"),
+        second: Gradient("#FF0000", "#FFFF00"),
+        gmt: Gradient("#FB5607", "#FFBE0B"),
+        secondary: Gradient("#FF006E", "#8338EC"),
+        clock_face: "#560BAD",
+    },
 ];
+
+/// Themes chosen by `--theme auto` for terminals with a light or a dark background.
+pub const LIGHT_THEME: &str = "gruvbox-light";
+pub const DARK_THEME: &str = "nord-frost";
+
+/// Whether text in `background` has to be dark to be readable, by its relative luminance.
+pub fn is_light(background: Rgb) -> bool {
+    let luminance = 0.2126 * background.get_red()
+        + 0.7152 * background.get_green()
+        + 0.0722 * background.get_blue();
+    luminance > 255.0 / 2.0
+}
+
+/// The index of the theme with the given name in `themes`.
+pub fn find_theme(themes: &[Theme], name: &str) -> Option<usize> {
+    themes.iter().position(|theme| theme.name == name)
+}
+
+/// Names further than this from every theme are not taken for a typo, see `similar_themes`.
+const MAX_TYPO_DISTANCE: usize = 2;
+
+/// The indices of the themes whose names are the fewest edits away from `name`, if that is few
+/// enough for `name` to be a typo of them.
+pub fn similar_themes(themes: &[Theme], name: &str) -> Vec<usize> {
+    let distances: Vec<usize> = themes
+        .iter()
+        .map(|theme| edit_distance(theme.name, name))
+        .collect();
+    match distances.iter().min() {
+        Some(&closest) if closest <= MAX_TYPO_DISTANCE => (0..themes.len())
+            .filter(|&index| distances[index] == closest)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// How many characters have to be inserted, deleted or substituted to turn `a` into `b`, known
+/// as the Levenshtein distance.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // Distances from the characters of `a` so far to each prefix of `b`
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b_char) in b.iter().enumerate() {
+            let substituted = diagonal + (a_char != b_char) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substituted.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// One line per theme for `--list-themes`, with the name followed by a swatch of the hour,
+/// minute and second hands and the clock face, in the colors of `color_mode`. A gradient is
+/// shown as its colors at the center and at the tip.
+pub fn list_themes(themes: &[Theme], color_mode: ColorMode) -> Vec<String> {
+    let width = themes
+        .iter()
+        .map(|theme| theme.name.len())
+        .max()
+        .unwrap_or(0);
+    themes
+        .iter()
+        .map(|theme| {
+            if color_mode == ColorMode::Monochrome {
+                return theme.name.to_string();
+            }
+            let block = |color: Rgb| "█".with(color_mode.to_crossterm_color(color)).to_string();
+            let paint = |paint: Paint| {
+                block(paint.color()) + &block(paint.tip_color().unwrap_or_else(|| paint.color()))
+            };
+            let face = Rgb::from_hex_str(theme.clock_face).unwrap();
+            format!(
+                "{:width$}  {} {} {} {}",
+                theme.name,
+                paint(theme.hour),
+                paint(theme.minute),
+                paint(theme.second),
+                block(face) + &block(face),
+                width = width
+            )
+        })
+        .collect()
+}
+
+/// Content of a theme file in `~/.config/analog-clock/themes`, named after the file.
+/// For example, `ocean.toml`:
+///
+/// ```toml
+/// hour = "#1B4965"
+/// minute = ["#5FA8D3", "#CAE9FF"]
+/// second = "#FF6B6B"
+/// gmt = "#FFD166"
+/// secondary = "#BEE9E8"
+/// clock-face = "#62B6CB"
+/// ```
+///
+/// Hands are either a single color, or the colors at the center and the tip of a gradient.
+/// The hand of `--gmt-hand` has the color of the second hand unless `gmt` is given, and so does
+/// `secondary` unless it is given.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ThemeFile {
+    hour: PaintFile,
+    minute: PaintFile,
+    second: PaintFile,
+    gmt: Option<PaintFile>,
+    secondary: Option<PaintFile>,
+    clock_face: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PaintFile {
+    Solid(String),
+    Gradient([String; 2]),
+}
+
+/// The built-in themes, followed by the themes in `~/.config/analog-clock/themes`.
+/// A user theme with the name of a built-in theme replaces it.
+pub fn load_themes() -> Result<Vec<Theme>, String> {
+    let mut themes = THEMES.to_vec();
+    let dir = match config_dir() {
+        Some(dir) => dir.join("themes"),
+        None => return Ok(themes),
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(themes),
+        Err(error) => {
+            return Err(format!(
+                "Unable to read theme directory {}: {}",
+                dir.display(),
+                error
+            ))
+        }
+    };
+    let mut paths = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .collect::<Vec<_>>();
+    // Cycling through the themes should not depend on the order of the file system
+    paths.sort();
+    for path in paths {
+        let theme = load_theme(&path)?;
+        match find_theme(&themes, theme.name) {
+            Some(index) => themes[index] = theme,
+            None => themes.push(theme),
+        }
+    }
+    Ok(themes)
+}
+
+fn load_theme(path: &Path) -> Result<Theme, String> {
+    let invalid = |error: String| format!("Invalid theme file {}: {}", path.display(), error);
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("Unable to read theme file {}: {}", path.display(), error))?;
+    let file: ThemeFile = toml::from_str(&content).map_err(|error| invalid(error.to_string()))?;
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| invalid("the file name is not valid UTF-8".to_string()))?;
+    // Themes are loaded once at startup and live as long as the program,
+    // so that user themes can share the `&'static str` colors of the built-in ones
+    let color = |color: &str| -> Result<&'static str, String> {
+        Rgb::from_hex_str(color).map_err(|_| invalid(format!("invalid color '{}'", color)))?;
+        Ok(Box::leak(color.to_string().into_boxed_str()))
+    };
+    let paint = |paint: &PaintFile| -> Result<Paint, String> {
+        Ok(match paint {
+            PaintFile::Solid(center) => Solid(color(center)?),
+            PaintFile::Gradient([center, tip]) => Gradient(color(center)?, color(tip)?),
+        })
+    };
+    Ok(Theme {
+        name: Box::leak(name.to_string().into_boxed_str()),
+        hour: paint(&file.hour)?,
+        minute: paint(&file.minute)?,
+        second: paint(&file.second)?,
+        gmt: paint(file.gmt.as_ref().unwrap_or(&file.second))?,
+        secondary: paint(file.secondary.as_ref().unwrap_or(&file.second))?,
+        clock_face: color(&file.clock_face)?,
+    })
+}
+
+/// Switches between two themes by the time of the day.
+#[derive(Clone, Copy)]
+pub struct ThemeSchedule {
+    pub day: usize,
+    pub night: usize,
+    pub night_from: NaiveTime,
+    /// Night may span midnight, in which case this is earlier than `night_from`.
+    pub night_to: NaiveTime,
+}
+
+impl ThemeSchedule {
+    /// The index of the theme to use at `time`.
+    pub fn theme_index(&self, time: NaiveTime) -> usize {
+        let night = if self.night_from <= self.night_to {
+            self.night_from <= time && time < self.night_to
+        } else {
+            self.night_from <= time || time < self.night_to
+        };
+        if night {
+            self.night
+        } else {
+            self.day
+        }
+    }
+}
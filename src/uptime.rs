@@ -0,0 +1,22 @@
+use std::fs;
+use std::time::Duration;
+
+/// Where Linux reports how long the machine has been running, in seconds.
+const UPTIME_FILE: &str = "/proc/uptime";
+
+/// How long this machine has been running, which fails on platforms other than Linux.
+pub fn uptime() -> Result<Duration, String> {
+    let unreadable = || {
+        format!(
+            "Unable to read how long this machine has been running from {}",
+            UPTIME_FILE
+        )
+    };
+    let content = fs::read_to_string(UPTIME_FILE).map_err(|_| unreadable())?;
+    let seconds: f64 = content
+        .split_whitespace()
+        .next()
+        .and_then(|seconds| seconds.parse().ok())
+        .ok_or_else(unreadable)?;
+    Ok(Duration::from_secs_f64(seconds.max(0.0)))
+}
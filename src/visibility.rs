@@ -0,0 +1,108 @@
+use crate::cli::parse_time;
+use crate::config::VisibilityRule;
+
+use chrono::NaiveTime;
+
+/// Parts of the clock that can be shown or hidden.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Toggle {
+    SecondHand,
+    HourLabels,
+    MinuteLabels,
+    Date,
+}
+
+impl Toggle {
+    const ALL: [Toggle; 4] = [
+        Toggle::SecondHand,
+        Toggle::HourLabels,
+        Toggle::MinuteLabels,
+        Toggle::Date,
+    ];
+
+    /// Name of this part in the `[[visibility]]` rules of the config file.
+    fn name(self) -> &'static str {
+        match self {
+            Toggle::SecondHand => "second-hand",
+            Toggle::HourLabels => "hour-labels",
+            Toggle::MinuteLabels => "minute-labels",
+            Toggle::Date => "date",
+        }
+    }
+}
+
+struct Rule {
+    toggle: Toggle,
+    visible: bool,
+    between: Option<(NaiveTime, NaiveTime)>,
+    radius_above: Option<f32>,
+    radius_below: Option<f32>,
+}
+
+impl Rule {
+    fn applies(&self, time: NaiveTime, radius: f32) -> bool {
+        let in_time = self.between.is_none_or(|(start, end)| {
+            if start <= end {
+                start <= time && time < end
+            } else {
+                start <= time || time < end
+            }
+        });
+        in_time
+            && self.radius_above.is_none_or(|above| radius > above)
+            && self.radius_below.is_none_or(|below| radius < below)
+    }
+}
+
+/// Rules from the config file that override whether parts of the clock are shown, evaluated
+/// on every frame.
+pub struct Visibility {
+    rules: Vec<Rule>,
+}
+
+impl Visibility {
+    pub fn new(rules: &[VisibilityRule]) -> Result<Visibility, String> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let toggle = Toggle::ALL
+                    .iter()
+                    .copied()
+                    .find(|toggle| toggle.name() == rule.element)
+                    .ok_or_else(|| {
+                        format!(
+                            "Unknown element '{}' in visibility rule, expected one of {}",
+                            rule.element,
+                            Toggle::ALL
+                                .iter()
+                                .map(|toggle| toggle.name())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })?;
+                let between = match &rule.between {
+                    Some([start, end]) => Some((parse_time(start)?, parse_time(end)?)),
+                    None => None,
+                };
+                Ok(Rule {
+                    toggle,
+                    visible: rule.visible,
+                    between,
+                    radius_above: rule.radius_above,
+                    radius_below: rule.radius_below,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Visibility { rules })
+    }
+
+    /// Whether `toggle` is visible at the given time and dial radius in rows, where the last
+    /// rule that applies wins, and `default` is used when none applies.
+    pub fn visible(&self, toggle: Toggle, default: bool, time: NaiveTime, radius: f32) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.toggle == toggle && rule.applies(time, radius))
+            .map_or(default, |rule| rule.visible)
+    }
+}
@@ -0,0 +1,54 @@
+use analog_clock_core::Region;
+use std::str::FromStr;
+
+/// Most clocks in either direction of `--wall`.
+const MAX_CLOCKS: usize = 16;
+
+/// A grid of clocks filling the screen, see `--wall`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wall {
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl Wall {
+    /// The area of each clock on a screen of the given size, row by row from the top left.
+    /// Every area has the same size, so that identical clocks only have to be drawn once.
+    pub fn tiles(&self, (width, height): (usize, usize)) -> Vec<Region> {
+        let (tile_width, tile_height) = (width / self.columns, height / self.rows);
+        (0..self.rows)
+            .flat_map(|row| {
+                (0..self.columns).map(move |column| Region {
+                    x: column * tile_width,
+                    y: row * tile_height,
+                    width: tile_width,
+                    height: tile_height,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses `COLUMNSxROWS`, such as `3x2`.
+impl FromStr for Wall {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Wall, String> {
+        let invalid = || {
+            format!(
+                "Invalid wall '{}', expected COLUMNSxROWS between 1x1 and {max}x{max}, such as 3x2",
+                text,
+                max = MAX_CLOCKS
+            )
+        };
+        let (columns, rows) = text.split_once('x').ok_or_else(invalid)?;
+        let parse = |count: &str| match count.parse() {
+            Ok(count) if (1..=MAX_CLOCKS).contains(&count) => Ok(count),
+            _ => Err(invalid()),
+        };
+        Ok(Wall {
+            columns: parse(columns)?,
+            rows: parse(rows)?,
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::{NaiveTime, Timelike};
+
+/// Letters of the word clock, where the words telling the time are lit, see `--face words`.
+/// Only English is supported.
+pub const LETTERS: [&str; 10] = [
+    "ITLISASAMPM",
+    "ACQUARTERDC",
+    "TWENTYFIVEX",
+    "HALFSTENFTO",
+    "PASTERUNINE",
+    "ONESIXTHREE",
+    "FOURFIVETWO",
+    "EIGHTELEVEN",
+    "SEVENTWELVE",
+    "TENSEOCLOCK",
+];
+
+/// Letters in a row of `LETTERS`.
+pub const LETTERS_WIDTH: usize = 11;
+
+/// A word of `LETTERS`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Word {
+    pub row: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+const fn word(row: usize, column: usize, length: usize) -> Word {
+    Word {
+        row,
+        column,
+        length,
+    }
+}
+
+const IT: Word = word(0, 0, 2);
+const IS: Word = word(0, 3, 2);
+const QUARTER: Word = word(1, 2, 7);
+const TWENTY: Word = word(2, 0, 6);
+const FIVE_MINUTES: Word = word(2, 6, 4);
+const HALF: Word = word(3, 0, 4);
+const TEN_MINUTES: Word = word(3, 5, 3);
+const TO: Word = word(3, 9, 2);
+const PAST: Word = word(4, 0, 4);
+const O_CLOCK: Word = word(9, 5, 6);
+
+/// From one to twelve.
+const HOURS: [Word; 12] = [
+    word(5, 0, 3),
+    word(6, 8, 3),
+    word(5, 6, 5),
+    word(6, 0, 4),
+    word(6, 4, 4),
+    word(5, 3, 3),
+    word(8, 0, 5),
+    word(7, 0, 5),
+    word(4, 7, 4),
+    word(9, 0, 3),
+    word(7, 5, 6),
+    word(8, 5, 6),
+];
+
+/// The words telling `time`, rounded down to five minutes, such as IT IS QUARTER PAST THREE.
+pub struct Phrase {
+    /// IT IS.
+    pub prefix: [Word; 2],
+    /// Such as QUARTER PAST or O'CLOCK.
+    pub minutes: Vec<Word>,
+    pub hour: Word,
+}
+
+impl Phrase {
+    pub fn at(time: NaiveTime) -> Phrase {
+        let minute = time.minute() / 5 * 5;
+        let minutes = match minute {
+            0 => vec![O_CLOCK],
+            5 | 55 => vec![FIVE_MINUTES],
+            10 | 50 => vec![TEN_MINUTES],
+            15 | 45 => vec![QUARTER],
+            20 | 40 => vec![TWENTY],
+            25 | 35 => vec![TWENTY, FIVE_MINUTES],
+            _ => vec![HALF],
+        };
+        // From 35 minutes on, the time is told until the next hour
+        let (minutes, hour) = match minute {
+            0 => (minutes, time.hour()),
+            5..=30 => ([minutes, vec![PAST]].concat(), time.hour()),
+            _ => ([minutes, vec![TO]].concat(), time.hour() + 1),
+        };
+        Phrase {
+            prefix: [IT, IS],
+            minutes,
+            // Midnight and noon are TWELVE
+            hour: HOURS[(hour as usize + 11) % 12],
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use crate::cli::parse_utc_offset;
+
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime};
+use std::convert::TryInto;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Where the time zone database is read from, unless `TZDIR` is set.
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+/// A time zone, either a fixed offset from UTC such as `+5:30`, or a name in the IANA time zone
+/// database such as `Europe/London`, which is read from the TZif files of the system, see
+/// tzfile(5).
+#[derive(Debug)]
+pub struct Zone {
+    /// UTC timestamps when the offset changes, with the offset from then on, in seconds east of
+    /// UTC.
+    transitions: Vec<(i64, i32)>,
+    /// The offset before the first transition, or always if there is none.
+    initial: i32,
+    /// How the offset changes after the last transition.
+    rule: Option<Rule>,
+}
+
+impl Zone {
+    /// The offset from UTC at the given time in UTC.
+    pub fn offset_at(&self, utc: NaiveDateTime) -> FixedOffset {
+        let timestamp = utc.timestamp();
+        if let Some(rule) = &self.rule {
+            if self
+                .transitions
+                .last()
+                .is_none_or(|&(at, _)| timestamp >= at)
+            {
+                return FixedOffset::east(rule.offset_at(utc));
+            }
+        }
+        let offset = self
+            .transitions
+            .iter()
+            .rev()
+            .find(|(at, _)| *at <= timestamp)
+            .map_or(self.initial, |&(_, offset)| offset);
+        FixedOffset::east(offset)
+    }
+}
+
+impl FromStr for Zone {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Zone, String> {
+        if text.starts_with(['+', '-']) {
+            return Ok(Zone {
+                transitions: Vec::new(),
+                initial: parse_utc_offset(text)?.local_minus_utc(),
+                rule: None,
+            });
+        }
+        let unknown = || {
+            format!(
+                "Unknown time zone '{}', expected a name such as Europe/London or an offset such as +9",
+                text
+            )
+        };
+        if text.is_empty() || text.split('/').any(|part| part.is_empty() || part == "..") {
+            return Err(unknown());
+        }
+        let path = env::var_os("TZDIR")
+            .map_or_else(|| PathBuf::from(ZONEINFO_DIR), PathBuf::from)
+            .join(text);
+        let data = fs::read(&path).map_err(|_| unknown())?;
+        parse_tzif(&data).ok_or_else(|| format!("Invalid time zone file {}", path.display()))
+    }
+}
+
+/// Parse a TZif file, using the 64-bit data of version 2 and later, and the rule in its footer.
+///
+/// Refer https://www.rfc-editor.org/rfc/rfc8536
+fn parse_tzif(mut data: &[u8]) -> Option<Zone> {
+    let (version, counts) = parse_header(&mut data)?;
+    let (time_size, counts) = if version >= b'2' {
+        // Skip the 32-bit data of version 1, which is followed by a header of its own
+        take(&mut data, counts.data_size(4))?;
+        (8, parse_header(&mut data)?.1)
+    } else {
+        (4, counts)
+    };
+    let times = take(&mut data, counts.transitions * time_size)?;
+    let indices = take(&mut data, counts.transitions)?;
+    let types = take(&mut data, counts.types * 6)?;
+    take(
+        &mut data,
+        counts.abbreviation_bytes
+            + counts.leap_seconds * (time_size + 4)
+            + counts.standard_indicators
+            + counts.utc_indicators,
+    )?;
+
+    let offsets: Vec<i32> = types
+        .chunks(6)
+        .map(|entry| i32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]))
+        .collect();
+    let transitions = times
+        .chunks(time_size)
+        .zip(indices)
+        .map(|(time, &index)| {
+            let at = match *time {
+                [a, b, c, d] => i32::from_be_bytes([a, b, c, d]) as i64,
+                _ => i64::from_be_bytes(time.try_into().ok()?),
+            };
+            Some((at, *offsets.get(index as usize)?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let rule = if version >= b'2' {
+        std::str::from_utf8(data)
+            .ok()
+            .and_then(|footer| parse_rule(footer.trim()))
+    } else {
+        None
+    };
+    Some(Zone {
+        transitions,
+        initial: *offsets.first()?,
+        rule,
+    })
+}
+
+/// How many of each kind of record a TZif file has.
+struct Counts {
+    utc_indicators: usize,
+    standard_indicators: usize,
+    leap_seconds: usize,
+    transitions: usize,
+    types: usize,
+    abbreviation_bytes: usize,
+}
+
+impl Counts {
+    /// Bytes of the data that follows the header, with times of `time_size` bytes.
+    fn data_size(&self, time_size: usize) -> usize {
+        self.transitions * (time_size + 1)
+            + self.types * 6
+            + self.abbreviation_bytes
+            + self.leap_seconds * (time_size + 4)
+            + self.standard_indicators
+            + self.utc_indicators
+    }
+}
+
+/// Parse the magic number, the version and the counts.
+fn parse_header(data: &mut &[u8]) -> Option<(u8, Counts)> {
+    let header = take(data, 44)?;
+    if &header[..4] != b"TZif" {
+        return None;
+    }
+    let count = |index: usize| {
+        let start = 20 + index * 4;
+        u32::from_be_bytes(header[start..start + 4].try_into().unwrap()) as usize
+    };
+    Some((
+        header[4],
+        Counts {
+            utc_indicators: count(0),
+            standard_indicators: count(1),
+            leap_seconds: count(2),
+            transitions: count(3),
+            types: count(4),
+            abbreviation_bytes: count(5),
+        },
+    ))
+}
+
+/// Split off the first `length` bytes of `data`.
+fn take<'a>(data: &mut &'a [u8], length: usize) -> Option<&'a [u8]> {
+    if data.len() < length {
+        return None;
+    }
+    let (head, tail) = data.split_at(length);
+    *data = tail;
+    Some(head)
+}
+
+/// The offsets of a POSIX TZ string such as `GMT0BST,M3.5.0/1,M10.5.0`, in seconds east of UTC.
+#[derive(Debug)]
+struct Rule {
+    standard: i32,
+    /// The offset during daylight saving time, with when it starts and ends.
+    daylight: Option<(i32, Transition, Transition)>,
+}
+
+impl Rule {
+    fn offset_at(&self, utc: NaiveDateTime) -> i32 {
+        let (daylight, start, end) = match &self.daylight {
+            Some(daylight) => daylight,
+            None => return self.standard,
+        };
+        // Transitions are in the local time that is in effect before them
+        let start = start.at(utc.year()) - Duration::seconds(self.standard as i64);
+        let end = end.at(utc.year()) - Duration::seconds(*daylight as i64);
+        let in_daylight = if start < end {
+            start <= utc && utc < end
+        } else {
+            // Daylight saving time spans the new year in the southern hemisphere
+            utc >= start || utc < end
+        };
+        if in_daylight {
+            *daylight
+        } else {
+            self.standard
+        }
+    }
+}
+
+/// A day written as `Mm.w.d`, the `d`th day of the week (0 is Sunday) of the `w`th week of month
+/// `m`, where week 5 is the last, at a local time in seconds after midnight.
+#[derive(Debug)]
+struct Transition {
+    month: u32,
+    week: u32,
+    weekday: u32,
+    time: i32,
+}
+
+impl Transition {
+    fn at(&self, year: i32) -> NaiveDateTime {
+        let first_weekday = NaiveDate::from_ymd(year, self.month, 1)
+            .weekday()
+            .num_days_from_sunday();
+        let mut day = 1 + (self.weekday + 7 - first_weekday) % 7 + (self.week - 1) * 7;
+        // The last week is whichever has this day of the week last
+        let date = loop {
+            match NaiveDate::from_ymd_opt(year, self.month, day) {
+                Some(date) => break date,
+                None => day -= 7,
+            }
+        };
+        date.and_hms(0, 0, 0) + Duration::seconds(self.time as i64)
+    }
+}
+
+/// Parse a POSIX TZ string, where offsets are west of UTC. Only transitions written as `Mm.w.d`
+/// are supported, which is what the time zone database uses.
+fn parse_rule(text: &str) -> Option<Rule> {
+    let rest = skip_name(text)?;
+    let (standard, rest) = parse_offset(rest)?;
+    let standard = -standard;
+    if rest.is_empty() {
+        return Some(Rule {
+            standard,
+            daylight: None,
+        });
+    }
+    let rest = skip_name(rest)?;
+    let (daylight, rest) = if rest.starts_with(',') {
+        (standard + 3600, rest)
+    } else {
+        let (daylight, rest) = parse_offset(rest)?;
+        (-daylight, rest)
+    };
+    let (start, end) = rest.strip_prefix(',')?.split_once(',')?;
+    Some(Rule {
+        standard,
+        daylight: Some((daylight, parse_transition(start)?, parse_transition(end)?)),
+    })
+}
+
+/// Skip the abbreviation of a zone, such as `GMT` or `<+0530>`.
+fn skip_name(text: &str) -> Option<&str> {
+    let rest = match text.strip_prefix('<') {
+        Some(quoted) => &quoted[quoted.find('>')? + 1..],
+        None => text.trim_start_matches(|character: char| character.is_ascii_alphabetic()),
+    };
+    if rest.len() < text.len() {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// Parse `[+|-]hh[:mm[:ss]]` into seconds, with the text after it.
+fn parse_offset(text: &str) -> Option<(i32, &str)> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (-1, unsigned),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let end = unsigned
+        .find(|character: char| !character.is_ascii_digit() && character != ':')
+        .unwrap_or(unsigned.len());
+    let mut seconds = 0;
+    for (index, part) in unsigned[..end].split(':').enumerate() {
+        let unit = [3600, 60, 1].get(index)?;
+        seconds += part.parse::<i32>().ok()? * unit;
+    }
+    Some((sign * seconds, &unsigned[end..]))
+}
+
+/// Parse `Mm.w.d[/time]`, where the time is 02:00 by default.
+fn parse_transition(text: &str) -> Option<Transition> {
+    let (date, time) = match text.split_once('/') {
+        Some((date, time)) => {
+            let (time, rest) = parse_offset(time)?;
+            if !rest.is_empty() {
+                return None;
+            }
+            (date, time)
+        }
+        None => (text, 2 * 3600),
+    };
+    let mut fields = date.strip_prefix('M')?.split('.');
+    let mut field = || fields.next()?.parse::<u32>().ok();
+    let (month, week, weekday) = (field()?, field()?, field()?);
+    if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+        return None;
+    }
+    Some(Transition {
+        month,
+        week,
+        weekday,
+        time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    /// A TZif file whose `transitions` switch to the type of the given index of `offsets`, with
+    /// `footer` after the 64-bit data, or only 32-bit data if there is no footer.
+    fn tzif(transitions: &[(i64, u8)], offsets: &[i32], footer: Option<&str>) -> Vec<u8> {
+        let abbreviations = b"ZZZ\0";
+        let data = |time_size: usize| {
+            let mut data = vec![];
+            data.extend(b"TZif");
+            data.push(if footer.is_some() { b'2' } else { 0 });
+            data.extend([0; 15]);
+            for count in [
+                0,
+                0,
+                0,
+                transitions.len(),
+                offsets.len(),
+                abbreviations.len(),
+            ] {
+                data.extend((count as u32).to_be_bytes())
+            }
+            for (at, _) in transitions {
+                match time_size {
+                    4 => data.extend((*at as i32).to_be_bytes()),
+                    _ => data.extend(at.to_be_bytes()),
+                }
+            }
+            data.extend(transitions.iter().map(|(_, index)| index));
+            for offset in offsets {
+                data.extend(offset.to_be_bytes());
+                data.extend([0, 0])
+            }
+            data.extend(abbreviations);
+            data
+        };
+        let mut file = data(4);
+        if let Some(footer) = footer {
+            file.extend(data(8));
+            file.extend(format!("\n{}\n", footer).bytes())
+        }
+        file
+    }
+
+    #[test]
+    fn parses_version_1_files() {
+        let zone = parse_tzif(&tzif(&[(1_000_000, 1)], &[-18000, -14400], None)).unwrap();
+        assert_eq!(
+            zone.offset_at(utc("1970-01-01 00:00:00")).local_minus_utc(),
+            -18000
+        );
+        assert_eq!(
+            zone.offset_at(utc("2024-07-01 00:00:00")).local_minus_utc(),
+            -14400
+        );
+    }
+
+    #[test]
+    fn parses_version_2_files_with_a_footer() {
+        let file = tzif(
+            &[(-1_000_000, 1), (1_000_000, 0)],
+            &[3600, 1800],
+            Some("CET-1CEST,M3.5.0,M10.5.0/3"),
+        );
+        let zone = parse_tzif(&file).unwrap();
+        assert_eq!(
+            zone.offset_at(utc("1969-01-01 00:00:00")).local_minus_utc(),
+            3600
+        );
+        assert_eq!(
+            zone.offset_at(utc("1970-01-01 00:00:00")).local_minus_utc(),
+            1800
+        );
+        // After the last transition, the footer applies
+        assert_eq!(
+            zone.offset_at(utc("2024-01-15 12:00:00")).local_minus_utc(),
+            3600
+        );
+        assert_eq!(
+            zone.offset_at(utc("2024-07-15 12:00:00")).local_minus_utc(),
+            7200
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let file = tzif(&[(1_000_000, 1)], &[0, 3600], Some("UTC0"));
+        assert!(parse_tzif(&file[..file.len() / 2]).is_none());
+        assert!(parse_tzif(b"TZif").is_none());
+        assert!(parse_tzif(&[b'X'; 44]).is_none());
+    }
+
+    #[test]
+    fn fixed_offsets_need_no_file() {
+        let zone: Zone = "+5:30".parse().unwrap();
+        assert_eq!(
+            zone.offset_at(utc("2024-01-15 12:00:00")).local_minus_utc(),
+            19800
+        );
+        assert!("Not/../A/Zone".parse::<Zone>().is_err());
+    }
+
+    #[test]
+    fn week_5_is_the_last_week_of_the_month() {
+        let last_sunday = parse_transition("M10.5.0").unwrap();
+        // October 2024 has four Sundays, October 2021 has five
+        assert_eq!(last_sunday.at(2024), utc("2024-10-27 02:00:00"));
+        assert_eq!(last_sunday.at(2021), utc("2021-10-31 02:00:00"));
+        let first_sunday = parse_transition("M4.1.0/3").unwrap();
+        assert_eq!(first_sunday.at(2024), utc("2024-04-07 03:00:00"));
+        assert!(parse_transition("M13.1.0").is_none());
+        assert!(parse_transition("J60").is_none());
+    }
+
+    #[test]
+    fn daylight_saving_time_in_the_northern_hemisphere() {
+        let london = parse_rule("GMT0BST,M3.5.0/1,M10.5.0").unwrap();
+        // Starts at 01:00 GMT, and ends at 02:00 BST, which is 01:00 GMT
+        assert_eq!(london.offset_at(utc("2024-03-31 00:59:59")), 0);
+        assert_eq!(london.offset_at(utc("2024-03-31 01:00:00")), 3600);
+        assert_eq!(london.offset_at(utc("2024-10-27 00:59:59")), 3600);
+        assert_eq!(london.offset_at(utc("2024-10-27 01:00:00")), 0);
+    }
+
+    #[test]
+    fn daylight_saving_time_that_spans_the_new_year() {
+        let sydney = parse_rule("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(sydney.offset_at(utc("2024-01-01 00:00:00")), 11 * 3600);
+        assert_eq!(sydney.offset_at(utc("2024-07-01 00:00:00")), 10 * 3600);
+        assert_eq!(sydney.offset_at(utc("2024-12-31 23:59:59")), 11 * 3600);
+        // Ends at 03:00 AEDT on 7 April, and starts at 02:00 AEST on 6 October
+        assert_eq!(sydney.offset_at(utc("2024-04-06 15:59:59")), 11 * 3600);
+        assert_eq!(sydney.offset_at(utc("2024-04-06 16:00:00")), 10 * 3600);
+        assert_eq!(sydney.offset_at(utc("2024-10-05 15:59:59")), 10 * 3600);
+        assert_eq!(sydney.offset_at(utc("2024-10-05 16:00:00")), 11 * 3600);
+    }
+
+    #[test]
+    fn offsets_with_minutes() {
+        let india = parse_rule("IST-5:30").unwrap();
+        assert_eq!(india.offset_at(utc("2024-07-01 00:00:00")), 19800);
+        let kathmandu = parse_rule("<+0545>-5:45").unwrap();
+        assert_eq!(kathmandu.offset_at(utc("2024-07-01 00:00:00")), 20700);
+        let newfoundland = parse_rule("NST3:30NDT,M3.2.0,M11.1.0").unwrap();
+        assert_eq!(newfoundland.offset_at(utc("2024-01-15 12:00:00")), -12600);
+        assert_eq!(newfoundland.offset_at(utc("2024-07-15 12:00:00")), -9000);
+        assert!(parse_rule("<+0545").is_none());
+    }
+}
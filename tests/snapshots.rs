@@ -0,0 +1,284 @@
+//! Compare frames printed by `--once` with the snapshots in `tests/snapshots`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to write the snapshots again after an intended change.
+
+use std::{
+    env, fs,
+    path::Path,
+    process::{self, Command},
+};
+
+/// The clock, run as in every test. stdout is not a terminal, so every frame is drawn on an
+/// 80x24 screen, and there is no config file, user theme or saved state under
+/// `tests/analog-clock`.
+fn clock() -> Command {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut command = Command::new(env!("CARGO_BIN_EXE_analog-clock"));
+    command
+        .env("XDG_CONFIG_HOME", manifest_dir.join("tests"))
+        .env("XDG_STATE_HOME", manifest_dir.join("tests"))
+        .env("LANG", "en_US.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("NO_COLOR");
+    command
+}
+
+fn assert_snapshot(name: &str, args: &[&str]) {
+    let output = clock()
+        .arg("--once")
+        .args(args)
+        .output()
+        .expect("Unable to run analog-clock");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual = String::from_utf8(output.stdout).expect("Frame is not UTF-8");
+    compare_snapshot(name, &actual)
+}
+
+/// Like `assert_snapshot`, but for the single frame that `--output-frames` writes, as text.
+fn assert_frame_snapshot(name: &str, args: &[&str]) {
+    let dir = env::temp_dir().join(format!("analog-clock-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let output = clock()
+        .arg("--output-frames")
+        .arg(&dir)
+        .args(["--format", "ansi", "--frames", "1"])
+        .args(args)
+        .output()
+        .expect("Unable to run analog-clock");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual = fs::read_to_string(dir.join("frame-000001.ansi"));
+    let _ = fs::remove_dir_all(&dir);
+    compare_snapshot(name, &actual.expect("No frame was written"))
+}
+
+fn compare_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).expect("Unable to write snapshot");
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing snapshot {}, run with UPDATE_SNAPSHOTS=1 to write it",
+            path.display()
+        )
+    });
+    assert!(
+        expected == actual,
+        "Frame differs from {}:\n{}",
+        path.display(),
+        actual
+    );
+}
+
+#[test]
+fn ten_past_ten() {
+    assert_snapshot("ten_past_ten", &["--time", "10:09:37", "--no-color"]);
+}
+
+#[test]
+fn three_o_clock_with_minute_labels() {
+    assert_snapshot(
+        "three_o_clock_with_minute_labels",
+        &["--time", "03:00:00", "--show-minute-labels", "--no-color"],
+    );
+}
+
+#[test]
+fn second_tail_and_center_cap() {
+    assert_snapshot(
+        "second_tail_and_center_cap",
+        &[
+            "--time",
+            "18:42:05",
+            "--second-tail",
+            "--center-cap",
+            "--no-color",
+        ],
+    );
+}
+
+#[test]
+fn binary_face() {
+    assert_snapshot(
+        "binary_face",
+        &["--time", "21:47:12", "--face", "binary", "--no-color"],
+    );
+}
+
+#[test]
+fn digital_face() {
+    assert_snapshot(
+        "digital_face",
+        &["--time", "21:47:12", "--face", "digital", "--no-color"],
+    );
+}
+
+#[test]
+fn words_face() {
+    assert_snapshot(
+        "words_face",
+        &["--time", "21:47:12", "--face", "words", "--no-color"],
+    );
+}
+
+#[test]
+fn themes() {
+    for theme in ["nord-frost", "gruvbox-light", "monokai", "sunset"] {
+        assert_snapshot(
+            &format!("theme_{}", theme),
+            &[
+                "--time",
+                "10:09:37",
+                "--theme",
+                theme,
+                "--color-mode",
+                "truecolor",
+                "--print-hash",
+            ],
+        );
+    }
+}
+
+#[test]
+fn dial_shapes() {
+    for shape in ["square", "octagon"] {
+        assert_snapshot(
+            &format!("dial_shape_{}", shape),
+            &["--time", "10:09:37", "--dial-shape", shape, "--no-color"],
+        );
+    }
+}
+
+#[test]
+fn markers() {
+    for markers in ["dots", "batons", "double-12"] {
+        assert_snapshot(
+            &format!("markers_{}", markers),
+            &["--time", "10:09:37", "--markers", markers, "--no-color"],
+        );
+    }
+}
+
+#[test]
+fn ascii() {
+    assert_snapshot(
+        "ascii",
+        &["--time", "04:42:20", "--ascii", "--show-minute-labels"],
+    );
+}
+
+#[test]
+fn supersample() {
+    assert_snapshot(
+        "supersample",
+        &["--time", "10:09:37", "--supersample", "3", "--no-color"],
+    );
+}
+
+#[test]
+fn status_line() {
+    assert_snapshot(
+        "status_line",
+        &["--time", "10:09:37", "--status-line", "--no-color"],
+    );
+}
+
+#[test]
+fn shadow() {
+    assert_snapshot("shadow", &["--time", "10:09:37", "--shadow", "--no-color"]);
+}
+
+#[test]
+fn numerals() {
+    for numerals in ["roman", "chinese"] {
+        assert_snapshot(
+            &format!("numerals_{}", numerals),
+            &["--time", "10:09:37", "--numerals", numerals, "--no-color"],
+        );
+    }
+}
+
+#[test]
+fn hand_styles() {
+    for style in ["arrow", "tapered"] {
+        assert_snapshot(
+            &format!("hand_style_{}", style),
+            &["--time", "10:09:37", "--hand-style", style, "--no-color"],
+        );
+    }
+}
+
+#[test]
+fn blink_center() {
+    // The center is bright on even seconds and dim on odd ones, which only differ in color
+    for (name, time) in [("even", "10:09:38"), ("odd", "10:09:39")] {
+        assert_snapshot(
+            &format!("blink_center_{}", name),
+            &[
+                "--time",
+                time,
+                "--blink-center",
+                "--hide-second-hand",
+                "--color-mode",
+                "256",
+            ],
+        );
+    }
+}
+
+#[test]
+fn show_elapsed() {
+    // The session has only just started, so the ring is empty but for where it starts
+    assert_snapshot(
+        "show_elapsed",
+        &[
+            "--time",
+            "10:09:37",
+            "--show-elapsed",
+            "session",
+            "--color-mode",
+            "256",
+        ],
+    );
+}
+
+#[test]
+fn face_image() {
+    assert_snapshot(
+        "face_image",
+        &[
+            "--time",
+            "10:09:37",
+            "--face-image",
+            "tests/face.png",
+            "--no-color",
+        ],
+    );
+}
+
+#[test]
+fn output_frames() {
+    assert_frame_snapshot(
+        "output_frames",
+        &[
+            "--time",
+            "07:21:45",
+            "--output-size",
+            "60x20",
+            "--color-mode",
+            "256",
+        ],
+    );
+}